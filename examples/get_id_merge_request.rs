@@ -57,8 +57,8 @@ fn run() -> Result<()> {
     let gl = GitLab::new(&hostname, &token).chain_err(|| "failure to create GitLab instance")?;
     // let gl = GitLab::new(&hostname, &token)
     //     .chain_err(|| "failure to create GitLab instance")?
-    //     .scheme("http").port(80);
-    // let gl = gl.scheme("http").port(80);
+    //     .scheme("http")?.port(80)?;
+    // let gl = gl.scheme("http")?.port(80)?;
 
     let matches = clap::App::new("get_id_merge_request")
         .version("1.0")
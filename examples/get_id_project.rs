@@ -56,8 +56,8 @@ fn run() -> Result<()> {
     let gl = GitLab::new(&hostname, &token).chain_err(|| "failure to create GitLab instance")?;
     // let gl = GitLab::new(&hostname, &token)
     //     .chain_err(|| "failure to create GitLab instance")?
-    //     .scheme("http").port(80);
-    // let gl = gl.scheme("http").port(80);
+    //     .scheme("http")?.port(80)?;
+    // let gl = gl.scheme("http")?.port(80)?;
 
     let matches = clap::App::new("get_id_project")
         .version("1.0")
@@ -53,8 +53,8 @@ fn run() -> Result<()> {
         gitlab::GitLab::new(&hostname, &token).chain_err(|| "failure to create GitLab instance")?;
     // let mut gl = gitlab::GitLab::new(&hostname, &token)
     //     .chain_err(|| "failure to create GitLab instance")?
-    //     .scheme("http").port(80);
-    // gl = gl.scheme("http").port(80);
+    //     .scheme("http")?.port(80)?;
+    // gl = gl.scheme("http")?.port(80)?;
 
     println!("gl: {:?}", gl);
 
@@ -57,18 +57,18 @@ fn run() -> Result<()> {
     let gl = GitLab::new(&hostname, &token).chain_err(|| "failure to create GitLab instance")?;
     // let gl = GitLab::new(&hostname, &token)
     //     .chain_err(|| "failure to create GitLab instance")?
-    //     .scheme("http").port(80);
-    // let gl = gl.scheme("http").port(80);
+    //     .scheme("http")?.port(80)?;
+    // let gl = gl.scheme("http")?.port(80)?;
 
     let issues = gl.issues().list().chain_err(|| "cannot get issues")?;
     println!("issues: {:?}", issues);
 
     let opened_issues =
-        gl.issues().state(issues::State::Opened).list().chain_err(|| "cannot get issues")?;
+        gl.issues().state(issues::ListingState::Opened).list().chain_err(|| "cannot get issues")?;
     println!("opened_issues: {:?}", opened_issues);
 
     let closed_issues =
-        gl.issues().state(issues::State::Closed).list().chain_err(|| "cannot get issues")?;
+        gl.issues().state(issues::ListingState::Closed).list().chain_err(|| "cannot get issues")?;
     println!("closed_issues: {:?}", closed_issues);
 
     let issue = gl.issues().single(142, 739).list().chain_err(|| "cannot get issues")?;
@@ -76,14 +76,14 @@ fn run() -> Result<()> {
 
     let group_issues = gl.issues()
         .group(21)
-        .state(issues::State::Closed)
+        .state(issues::ListingState::Closed)
         .list()
         .chain_err(|| "cannot get issues")?;
     println!("group_issues: {:?}", group_issues);
 
     let project_issues = gl.issues()
         .project(142)
-        .state(issues::State::Opened)
+        .state(issues::ListingState::Opened)
         .list()
         .chain_err(|| "cannot get issues")?;
     println!("project_issues: {:?}", project_issues);
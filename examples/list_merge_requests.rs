@@ -55,8 +55,8 @@ fn run() -> Result<()> {
     let gl = GitLab::new(&hostname, &token).chain_err(|| "failure to create GitLab instance")?;
     // let gl = GitLab::new(&hostname, &token)
     //     .chain_err(|| "failure to create GitLab instance")?
-    //     .scheme("http").port(80);
-    // let gl = gl.scheme("http").port(80);
+    //     .scheme("http")?.port(80)?;
+    // let gl = gl.scheme("http")?.port(80)?;
 
     let project_id = 142;
     let merge_requests_ids = vec![409, 410];
@@ -57,9 +57,9 @@ fn run() -> Result<()> {
     let gl = GitLab::new(&hostname, &token).chain_err(|| "failure to create GitLab instance")?;
     // let gl = GitLab::new(&hostname, &token)
     //     .chain_err(|| "failure to create GitLab instance")?
-    //     .scheme("http")
-    //     .port(80);
-    // let gl = gl.scheme("http").port(80);
+    //     .scheme("http")?
+    //     .port(80)?;
+    // let gl = gl.scheme("http")?.port(80)?;
 
     let projects = gl.projects().list().chain_err(|| "cannot get projects")?;
     println!("projects: {:?}", projects);
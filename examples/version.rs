@@ -53,8 +53,8 @@ fn run() -> Result<()> {
     };
 
     let gl = GitLab::new(&hostname, &token).chain_err(|| "failure to create GitLab instance")?;
-    // let gl = GitLab::new(&hostname, &token).scheme("http").port(80);
-    // let gl = gl.scheme("http").port(80);
+    // let gl = GitLab::new(&hostname, &token).scheme("http")?.port(80)?;
+    // let gl = gl.scheme("http")?.port(80)?;
     let version = gl.version().chain_err(|| "cannot get version")?;
 
     println!("version: {:?}", version);
@@ -0,0 +1,314 @@
+//! Dates and times returned by GitLab
+//!
+//! GitLab sends them in the ISO 8601 format, with milliseconds and a time zone offset, for
+//! example `2017-04-29T08:46:00.000Z`.
+//!
+//! With the `chrono` feature, they are parsed into `chrono::DateTime<chrono::Utc>`. Otherwise,
+//! they are kept as the strings sent by GitLab. Dates without a time, like the due dates
+//! (`2017-05-01`), are then taken at midnight UTC.
+//!
+//! The fields of type `DateTime` use this module's functions to be (de)serialized, the optional
+//! ones the `option` module's, and the optional dates without a time the `option_date` module's:
+//!
+//! ```text
+//! #[serde(with = "::datetime")]
+//! pub created_at: ::datetime::DateTime,
+//! #[serde(default, with = "::datetime::option")]
+//! pub updated_at: Option<::datetime::DateTime>,
+//! #[serde(default, with = "::datetime::option_date")]
+//! pub due_date: Option<::datetime::DateTime>,
+//! ```
+
+
+use serde;
+#[cfg(feature = "chrono")]
+use chrono;
+
+
+/// A date and time returned by GitLab.
+#[cfg(feature = "chrono")]
+pub type DateTime = chrono::DateTime<chrono::Utc>;
+
+/// A date and time returned by GitLab (enable the `chrono` feature to have it parsed).
+#[cfg(not(feature = "chrono"))]
+pub type DateTime = String;
+
+
+/// The date and time `timestamp` seconds after the Unix epoch (UTC), `None` if it is out of
+/// chrono's range.
+#[cfg(feature = "chrono")]
+pub fn from_timestamp(timestamp: u64) -> Option<DateTime> {
+    use chrono::TimeZone;
+
+    if timestamp > i64::max_value() as u64 {
+        return None;
+    }
+
+    chrono::Utc.timestamp_opt(timestamp as i64, 0).single()
+}
+
+/// The date and time `timestamp` seconds after the Unix epoch, in GitLab's format. `None` after
+/// the year 9999, which doesn't fit the format.
+#[cfg(not(feature = "chrono"))]
+pub fn from_timestamp(timestamp: u64) -> Option<DateTime> {
+    let days = timestamp / 86400;
+    let seconds = timestamp % 86400;
+
+    // Civil date from the number of days since 1970-01-01 (proleptic Gregorian calendar).
+    let z = days + 719468;
+    let era = z / 146097;
+    let day_of_era = z % 146097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 -
+                       day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    if year > 9999 {
+        return None;
+    }
+
+    Some(format!("{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.000Z",
+                 year,
+                 month,
+                 day,
+                 seconds / 3600,
+                 seconds % 3600 / 60,
+                 seconds % 60))
+}
+
+
+#[cfg(feature = "chrono")]
+pub fn deserialize<D>(deserializer: D) -> ::std::result::Result<DateTime, D::Error>
+    where D: serde::Deserializer
+{
+    let date: String = serde::Deserialize::deserialize(deserializer)?;
+
+    chrono::DateTime::parse_from_rfc3339(&date)
+        .map(|parsed| parsed.with_timezone(&chrono::Utc))
+        .or_else(|_| {
+            chrono::NaiveDate::parse_from_str(&date, "%Y-%m-%d")
+                .map(|day| chrono::DateTime::from_utc(day.and_hms(0, 0, 0), chrono::Utc))
+        })
+        .map_err(|e| {
+            <D::Error as serde::de::Error>::custom(format!("invalid date '{}': {}", date, e))
+        })
+}
+
+#[cfg(not(feature = "chrono"))]
+pub fn deserialize<D>(deserializer: D) -> ::std::result::Result<DateTime, D::Error>
+    where D: serde::Deserializer
+{
+    serde::Deserialize::deserialize(deserializer)
+}
+
+
+/// The date in GitLab's format, in UTC, for example to be sent as a query parameter.
+#[cfg(feature = "chrono")]
+pub fn to_string(date: &DateTime) -> String {
+    date.format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string()
+}
+
+/// The date as sent by GitLab, for example to be sent back as a query parameter.
+#[cfg(not(feature = "chrono"))]
+pub fn to_string(date: &DateTime) -> String {
+    date.clone()
+}
+
+
+/// Serialize the date in GitLab's format, see `to_string()`.
+pub fn serialize<S>(date: &DateTime, serializer: S) -> ::std::result::Result<S::Ok, S::Error>
+    where S: serde::Serializer
+{
+    serializer.serialize_str(&to_string(date))
+}
+
+
+/// (De)serialization of the optional dates (`Option<DateTime>`).
+pub mod option {
+    use serde;
+
+    use datetime::DateTime;
+
+
+    #[derive(Deserialize)]
+    struct Wrapper(#[serde(with = "::datetime")] DateTime);
+
+
+    pub fn deserialize<D>(deserializer: D) -> ::std::result::Result<Option<DateTime>, D::Error>
+        where D: serde::Deserializer
+    {
+        let wrapped: Option<Wrapper> = serde::Deserialize::deserialize(deserializer)?;
+
+        Ok(wrapped.map(|Wrapper(date)| date))
+    }
+
+    pub fn serialize<S>(date: &Option<DateTime>,
+                        serializer: S)
+                        -> ::std::result::Result<S::Ok, S::Error>
+        where S: serde::Serializer
+    {
+        match *date {
+            Some(ref date) => serializer.serialize_some(&super::to_string(date)),
+            None => serializer.serialize_none(),
+        }
+    }
+}
+
+
+/// (De)serialization of the optional dates without a time (`Option<DateTime>`), serialized
+/// back without a time.
+pub mod option_date {
+    use serde;
+
+    use datetime::DateTime;
+
+
+    pub fn deserialize<D>(deserializer: D) -> ::std::result::Result<Option<DateTime>, D::Error>
+        where D: serde::Deserializer
+    {
+        super::option::deserialize(deserializer)
+    }
+
+    #[cfg(feature = "chrono")]
+    pub fn serialize<S>(date: &Option<DateTime>,
+                        serializer: S)
+                        -> ::std::result::Result<S::Ok, S::Error>
+        where S: serde::Serializer
+    {
+        match *date {
+            Some(ref date) => serializer.serialize_some(&date.format("%Y-%m-%d").to_string()),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    #[cfg(not(feature = "chrono"))]
+    pub fn serialize<S>(date: &Option<DateTime>,
+                        serializer: S)
+                        -> ::std::result::Result<S::Ok, S::Error>
+        where S: serde::Serializer
+    {
+        super::option::serialize(date, serializer)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use serde_json;
+
+    use datetime::{DateTime, from_timestamp};
+
+
+    #[derive(Debug, Serialize, Deserialize)]
+    struct Dated {
+        #[serde(with = "::datetime")]
+        created_at: DateTime,
+    }
+
+
+    #[derive(Debug, Serialize, Deserialize)]
+    struct MaybeDated {
+        #[serde(default, with = "::datetime::option")]
+        due_date: Option<DateTime>,
+    }
+
+
+    #[test]
+    fn optional() {
+        let dated: MaybeDated = serde_json::from_str(r#"{"due_date": "2017-04-29T08:46:00.000Z"}"#)
+            .expect("JSON deserialization failed");
+        assert_eq!(serde_json::to_string(&dated).unwrap(),
+                   r#"{"due_date":"2017-04-29T08:46:00.000Z"}"#);
+
+        for json in &[r#"{"due_date": null}"#, "{}"] {
+            let dated: MaybeDated = serde_json::from_str(json)
+                .expect("JSON deserialization failed");
+            assert!(dated.due_date.is_none());
+            assert_eq!(serde_json::to_string(&dated).unwrap(), r#"{"due_date":null}"#);
+        }
+    }
+
+
+    #[derive(Debug, Serialize, Deserialize)]
+    struct Due {
+        #[serde(default, with = "::datetime::option_date")]
+        due_date: Option<DateTime>,
+    }
+
+
+    #[test]
+    fn optional_date() {
+        let due: Due = serde_json::from_str(r#"{"due_date": "2017-05-01"}"#)
+            .expect("JSON deserialization failed");
+        assert_eq!(serde_json::to_string(&due).unwrap(), r#"{"due_date":"2017-05-01"}"#);
+
+        let due: Due = serde_json::from_str("{}").expect("JSON deserialization failed");
+        assert!(due.due_date.is_none());
+    }
+
+
+    #[cfg(not(feature = "chrono"))]
+    #[test]
+    fn deserialize_string() {
+        let dated: Dated = serde_json::from_str(r#"{"created_at": "2017-04-29T08:46:00.000Z"}"#)
+            .expect("JSON deserialization failed");
+        assert_eq!(dated.created_at, "2017-04-29T08:46:00.000Z");
+        assert_eq!(serde_json::to_string(&dated).unwrap(),
+                   r#"{"created_at":"2017-04-29T08:46:00.000Z"}"#);
+    }
+
+
+    #[test]
+    fn timestamp() {
+        let dated = Dated { created_at: from_timestamp(1493455560).unwrap() };
+        assert_eq!(serde_json::to_string(&dated).unwrap(),
+                   r#"{"created_at":"2017-04-29T08:46:00.000Z"}"#);
+
+        let dated = Dated { created_at: from_timestamp(0).unwrap() };
+        assert_eq!(serde_json::to_string(&dated).unwrap(),
+                   r#"{"created_at":"1970-01-01T00:00:00.000Z"}"#);
+
+        let dated = Dated { created_at: from_timestamp(951782399).unwrap() };
+        assert_eq!(serde_json::to_string(&dated).unwrap(),
+                   r#"{"created_at":"2000-02-28T23:59:59.000Z"}"#);
+
+        let dated = Dated { created_at: from_timestamp(253402300799).unwrap() };
+        assert_eq!(serde_json::to_string(&dated).unwrap(),
+                   r#"{"created_at":"9999-12-31T23:59:59.000Z"}"#);
+
+        // Out of range.
+        assert!(from_timestamp(u64::max_value()).is_none());
+        assert!(from_timestamp(i64::max_value() as u64).is_none());
+    }
+
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn deserialize_chrono() {
+        use chrono::{Datelike, Timelike};
+
+        let dated: Dated = serde_json::from_str(r#"{"created_at": "2017-04-29T08:46:00.000Z"}"#)
+            .expect("JSON deserialization failed");
+        assert_eq!((dated.created_at.year(), dated.created_at.month(), dated.created_at.day()),
+                   (2017, 4, 29));
+        assert_eq!((dated.created_at.hour(), dated.created_at.minute()), (8, 46));
+        assert_eq!(serde_json::to_string(&dated).unwrap(),
+                   r#"{"created_at":"2017-04-29T08:46:00.000Z"}"#);
+
+        // Offsets are converted to UTC.
+        let json = r#"{"created_at": "2017-04-29T10:46:00.123+02:00"}"#;
+        let dated: Dated = serde_json::from_str(json).expect("JSON deserialization failed");
+        assert_eq!(serde_json::to_string(&dated).unwrap(),
+                   r#"{"created_at":"2017-04-29T08:46:00.123Z"}"#);
+
+        assert!(serde_json::from_str::<Dated>(r#"{"created_at": "yesterday"}"#).is_err());
+
+        // Dates without a time are taken at midnight UTC.
+        let dated: Dated = serde_json::from_str(r#"{"created_at": "2017-05-01"}"#)
+            .expect("JSON deserialization failed");
+        assert_eq!(serde_json::to_string(&dated).unwrap(),
+                   r#"{"created_at":"2017-05-01T00:00:00.000Z"}"#);
+    }
+}
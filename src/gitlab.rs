@@ -1,29 +1,130 @@
 
-use std::io::Read;  // Trait providing read_to_string()
 use std;
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
 
+use crossbeam;
 use url;
 use hyper;
 use serde;
 use serde_json;
-use regex;
+use semver;
 
 
 // use Groups;
+use BuildQuery;
 use Lister;
+use PageInfo;
+use pagination::parse_header;
+use transport::{HyperTransport, JitterRng, RateLimit, Request, RequestOptions, Response,
+                ResponseMeta, RetryConfig, StreamingResponse, Transport};
 
 use ::errors::*;
 
 
+/// The version of the API used until `server_version()` finds a GitLab providing the API v4.
 pub const API_VERSION: u16 = 3;
 
+/// The `Accept` header of the requests, unless overridden by `RequestOptions::accept()`.
+const DEFAULT_ACCEPT: &'static str = "application/json";
 
+/// Maximum number of redirects followed by a GET.
+const MAX_REDIRECTS: u8 = 5;
 
+/// Number of items per page fetched when searching through all the pages of a listing.
+const PAGINATED_SEARCH_PER_PAGE: u16 = 20;
 
+
+
+
+/// A GitLab client.
+///
+/// A `GitLab` is `Send + Sync`: it can be shared between threads, for example behind an `Arc`.
 pub struct GitLab {
     url: url::Url,
     private_token: String,
-    client: hyper::Client,
+    transport: Box<Transport>,
+    /// Number of items per page of the listings not explicitly paginated
+    per_page: Option<u16>,
+    /// Options of all requests, unless overridden for a single call
+    options: RequestOptions,
+    /// Whether the default transport accepts invalid TLS certificates
+    accept_invalid_certs: bool,
+    /// Retry policy for `429` and `5xx` responses, `None` to never retry them
+    retry: Option<RetryConfig>,
+    /// Random numbers of the retries' jitter
+    retry_rng: Mutex<JitterRng>,
+    /// Cached result of `server_version()`
+    server_version: Mutex<Option<semver::Version>>,
+    /// Diagnostic headers of the last response received
+    last_response_meta: Mutex<Option<ResponseMeta>>,
+    /// Rate limit announced by the last response received
+    rate_limit: Mutex<Option<RateLimit>>,
+    /// Successful GET responses, when enabled with `enable_cache()`
+    cache: Mutex<Option<ResponseCache>>,
+}
+
+
+/// The method, URL and headers (sorted) of a cached request.
+///
+/// The headers are part of the key since they can change the response, for example `Accept`.
+type CacheKey = (hyper::method::Method, String, Vec<String>);
+
+
+/// In-memory cache of responses, keyed by method, URL and headers.
+struct ResponseCache {
+    ttl: Duration,
+    entries: HashMap<CacheKey, (Instant, Response)>,
+}
+
+
+impl ResponseCache {
+    fn new(ttl: Duration) -> ResponseCache {
+        ResponseCache {
+            ttl: ttl,
+            entries: HashMap::new(),
+        }
+    }
+
+    /// The response cached for `key`, unless it is older than the TTL.
+    fn get(&mut self, key: &CacheKey) -> Option<Response> {
+        let expired = match self.entries.get(key) {
+            Some(&(stored, ref response)) => {
+                if stored.elapsed() < self.ttl {
+                    return Some(response.clone());
+                }
+                true
+            }
+            None => false,
+        };
+        if expired {
+            self.entries.remove(key);
+        }
+
+        None
+    }
+
+    /// Cache `response`, evicting the expired entries.
+    fn insert(&mut self, key: CacheKey, response: Response) {
+        let ttl = self.ttl;
+        self.entries.retain(|_, &mut (stored, _)| stored.elapsed() < ttl);
+        self.entries.insert(key, (Instant::now(), response));
+    }
+}
+
+
+/// The key of `request` in the `ResponseCache`.
+fn cache_key(request: &Request) -> CacheKey {
+    let mut headers: Vec<String> = request.headers
+        .iter()
+        .map(|header| format!("{}: {}", header.name().to_lowercase(), header.value_string()))
+        .collect();
+    headers.sort();
+
+    (request.method.clone(), request.url.clone(), headers)
 }
 
 
@@ -61,7 +162,7 @@ fn validate_url(scheme: &str, domain: &str, port: u16) -> Result<url::Url> {
     let url_string = format!("{}://{}/api/v{}/", scheme, domain, API_VERSION);
     let mut url = url::Url::parse(&url_string)
         .chain_err(|| format!("failure to parse URL '{}'", url_string))?;
-    url.set_port(Some(port)).expect("bad port provided");
+    set_url_port(&mut url, port)?;
 
     {
         let url_host = url.host_str();
@@ -76,6 +177,15 @@ fn validate_url(scheme: &str, domain: &str, port: u16) -> Result<url::Url> {
     Ok(url)
 }
 
+/// Set the port of `url`, failing for URLs which cannot have a port (e.g. `mailto:`).
+fn set_url_port(url: &mut url::Url, port: u16) -> Result<()> {
+    if url.set_port(Some(port)).is_err() {
+        bail!(format!("cannot set port {} on URL '{}'", port, url));
+    }
+
+    Ok(())
+}
+
 impl GitLab {
     pub fn _new(scheme: &str, domain: &str, port: u16, private_token: &str) -> Result<GitLab> {
         if private_token.len() != 20 {
@@ -85,20 +195,36 @@ impl GitLab {
 
         let url: url::Url = validate_url(scheme, domain, port).chain_err(|| "invalid URL")?;
 
+        let proxy = match std::env::var("HTTP_PROXY") {
+            Ok(proxy) => {
+                let proxy: Vec<&str> = proxy.trim_left_matches("http://").split(':').collect();
+                let hostname = proxy[0].to_string();
+                let port: u16 = proxy[1].parse()
+                    .chain_err(|| format!("failure to set port to {}", proxy[1]))?;
+
+                Some((hostname, port))
+            }
+            Err(_) => None,
+        };
+
         Ok(GitLab {
             url: url,
             private_token: private_token.to_string(),
-            client: match std::env::var("HTTP_PROXY") {
-                Ok(proxy) => {
-                    let proxy: Vec<&str> = proxy.trim_left_matches("http://").split(':').collect();
-                    let hostname = proxy[0].to_string();
-                    let port = proxy[1].parse()
-                        .chain_err(|| format!("failure to set port to {}", proxy[1]))?;
-
-                    hyper::Client::with_http_proxy(hostname, port)
+            transport: Box::new(HyperTransport::new(move || match proxy {
+                Some((ref hostname, port)) => {
+                    hyper::Client::with_http_proxy(hostname.clone(), port)
                 }
-                Err(_) => hyper::Client::new(),
-            },
+                None => hyper::Client::new(),
+            })),
+            per_page: None,
+            options: RequestOptions::new(),
+            accept_invalid_certs: false,
+            retry: None,
+            retry_rng: Mutex::new(JitterRng::from_clock()),
+            server_version: Mutex::new(None),
+            last_response_meta: Mutex::new(None),
+            rate_limit: Mutex::new(None),
+            cache: Mutex::new(None),
         })
     }
 
@@ -111,16 +237,168 @@ impl GitLab {
         GitLab::_new("https", domain, 443, private_token)
     }
 
-    pub fn port(mut self, port: u16) -> Self {
-        self.url.set_port(Some(port)).unwrap();
+    /// Create a `GitLab` instance configured from the environment.
+    ///
+    /// | Variable | Required | Description |
+    /// | -------- | -------- | ----------- |
+    /// | `GITLAB_HOSTNAME` | yes | The GitLab server's hostname |
+    /// | `GITLAB_TOKEN`    | yes | The private token |
+    /// | `GITLAB_PER_PAGE` | no  | Default number of items per page (see `per_page()`) |
+    /// | `GITLAB_API_INSECURE` | no | `1` to accept invalid TLS certificates (see `danger_accept_invalid_certs()`), for development only |
+    pub fn from_env() -> Result<GitLab> {
+        let hostname = std::env::var("GITLAB_HOSTNAME")
+            .chain_err(|| "cannot read environment variable 'GITLAB_HOSTNAME'")?;
+        let token = std::env::var("GITLAB_TOKEN")
+            .chain_err(|| "cannot read environment variable 'GITLAB_TOKEN'")?;
+
+        let mut gl = GitLab::new(&hostname, &token)?;
+
+        let insecure = std::env::var("GITLAB_API_INSECURE").map(|value| value.trim() == "1");
+        if insecure.unwrap_or(false) {
+            warn!("GITLAB_API_INSECURE=1: TLS certificates are not verified!");
+            gl = gl.danger_accept_invalid_certs()?;
+        }
+
+        match std::env::var("GITLAB_PER_PAGE") {
+            Ok(per_page) => {
+                let per_page = per_page.trim()
+                    .parse()
+                    .chain_err(|| format!("invalid GITLAB_PER_PAGE '{}'", per_page))?;
+                gl.per_page(per_page).chain_err(|| "invalid GITLAB_PER_PAGE")
+            }
+            Err(_) => Ok(gl),
+        }
+    }
+
+    /// Create a `GitLab` instance for `domain`, reading the private token from the file `path`.
+    ///
+    /// Keeping the token in a file (readable only by its owner) avoids exposing it on the
+    /// command line or in the environment. Whitespace around the token, like a trailing
+    /// newline, is ignored. The token itself must be made of letters, digits, `-` and `_`.
+    pub fn from_token_file<P>(domain: &str, path: P) -> Result<GitLab>
+        where P: AsRef<Path>
+    {
+        let path = path.as_ref();
+        let mut file = std::fs::File::open(path)
+            .chain_err(|| format!("cannot open token file '{}'", path.display()))?;
+        let mut token = String::new();
+        file.read_to_string(&mut token)
+            .chain_err(|| format!("cannot read token file '{}'", path.display()))?;
+
+        // The token is never included in the errors.
+        let token = token.trim();
+        if token.is_empty() {
+            bail!(format!("token file '{}' is empty", path.display()));
+        }
+        if !token.chars().all(|c| c.is_digit(36) || c == '-' || c == '_') {
+            bail!(format!("token file '{}' does not contain a valid private token",
+                          path.display()));
+        }
+
+        GitLab::new(domain, token)
+            .chain_err(|| format!("invalid private token in file '{}'", path.display()))
+    }
+
+    /// Set the number of items per page of the listings not explicitly paginated (see
+    /// `get_listing()`).
+    ///
+    /// GitLab does not return more than 100 items per page: `per_page` must be between 1 and
+    /// 100.
+    pub fn per_page(mut self, per_page: u16) -> Result<Self> {
+        if per_page < 1 || per_page > ::pagination::MAX_PER_PAGE {
+            bail!(format!("per_page should be between 1 and {} (not {})",
+                          ::pagination::MAX_PER_PAGE,
+                          per_page));
+        }
+        self.per_page = Some(per_page);
+        Ok(self)
+    }
+
+    /// Change the port used to connect to GitLab.
+    pub fn port(mut self, port: u16) -> Result<Self> {
+        set_url_port(&mut self.url, port)?;
+        Ok(self)
+    }
+
+    /// Change the scheme used to connect to GitLab; only `http` and `https` are supported.
+    pub fn scheme(mut self, scheme: &str) -> Result<Self> {
+        match scheme {
+            "http" | "https" => {}
+            _ => bail!(format!("unsupported scheme '{}', not 'http' or 'https'", scheme)),
+        }
+
+        if self.url.set_scheme(scheme).is_err() {
+            bail!(format!("cannot set scheme '{}'", scheme));
+        }
+
+        Ok(self)
+    }
+
+    /// Accept any TLS certificate, including self-signed and expired ones, for example to use a
+    /// development GitLab instance.
+    ///
+    /// This is dangerous: anyone able to intercept the connection can impersonate GitLab and
+    /// steal the private token. `from_env()` calls it when `GITLAB_API_INSECURE` is `1`.
+    ///
+    /// This replaces the transport by a new `hyper` one (see `HyperTransport`).
+    pub fn danger_accept_invalid_certs(mut self) -> Result<Self> {
+        warn!("Accepting invalid TLS certificates: GitLab's identity is not verified!");
+        self.transport = Box::new(HyperTransport::danger_accept_invalid_certs()?);
+        self.accept_invalid_certs = true;
+        Ok(self)
+    }
+
+    /// Whether invalid TLS certificates are accepted, see `danger_accept_invalid_certs()`.
+    pub fn accepts_invalid_certs(&self) -> bool {
+        self.accept_invalid_certs
+    }
+
+    /// Use `transport` to send the requests instead of the default `hyper` client.
+    ///
+    /// See the `transport` module.
+    pub fn transport(mut self, transport: Box<Transport>) -> Self {
+        self.transport = transport;
+        self.accept_invalid_certs = false;
+        self
+    }
+
+    /// Set the options (timeout, retries, headers) of all requests.
+    ///
+    /// Calls taking their own `RequestOptions` (like `get_with_options()`) override these.
+    pub fn request_options(mut self, options: RequestOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Retry the requests answered `429 Too Many Requests` or `5xx` following `config`.
+    ///
+    /// By default, these responses are returned (and reported as errors) right away. See
+    /// `RetryConfig` for the delays between retries.
+    pub fn with_retry(mut self, config: RetryConfig) -> Self {
+        if let Some(seed) = config.seed {
+            self.retry_rng = Mutex::new(JitterRng::new(seed));
+        }
+        self.retry = Some(config);
         self
     }
 
-    pub fn scheme(mut self, scheme: &str) -> Self {
-        self.url.set_scheme(scheme).unwrap();
+    /// Cache the successful (`200 OK`) responses to GET requests for `ttl`.
+    ///
+    /// Identical GETs (same URL and headers) made within `ttl` are answered from memory without
+    /// contacting GitLab. Other requests (POST, PUT, DELETE) are never cached, nor do they
+    /// invalidate the cache: use `clear_cache()` after modifying data that was already read.
+    pub fn enable_cache(mut self, ttl: Duration) -> Self {
+        self.cache = Mutex::new(Some(ResponseCache::new(ttl)));
         self
     }
 
+    /// Forget all the responses cached since `enable_cache()`.
+    pub fn clear_cache(&self) {
+        if let Some(ref mut cache) = *self.cache.lock().unwrap() {
+            cache.entries.clear();
+        }
+    }
+
     /// Build a URL used to access GitLab instance, including some parameters.
     ///
     /// # Examples
@@ -138,17 +416,65 @@ impl GitLab {
     /// assert_eq!(gl.build_url("groups?order_by=path").unwrap(), expected_url);
     /// ```
     pub fn build_url(&self, query: &str) -> Result<String> {
-        let mut new_url = self.url
-            .clone()
+        let mut new_url = self.join_query(query)?;
+        new_url.query_pairs_mut().append_pair("private_token", &self.private_token);
+
+        Ok(new_url.into_string())
+    }
+
+    /// Same as `build_url()` but without the private token, so the URL can safely be logged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gitlab_api::GitLab;
+    ///
+    /// let expected_url = "https://gitlab.example.com/api/v3/groups?order_by=path";
+    ///
+    /// let gl = GitLab::new("gitlab.example.com", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+    ///
+    /// assert_eq!(gl.build_url_safe("groups?order_by=path").unwrap(), expected_url);
+    /// ```
+    pub fn build_url_safe(&self, query: &str) -> Result<String> {
+        Ok(self.join_query(query)?.into_string())
+    }
+
+    /// The version of the API the queries are sent to.
+    ///
+    /// This is `API_VERSION` (3) until the GitLab server's version is known: once
+    /// `server_version()` (or `supports_api_v4()`, ...) found a GitLab providing the API v4, the
+    /// queries are sent to `/api/v4/` instead, and the listers use the API v4 parameters where
+    /// they differ (for example `iids[]` instead of `iid`). Only the cached version is used:
+    /// this never queries GitLab.
+    pub fn api_version(&self) -> u16 {
+        match *self.server_version.lock().unwrap() {
+            Some(ref version) if ::version::supports_api_v4(version) => 4,
+            _ => API_VERSION,
+        }
+    }
+
+    /// The base URL of the queries, for `api_version()`.
+    fn api_url(&self) -> url::Url {
+        let mut url = self.url.clone();
+
+        let api_version = self.api_version();
+        if api_version != API_VERSION {
+            let path = url.path().replace(&format!("/api/v{}/", API_VERSION),
+                                          &format!("/api/v{}/", api_version));
+            url.set_path(&path);
+        }
+
+        url
+    }
+
+    fn join_query(&self, query: &str) -> Result<url::Url> {
+        self.api_url()
             .join(query)
             .chain_err(|| {
                 format!("Failure to join query '{}' to url {}",
                         query,
                         self.url.as_str())
-            })?;
-        new_url.query_pairs_mut().append_pair("private_token", &self.private_token);
-
-        Ok(new_url.into_string())
+            })
     }
 
     // pub fn attempt_connection(&self) -> Result<hyper::client::Response, hyper::Error> {
@@ -172,430 +498,2961 @@ impl GitLab {
     /// * Until all `BuildQuery::build_query()`s use `serde_urlencoded`, the `query` paramter will
     ///   have to remain a string.
     ///
+    /// The JSON body is deserialized while it is received, without being kept whole in memory.
+    ///
     /// Returns a specific GitLab type, wrapped in a `Result`.
     pub fn get<T, U>(&self, query: &str, page: U, per_page: U) -> Result<T>
         where T: serde::Deserialize,
               U: Into<Option<u16>>
     {
-        let mut url = self.build_url(query)
-            .chain_err(|| format!("failure to build url for query '{}'", query))?;
-        info!("url: {:?}", remove_gitlab_token_from_url(&url));
-
-        // Add pagination information if requested.
-        page.into().map(|page| url.push_str(&format!("&page={}", page)));
-        per_page.into().map(|per_page| url.push_str(&format!("&per_page={}", per_page)));
-
-        // Close connections after each GET.
-        let mut res: hyper::client::Response = self.client
-            .get(&url)
-            .header(hyper::header::Connection::close())
-            .send()
-            .chain_err(|| format!("cannot send request '{}' to {:?}", query, self))?;
-        info!("res.status: {:?}", res.status);
-        // The headers might leak the token, don't print them.
-        // debug!("res.headers: {:?}", res.headers);
-        // Hide the url's token in logger
-        debug!("res.url: {}",
-               remove_gitlab_token_from_url(res.url.as_str()));
+        self.get_with_options(query, page, per_page, &RequestOptions::new())
+    }
 
-        let mut body = String::new();
-        res.read_to_string(&mut body).chain_err(|| "cannot read response body")?;
-        debug!("body:\n{}", body);
+    /// Same as `get()` for the first page of a listing, with the default number of items per
+    /// page (see `per_page()`).
+    ///
+    /// The listers' `list()` use it. Other GETs, like the ones of a single item, are sent
+    /// without `per_page`.
+    pub fn get_listing<T>(&self, query: &str) -> Result<T>
+        where T: serde::Deserialize
+    {
+        self.get(query, None, self.per_page)
+    }
 
-        if res.status != hyper::status::StatusCode::Ok {
-            bail!(format!("status code '{}', not '200 OK'", res.status));
-        }
+    /// Same as `get()` but `options` override the `GitLab`'s request options for this call.
+    pub fn get_with_options<T, U>(&self,
+                                  query: &str,
+                                  page: U,
+                                  per_page: U,
+                                  options: &RequestOptions)
+                                  -> Result<T>
+        where T: serde::Deserialize,
+              U: Into<Option<u16>>
+    {
+        let res = self.get_json(query, page, per_page, options)?;
 
-        serde_json::from_str(body.as_str())
-            .chain_err(|| format!("cannot build Rust struct from JSON data: {}", body))
+        parse_json(query, res.body)
     }
 
-    pub fn version(&self) -> Result<::Version> {
-        self.get("version", None, None).chain_err(|| "cannot query 'version'")
+    /// Perform an HTTP GET to the GitLab server from a specific query, returning both the
+    /// GitLab type and the raw JSON value.
+    ///
+    /// The raw `serde_json::Value` gives access to the fields returned by GitLab but not (yet)
+    /// modeled by this crate's structs.
+    pub fn get_with_raw<T>(&self, query: &str) -> Result<(T, serde_json::Value)>
+        where T: serde::Deserialize
+    {
+        let res = self.get_json(query, None, None, &RequestOptions::new())?.into_response()?;
+
+        parse_with_raw(res.body.as_str())
     }
 
-    pub fn groups(&self) -> ::groups::GroupsLister {
-        ::groups::GroupsLister::new(self)
+    /// Same as `get()` but an empty answer (`[]`, `{}`, `null` or `false`) gives `T::default()`.
+    ///
+    /// See the `tolerant` module for the endpoints needing this.
+    pub fn get_or_default<T>(&self, query: &str) -> Result<T>
+        where T: serde::Deserialize + Default
+    {
+        let res = self.get_json(query, None, None, &RequestOptions::new())?.into_response()?;
+
+        ::tolerant::from_str_or_default(&res.body)
     }
 
-    pub fn projects(&self) -> ::projects::ProjectsLister {
-        ::projects::ProjectsLister::new(self)
+    /// Same as `get()` but an empty answer (`[]`, `{}`, `null` or `false`) gives `None`.
+    ///
+    /// See the `tolerant` module for the endpoints needing this.
+    pub fn get_optional<T>(&self, query: &str) -> Result<Option<T>>
+        where T: serde::Deserialize
+    {
+        let res = self.get_json(query, None, None, &RequestOptions::new())?.into_response()?;
+
+        ::tolerant::from_str_optional(&res.body)
     }
 
-    pub fn issues(&self) -> ::issues::IssuesLister {
-        ::issues::IssuesLister::new(self)
+    /// Perform an HTTP GET to the GitLab server from a specific query, returning both the
+    /// GitLab type and the pagination information sent by GitLab in the response's headers.
+    ///
+    /// See `get()` for the meaning of the arguments.
+    pub fn get_with_headers<T, U>(&self,
+                                  query: &str,
+                                  page: U,
+                                  per_page: U)
+                                  -> Result<(T, PageInfo)>
+        where T: serde::Deserialize,
+              U: Into<Option<u16>>
+    {
+        self.get_with_headers_and_options(query, page, per_page, &RequestOptions::new())
     }
 
-    pub fn merge_requests(&self, project_id: i64) -> ::merge_requests::MergeRequestsLister {
-        ::merge_requests::MergeRequestsLister::new(self, project_id)
+    /// Same as `get_with_headers()` but `options` (timeout, retries, headers) override the
+    /// `GitLab`'s request options for this call.
+    pub fn get_with_headers_and_options<T, U>(&self,
+                                              query: &str,
+                                              page: U,
+                                              per_page: U,
+                                              options: &RequestOptions)
+                                              -> Result<(T, PageInfo)>
+        where T: serde::Deserialize,
+              U: Into<Option<u16>>
+    {
+        let res = self.get_json(query, page, per_page, options)?;
+        let page_info = PageInfo::from_headers(&res.headers);
+
+        Ok((parse_json(query, res.body)?, page_info))
     }
 
-    // pub fn groups(&mut self, listing: ::groups::Listing) -> Result<Groups, serde_json::Error> {
-    //     let query = listing.build_query();
-    //     // self.get(&query)
-    //     unimplemented!();
-    // }
+    /// The total number of items listed by `query`, from the `X-Total` header.
+    ///
+    /// Only a single item is fetched. Returns `None` if GitLab did not send the total, which it
+    /// omits for listings too large to be counted cheaply.
+    pub fn count(&self, query: &str) -> Result<Option<u64>> {
+        let (_, page_info): (serde_json::Value, PageInfo) = self.get_with_headers(query, 1, 1)?;
 
-    // pub fn owned_groups(&mut self) -> Result<Groups, serde_json::Error> {
-    //     let query = ::groups::owned_groups::Listing::new().build_query();
-    //     info!("query: {:?}", query);
-    //     self.get(&query)
-    // }
+        Ok(page_info.total)
+    }
 
+    /// Perform an HTTP GET to the GitLab server from a specific query, returning the response's
+    /// body as a string.
+    ///
+    /// See `get()` for the meaning of the arguments.
+    pub fn get_raw<U>(&self, query: &str, page: U, per_page: U) -> Result<String>
+        where U: Into<Option<u16>>
+    {
+        self.get_raw_with_options(query, page, per_page, &RequestOptions::new())
+    }
 
-    /// Search for a (generic) GitLab item, iterating over all found match to get the proper one.
+    /// Same as `get_raw()` but `options` override the `GitLab`'s request options for this call.
     ///
-    /// This allows getting, for example, a specific issue from a specific project. The GitLab API
-    /// does not make this easy to do in a generic way, so we need to perform the search in a loop
-    /// until the proper item is found and returned.
-    fn get_paginated_from_project<T, F, G, L>(&self, item_search_closure: F, iter_find_closure: G) -> Result<T>
-        where F: Fn() -> L,
-              G: Fn(&<std::vec::IntoIter<T> as IntoIterator>::Item) -> bool,
-              L: Lister<Vec<T>>
+    /// Use `RequestOptions::accept()` to ask for another representation than JSON, for example
+    /// `text/plain` for a diff.
+    pub fn get_raw_with_options<U>(&self,
+                                   query: &str,
+                                   page: U,
+                                   per_page: U,
+                                   options: &RequestOptions)
+                                   -> Result<String>
+        where U: Into<Option<u16>>
+    {
+        self._get(query, page, per_page, options)?.into_response().map(|res| res.body)
+    }
+
+    /// Same as `_get()` but make sure GitLab answered with JSON before the body gets parsed.
+    fn get_json<U>(&self,
+                   query: &str,
+                   page: U,
+                   per_page: U,
+                   options: &RequestOptions)
+                   -> Result<StreamingResponse>
+        where U: Into<Option<u16>>
     {
-        // Explicitly set the pagination information so we can iterate over the pages.
-        let mut pagination_page = 1;
-        let pagination_per_page = 20;
+        let res = self._get(query, page, per_page, options)?;
 
-        let mut found: Option<T>;
+        let content_type = res.headers
+            .get_raw("Content-Type")
+            .and_then(|values| values.first())
+            .map(|value| String::from_utf8_lossy(value).into_owned());
+        check_json_content_type(content_type.as_ref().map(|s| s.as_str()))
+            .chain_err(|| format!("unexpected response to query '{}'", query))?;
 
-        // Query GitLab inside the page loop
-        loop {
-            // Query GitLab, specifying the pagination information. Use a closure, passed as
-            // argument, to make this operation generic.
-            // To list project's issues:
-            // let found_items = self.issues().project(id).list_paginated(...).chain_err(...)?;
-            // To list project's merge requests:
-            // let found_items = self.merge_requests(id).list_paginated(...).chain_err(..)?;
-            // To get matching projects:
-            // let found_items = self.projects().search(name).list_paginated(...).chain_err(...)?;
-
-            let found_items =
-                item_search_closure().list_paginated(pagination_page, pagination_per_page)
-                    .chain_err(|| "cannot get item in GitLab::get_paginated_from_project()")?;
-
-            let nb_found = found_items.len();
-
-            // Find the right item in the vector, if any. Use the second closure passed as argument
-            // as the closure used in `find()`.
-            found = found_items.into_iter().find(&iter_find_closure);
-
-            // Break if we find something.
-            if found.is_some() {
-                break;
+        Ok(res)
+    }
+
+    /// Perform the GET, returning the `200 OK` response with its body left to be read.
+    fn _get<U>(&self,
+               query: &str,
+               page: U,
+               per_page: U,
+               options: &RequestOptions)
+               -> Result<StreamingResponse>
+        where U: Into<Option<u16>>
+    {
+        let mut url = self.build_url(query)
+            .chain_err(|| format!("failure to build url for query '{}'", query))?;
+        info!("url: {:?}", self.build_url_safe(query)?);
+
+        // Add pagination information if requested.
+        let page = page.into();
+        page.map(|page| url.push_str(&format!("&page={}", page)));
+        per_page.into()
+            .map(|per_page| url.push_str(&format!("&per_page={}", per_page)));
+
+        let mut request = Request::new(hyper::method::Method::Get, &url);
+        self.options.merge(options).apply(&mut request);
+        let cache_key = cache_key(&request);
+        let cached = self.cache.lock().unwrap().as_mut().and_then(|cache| cache.get(&cache_key));
+        if let Some(res) = cached {
+            debug!("response to query '{}' found in cache", query);
+            return Ok(StreamingResponse::from(res));
+        }
+
+        // Follow the redirects ourselves: the token must only be sent to the GitLab host.
+        let mut nb_redirects = 0;
+        let res = loop {
+            let res = self.send_streaming(Request::new(hyper::method::Method::Get, &url), options)
+                .chain_err(|| format!("cannot send request '{}' to {:?}", query, self))?;
+            info!("res.status: {:?}", res.status);
+
+            if !is_redirect(res.status) {
+                break res;
             }
 
-            // Also break if the number of found items is less than the maximum allowed per page.
-            // In that case, there is no more match and we need to stop the loop (we did not find
-            // anything).
-            if nb_found < pagination_per_page as usize {
-                break;
+            nb_redirects += 1;
+            if nb_redirects > MAX_REDIRECTS {
+                bail!(format!("too many redirects (more than {})", MAX_REDIRECTS));
             }
 
-            // Bump to the next page
-            pagination_page += 1;
-        }
+            let location = res.headers
+                .get_raw("Location")
+                .and_then(|values| values.first())
+                .map(|value| String::from_utf8_lossy(value).into_owned())
+                .ok_or_else(|| format!("redirect ('{}') without a 'Location' header", res.status))?;
+            url = redirect_target(&url, &location, &self.private_token)
+                .chain_err(|| format!("cannot follow redirect of query '{}'", query))?;
+        };
+        // The headers might leak the token, don't print them.
+        // debug!("res.headers: {:?}", res.headers);
 
-        // Return the found item
-        match found {
-            None => bail!("not found!"),
-            Some(item) => Ok(item),
+        if res.status != hyper::status::StatusCode::Ok {
+            let res = res.into_response()?;
+            bail!(status_error(res.status, &res.body))
         }
-    }
 
-    /// Get a specific "namespace/name" project.
-    /// NOTE: We can't search for "namespace/name", so we search for "name", and refine the match
-    ///       on the namespace. This means the operation could be slow as multiple query to the
-    ///       GitLab server might be required to find the right item.
-    pub fn get_project(&self, namespace: &str, name: &str) -> Result<::projects::Project> {
+        if page.is_none() {
+            warn_if_truncated(query, &res.headers);
+        }
 
-        // Closure to search for the item, possibly returning multiple match on multiple pages.
-        let query_gitlab_closure = || self.projects().search(name.to_string());
-        // Closure to find the right item in the found list on the page.
-        let iter_find_closure = |project: &::projects::Project| {
-            project.namespace.name == namespace && project.name == name
-        };
+        // Cached responses have to be read whole.
+        // The body is read before locking the cache, not to block the other threads meanwhile.
+        let caching = self.cache.lock().unwrap().is_some();
+        if caching {
+            let res = res.into_response()?;
+            debug!("body:\n{}", res.body);
+            if let Some(ref mut cache) = *self.cache.lock().unwrap() {
+                cache.insert(cache_key, res.clone());
+            }
+            return Ok(StreamingResponse::from(res));
+        }
 
-        self.get_paginated_from_project(query_gitlab_closure, iter_find_closure)
+        Ok(res)
     }
 
-    /// Get a project issue from a its project's `namespace` and `name` and the issue's `iid`.
-    ///
-    /// Since GitLab uses unique `id`s in its API and _not_ `iid`s, we will need to list issues
-    /// (grouped by pages of 20) until we find the proper issue matching the `id` requested.
+    /// Perform an HTTP POST to the GitLab server from a specific query.
     ///
-    /// **Note**: A `iid` is the issue number as seen by normal user, for example appearing on
-    /// a GitLab URL. This `iid` can be used to reference an issue (in other issues, in commit
-    /// messages, etc.) by prepending a pound sign to it, for example `#3`. An `id`, instead, is
-    /// GitLab's internal and unique id associated with the issue.
+    /// The `query` is the same as for `get()`, for example `projects/:id/repository/commits`.
+    /// The `body` is serialized to JSON and sent as the request's body.
     ///
-    /// Because we need to search (and thus query the GitLab server possibly multiple times), this
-    /// _can_ be a slow operation if there is many issues in the project.
-    pub fn get_issue(&self, namespace: &str, name: &str, iid: i64) -> Result<::issues::Issue> {
-        // We first need to find the specific project.
-        let project = self.get_project(namespace, name)
-            .chain_err(|| format!("cannot get project '{}/{}'", namespace, name))?;
-
-        // Closure to search for the item, possibly returning multiple match on multiple pages.
-        let query_gitlab_closure = || self.issues().project(project.id);
-        // Closure to find the right item in the found list on the page.
-        let iter_find_closure = |issue: &::issues::Issue| issue.iid == iid;
+    /// Returns the (created) GitLab type, wrapped in a `Result`.
+    pub fn post<T, B>(&self, query: &str, body: B) -> Result<T>
+        where T: serde::Deserialize,
+              B: serde::Serialize
+    {
+        self.post_with_options(query, body, &RequestOptions::new())
+    }
 
-        self.get_paginated_from_project(query_gitlab_closure, iter_find_closure)
+    /// Same as `post()` but `options` override the `GitLab`'s request options for this call.
+    pub fn post_with_options<T, B>(&self,
+                                   query: &str,
+                                   body: B,
+                                   options: &RequestOptions)
+                                   -> Result<T>
+        where T: serde::Deserialize,
+              B: serde::Serialize
+    {
+        self._send_json(hyper::method::Method::Post, query, body, options, true)
     }
 
-    /// Get a project merge request from a its project's `namespace` and `name` and
-    /// the issue's `iid`.
-    ///
-    /// Since GitLab uses unique `id`s in its API and _not_ `iid`s, we will need to
-    /// list issues (grouped by pages of 20) until we find the proper issue matching
-    /// the `id` requested.
+    /// Perform an HTTP PUT to the GitLab server from a specific query.
     ///
-    /// **Note**: A `iid` is the issue number as seen by normal user, for example appearing on
-    /// a GitLab URL. This `iid` can be used to reference an issue (in other issues, in commit
-    /// messages, etc.) by prepending a pound sign to it, for example `#3`. An `id`, instead, is
-    /// GitLab's internal and unique id associated with the issue.
+    /// See `post()` for the meaning of the arguments. Endpoints without parameters (for example
+    /// `projects/:id/repository/branches/:branch/protect`) take `()` as `body`: no body is sent.
     ///
-    /// Because we need to search (and thus query the GitLab server possibly multiple times), this
-    /// _can_ be a slow operation if there is many issues in the project.
-    pub fn get_merge_request(&self,
-                             namespace: &str,
-                             name: &str,
-                             iid: i64)
-                             -> Result<::merge_requests::MergeRequest> {
+    /// Returns the (updated) GitLab type, wrapped in a `Result`.
+    pub fn put<T, B>(&self, query: &str, body: B) -> Result<T>
+        where T: serde::Deserialize,
+              B: serde::Serialize
+    {
+        self._send_json(hyper::method::Method::Put,
+                        query,
+                        body,
+                        &RequestOptions::new(),
+                        true)
+    }
 
-        // We first need to find the specific project.
-        let project = self.get_project(namespace, name)
-            .chain_err(|| format!("cannot get project '{}/{}'", namespace, name))?;
+    fn _send_json<T, B>(&self,
+                        method: hyper::method::Method,
+                        query: &str,
+                        body: B,
+                        options: &RequestOptions,
+                        log_response: bool)
+                        -> Result<T>
+        where T: serde::Deserialize,
+              B: serde::Serialize
+    {
+        let url = self.build_url(query)
+            .chain_err(|| format!("failure to build url for query '{}'", query))?;
+        info!("url: {:?}", self.build_url_safe(query)?);
 
-        // Closure to search for the item, possibly returning multiple match on multiple pages.
-        let query_gitlab_closure = || self.merge_requests(project.id);
-        // Closure to find the right item in the found list on the page.
-        let iter_find_closure = |merge_request: &::merge_requests::MergeRequest| merge_request.iid == iid;
+        let body = serde_json::to_string(&body).chain_err(|| "cannot serialize body to JSON")?;
+        debug!("request body:\n{}", body);
 
-        self.get_paginated_from_project(query_gitlab_closure, iter_find_closure)
+        // A unit body (`()`) means "no body".
+        let request = if body == "null" {
+            Request::new(method, &url)
+        } else {
+            Request::json(method, &url, &body)
+        };
+
+        let res = self.send(request, options)
+            .chain_err(|| format!("cannot send request '{}' to {:?}", query, self))?;
+        info!("res.status: {:?}", res.status);
+        // Responses containing secrets (for example tokens) must not end up in the logs.
+        if log_response {
+            debug!("body:\n{}", res.body);
+        }
+
+        match res.status {
+            hyper::status::StatusCode::Ok |
+            hyper::status::StatusCode::Created => {}
+            status => bail!(status_error(status, &res.body)),
+        }
+
+        // Some actions answer without a body, read it as `null`.
+        let body = if res.body.trim().is_empty() { "null" } else { res.body.as_str() };
+
+        serde_json::from_str(body).chain_err(|| if log_response {
+            format!("cannot build Rust struct from JSON data: {}", res.body)
+        } else {
+            String::from("cannot build Rust struct from JSON data")
+        })
+    }
+
+    /// Perform an HTTP DELETE to the GitLab server from a specific query.
+    ///
+    /// The `query` is the same as for `get()`, for example `projects/:id/hooks/:hook_id`.
+    ///
+    /// This is the _strict_ version: deleting an item that does not exist (`404 Not Found`)
+    /// is reported as an error. See `delete_idempotent()` for the lenient version.
+    pub fn delete(&self, query: &str) -> Result<()> {
+        self._delete(query, false)
+    }
+
+    /// Perform an HTTP DELETE to the GitLab server from a specific query, ignoring missing items.
+    ///
+    /// Contrary to `delete()`, a `404 Not Found` is considered a success: the item is already
+    /// gone, which is what was asked for. Calling this method multiple times with the same
+    /// `query` thus always returns `Ok(())`, which is what reconciliation loops need.
+    ///
+    /// Any other non-success status code is still reported as an error.
+    pub fn delete_idempotent(&self, query: &str) -> Result<()> {
+        self._delete(query, true)
+    }
+
+    fn _delete(&self, query: &str, idempotent: bool) -> Result<()> {
+        let url = self.build_url(query)
+            .chain_err(|| format!("failure to build url for query '{}'", query))?;
+        info!("url: {:?}", self.build_url_safe(query)?);
+
+        let res = self.send(Request::new(hyper::method::Method::Delete, &url),
+                            &RequestOptions::new())
+            .chain_err(|| format!("cannot send request '{}' to {:?}", query, self))?;
+        info!("res.status: {:?}", res.status);
+
+        check_delete_status(&res, idempotent)
+    }
+
+    /// Send `request` through the transport, applying the `GitLab`'s request options
+    /// overridden by `options`.
+    ///
+    /// Failures to send the request or to receive its response are retried as configured.
+    fn send(&self, request: Request, options: &RequestOptions) -> Result<Response> {
+        self._send(request, options, false)?.into_response()
+    }
+
+    /// Same as `send()` but the response's body is left to be read by the caller.
+    ///
+    /// Only failures to send the request or to receive the response's headers are retried.
+    fn send_streaming(&self,
+                      request: Request,
+                      options: &RequestOptions)
+                      -> Result<StreamingResponse> {
+        self._send(request, options, true)
+    }
+
+    fn _send(&self,
+             mut request: Request,
+             options: &RequestOptions,
+             streaming: bool)
+             -> Result<StreamingResponse> {
+        let options = self.options.merge(options);
+        options.apply(&mut request);
+        if request.headers.get_raw("Accept").is_none() {
+            request.headers.set_raw("Accept", vec![DEFAULT_ACCEPT.as_bytes().to_vec()]);
+        }
+
+        let start = Instant::now();
+        let mut nb_retries = 0;
+        let mut nb_status_retries = 0;
+        loop {
+            if options.deadline_passed() {
+                bail!(ErrorKind::DeadlineExceeded);
+            }
+
+            let result = if streaming {
+                self.transport.send_streaming(request.clone())
+            } else {
+                self.transport.send(request.clone()).map(StreamingResponse::from)
+            };
+
+            match result {
+                Err(ref e) if nb_retries < options.retry_count() && is_idempotent(&request) => {
+                    nb_retries += 1;
+                    warn!("request failed ({}), retrying ({}/{})",
+                          e,
+                          nb_retries,
+                          options.retry_count());
+                }
+                result => {
+                    if let Ok(ref response) = result {
+                        *self.last_response_meta.lock().unwrap() =
+                            Some(ResponseMeta::from_headers(&response.headers));
+                        *self.rate_limit.lock().unwrap() =
+                            RateLimit::from_headers(&response.headers);
+
+                        if let Some(delay) =
+                               self.retry_delay(response, nb_status_retries, start.elapsed()) {
+                            // Don't wait for a retry which couldn't be sent anyway.
+                            if options.deadline_within(delay) {
+                                bail!(ErrorKind::DeadlineExceeded);
+                            }
+                            nb_status_retries += 1;
+                            warn!("status code '{}', retrying in {:?} ({}/{})",
+                                  response.status,
+                                  delay,
+                                  nb_status_retries,
+                                  self.retry.map_or(0, |retry| retry.max_retries));
+                            std::thread::sleep(delay);
+                            continue;
+                        }
+                    }
+                    return result;
+                }
+            }
+        }
+    }
+
+    /// How long to wait before sending again a request answered `response`, `None` if it must
+    /// not be retried.
+    fn retry_delay(&self,
+                   response: &StreamingResponse,
+                   nb_retries: u32,
+                   elapsed: Duration)
+                   -> Option<Duration> {
+        match self.retry {
+            Some(ref retry) if RetryConfig::is_retryable(response.status) => {
+                let retry_after = parse_header(&response.headers, "Retry-After")
+                    .map(Duration::from_secs);
+                retry.delay(nb_retries, retry_after, elapsed, &mut self.retry_rng.lock().unwrap())
+            }
+            _ => None,
+        }
+    }
+
+    /// Diagnostic information (`X-Request-Id` and `X-Gitlab-*` headers) of the last response
+    /// received from GitLab, whether the request succeeded or not.
+    ///
+    /// Returns `None` if no response was received yet.
+    pub fn last_response_meta(&self) -> Option<ResponseMeta> {
+        self.last_response_meta.lock().unwrap().clone()
+    }
+
+    /// The rate limit (`RateLimit-*` headers) announced by the last response received from
+    /// GitLab, to slow down before being answered `429 Too Many Requests`.
+    ///
+    /// Returns `None` if no response was received yet, or if the last one had no (or malformed)
+    /// rate limit headers.
+    pub fn rate_limit(&self) -> Option<RateLimit> {
+        self.rate_limit.lock().unwrap().clone()
+    }
+
+    /// Get GitLab's version.
+    ///
+    /// GitLab 11.0 removed the API v3: if it answers `404 Not Found`, the version is queried
+    /// through the API v4 instead.
+    pub fn version(&self) -> Result<::Version> {
+        let version = match self.get("version", None, None) {
+            Err(Error(ErrorKind::NotFound(_), _)) if self.api_version() == API_VERSION => {
+                self.get("../v4/version", None, None)
+            }
+            version => version,
+        };
+
+        version.chain_err(|| "cannot query 'version'")
+    }
+
+    /// Get GitLab's version, parsed as a semantic version.
+    ///
+    /// The version is only queried once and then cached for the lifetime of `self`.
+    pub fn server_version(&self) -> Result<semver::Version> {
+        let cached = self.server_version.lock().unwrap().clone();
+        if let Some(version) = cached {
+            return Ok(version);
+        }
+
+        let version = self.version()?.semver()?;
+        *self.server_version.lock().unwrap() = Some(version.clone());
+
+        Ok(version)
+    }
+
+    /// Does the GitLab server provide the API v4?
+    pub fn supports_api_v4(&self) -> Result<bool> {
+        Ok(::version::supports_api_v4(&self.server_version()?))
+    }
+
+    /// Does the GitLab server provide keyset pagination?
+    pub fn supports_keyset_pagination(&self) -> Result<bool> {
+        Ok(::version::supports_keyset_pagination(&self.server_version()?))
+    }
+
+    pub fn groups(&self) -> ::groups::GroupsLister {
+        ::groups::GroupsLister::new(self)
+    }
+
+    pub fn projects(&self) -> ::projects::ProjectsLister {
+        ::projects::ProjectsLister::new(self)
+    }
+
+    pub fn issues(&self) -> ::issues::IssuesLister {
+        ::issues::IssuesLister::new(self)
+    }
+
+    pub fn merge_requests(&self, project_id: i64) -> ::merge_requests::MergeRequestsLister {
+        ::merge_requests::MergeRequestsLister::new(self, project_id)
+    }
+
+    pub fn namespaces(&self) -> ::namespaces::NamespacesLister {
+        ::namespaces::NamespacesLister::new(self)
+    }
+
+    // pub fn groups(&mut self, listing: ::groups::Listing) -> Result<Groups, serde_json::Error> {
+    //     let query = listing.build_query();
+    //     // self.get(&query)
+    //     unimplemented!();
+    // }
+
+    // pub fn owned_groups(&mut self) -> Result<Groups, serde_json::Error> {
+    //     let query = ::groups::owned_groups::Listing::new().build_query();
+    //     info!("query: {:?}", query);
+    //     self.get(&query)
+    // }
+
+
+    /// Search for a (generic) GitLab item, iterating over all found match to get the proper one.
+    ///
+    /// This allows getting, for example, a specific issue from a specific project. The GitLab API
+    /// does not make this easy to do in a generic way, so we need to perform the search in a loop
+    /// until the proper item is found and returned.
+    ///
+    /// The pages are fetched (following GitLab's pagination headers) until a match is found.
+    /// Returns `None` if no item matches.
+    fn find_paginated_from_project<T, F, G, L>(&self,
+                                               item_search_closure: F,
+                                               iter_find_closure: G)
+                                               -> Result<Option<T>>
+        where F: Fn() -> L,
+              G: Fn(&<std::vec::IntoIter<T> as IntoIterator>::Item) -> bool,
+              L: Lister<Vec<T>>
+    {
+        // Query GitLab, one page at a time. Use a closure, passed as argument, to make this
+        // operation generic.
+        // To list project's issues:
+        // let lister = self.issues().project(id);
+        // To list project's merge requests:
+        // let lister = self.merge_requests(id);
+        // To get matching projects:
+        // let lister = self.projects().search(name);
+        let lister = item_search_closure();
+
+        for item in lister.paginated_iter(PAGINATED_SEARCH_PER_PAGE) {
+            let item = item.chain_err(|| {
+                    "cannot get item in GitLab::find_paginated_from_project()"
+                })?;
+
+            // Use the second closure passed as argument to find the right item.
+            if iter_find_closure(&item) {
+                return Ok(Some(item));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Search for (generic) GitLab items, iterating over all pages to get all the matching ones.
+    ///
+    /// This is the same as `find_paginated_from_project()` but instead of stopping at the first
+    /// match, all items for which `iter_filter_closure` returns `true` are returned.
+    fn get_all_paginated_from_project<T, F, G, L>(&self,
+                                                  item_search_closure: F,
+                                                  iter_filter_closure: G)
+                                                  -> Result<Vec<T>>
+        where F: Fn() -> L,
+              G: Fn(&T) -> bool,
+              L: Lister<Vec<T>>
+    {
+        let lister = item_search_closure();
+        let mut found: Vec<T> = Vec::new();
+
+        for item in lister.paginated_iter(PAGINATED_SEARCH_PER_PAGE) {
+            let item = item.chain_err(|| {
+                    "cannot get items in GitLab::get_all_paginated_from_project()"
+                })?;
+
+            // Keep the matching items.
+            if iter_filter_closure(&item) {
+                found.push(item);
+            }
+        }
+
+        Ok(found)
+    }
+
+    /// Get a specific "namespace/name" project.
+    ///
+    /// Same as `try_get_project()`, but a missing project is reported as
+    /// `ErrorKind::NotFound`.
+    pub fn get_project(&self, namespace: &str, name: &str) -> Result<::projects::Project> {
+        match self.try_get_project(namespace, name)? {
+            Some(project) => Ok(project),
+            None => bail!(ErrorKind::NotFound(format!("project '{}/{}'", namespace, name))),
+        }
+    }
+
+    /// Get a specific "namespace/name" project, `None` if there is no such project.
+    ///
+    /// The project is first requested directly through its full path (`namespace/name`). If it
+    /// is not found (for example because the project's `name` differs from its `path`), we fall
+    /// back to searching for it. Other failures (network, authentication...) are errors.
+    ///
+    /// NOTE: We can't search for "namespace/name", so we search for "name", and refine the match
+    ///       on the namespace. This means the operation could be slow as multiple query to the
+    ///       GitLab server might be required to find the right item.
+    pub fn try_get_project(&self,
+                           namespace: &str,
+                           name: &str)
+                           -> Result<Option<::projects::Project>> {
+
+        // Fast path: get the project from its full path.
+        let full_path = format!("{}/{}", namespace, name);
+        let listing_id = ::projects::ListingId::NamespaceProject(full_path.clone());
+        let query = self.projects().id(listing_id).build_query();
+        match self.get(&query, None, None) {
+            Ok(project) => return Ok(Some(project)),
+            Err(Error(ErrorKind::NotFound(_), _)) => {
+                info!("project '{}' not found directly, searching for it", full_path)
+            }
+            Err(e) => return Err(e).chain_err(|| format!("cannot get project '{}'", full_path)),
+        }
+
+        // Closure to search for the item, possibly returning multiple match on multiple pages.
+        let query_gitlab_closure = || self.projects().search(name.to_string());
+        // Closure to find the right item in the found list on the page.
+        let iter_find_closure =
+            |project: &::projects::Project| project_matches(project, Some(namespace), name);
+
+        self.find_paginated_from_project(query_gitlab_closure, iter_find_closure)
+    }
+
+    /// Find all projects named `name`, optionally restricted to the `namespace`.
+    ///
+    /// Contrary to `get_project()`, all the matching projects are returned (possibly from
+    /// multiple namespaces) instead of only the first one, letting the caller disambiguate.
+    ///
+    /// Like `get_project()`, this _can_ be slow since all search result pages are fetched.
+    pub fn find_projects(&self,
+                         namespace: Option<&str>,
+                         name: &str)
+                         -> Result<::projects::Projects> {
+        // Closure to search for the items, possibly returning multiple match on multiple pages.
+        let query_gitlab_closure = || self.projects().search(name.to_string());
+        // Closure to keep the right items in the found list on the page.
+        let iter_filter_closure =
+            |project: &::projects::Project| project_matches(project, namespace, name);
+
+        self.get_all_paginated_from_project(query_gitlab_closure, iter_filter_closure)
+    }
+
+    /// Get a project issue from a its project's `namespace` and `name` and the issue's `iid`.
+    ///
+    /// Same as `try_get_issue()`, but a missing project or issue is reported as
+    /// `ErrorKind::NotFound`.
+    pub fn get_issue(&self, namespace: &str, name: &str, iid: i64) -> Result<::issues::Issue> {
+        match self.try_get_issue(namespace, name, iid)? {
+            Some(issue) => Ok(issue),
+            None => {
+                bail!(ErrorKind::NotFound(format!("issue {} of project '{}/{}'",
+                                                  iid,
+                                                  namespace,
+                                                  name)))
+            }
+        }
+    }
+
+    /// Get a project issue from a its project's `namespace` and `name` and the issue's `iid`,
+    /// `None` if there is no such project or issue.
+    ///
+    /// Since GitLab uses unique `id`s in its API and _not_ `iid`s, we will need to list issues
+    /// (grouped by pages of 20) until we find the proper issue matching the `id` requested.
+    ///
+    /// **Note**: A `iid` is the issue number as seen by normal user, for example appearing on
+    /// a GitLab URL. This `iid` can be used to reference an issue (in other issues, in commit
+    /// messages, etc.) by prepending a pound sign to it, for example `#3`. An `id`, instead, is
+    /// GitLab's internal and unique id associated with the issue.
+    ///
+    /// Because we need to search (and thus query the GitLab server possibly multiple times), this
+    /// _can_ be a slow operation if there is many issues in the project.
+    pub fn try_get_issue(&self,
+                         namespace: &str,
+                         name: &str,
+                         iid: i64)
+                         -> Result<Option<::issues::Issue>> {
+        // We first need to find the specific project.
+        let project = self.try_get_project(namespace, name)
+            .chain_err(|| format!("cannot get project '{}/{}'", namespace, name))?;
+        let project = match project {
+            Some(project) => project,
+            None => return Ok(None),
+        };
+
+        // Closure to search for the item, possibly returning multiple match on multiple pages.
+        let query_gitlab_closure = || self.issues().project(project.id);
+        // Closure to find the right item in the found list on the page.
+        let iter_find_closure = |issue: &::issues::Issue| issue.iid == iid;
+
+        self.find_paginated_from_project(query_gitlab_closure, iter_find_closure)
+    }
+
+    /// Get a project merge request from a its project's `namespace` and `name` and
+    /// the merge request's `iid`.
+    ///
+    /// Same as `try_get_merge_request()`, but a missing project or merge request is reported as
+    /// `ErrorKind::NotFound`.
+    pub fn get_merge_request(&self,
+                             namespace: &str,
+                             name: &str,
+                             iid: i64)
+                             -> Result<::merge_requests::MergeRequest> {
+        match self.try_get_merge_request(namespace, name, iid)? {
+            Some(merge_request) => Ok(merge_request),
+            None => {
+                bail!(ErrorKind::NotFound(format!("merge request {} of project '{}/{}'",
+                                                  iid,
+                                                  namespace,
+                                                  name)))
+            }
+        }
+    }
+
+    /// Close the issue `issue_id` of project `project_id`.
+    ///
+    /// `issue_id` identifies the issue in its path: it is its `id` with the API v3, its `iid`
+    /// with the API v4 (see `api_version()`). Returns the closed issue.
+    pub fn close_issue(&self, project_id: i64, issue_id: i64) -> Result<::issues::Issue> {
+        self.issues()
+            .edit(project_id, issue_id)
+            .state_event(::issues::StateEvent::Close)
+            .update()
+            .chain_err(|| format!("cannot close issue {} of project {}", issue_id, project_id))
+    }
+
+    /// Reopen the closed issue `issue_id` of project `project_id`.
+    ///
+    /// See `close_issue()` for the meaning of `issue_id`. Returns the reopened issue.
+    pub fn reopen_issue(&self, project_id: i64, issue_id: i64) -> Result<::issues::Issue> {
+        self.issues()
+            .edit(project_id, issue_id)
+            .state_event(::issues::StateEvent::Reopen)
+            .update()
+            .chain_err(|| format!("cannot reopen issue {} of project {}", issue_id, project_id))
+    }
+
+    /// Get a project merge request from a its project's `namespace` and `name` and
+    /// the merge request's `iid`, `None` if there is no such project or merge request.
+    ///
+    /// **Note**: A `iid` is the merge request number as seen by normal user, for example
+    /// appearing on a GitLab URL. This `iid` can be used to reference a merge request (in issues,
+    /// in commit messages, etc.) by prepending an exclamation mark to it, for example `!3`. An
+    /// `id`, instead, is GitLab's internal and unique id associated with the merge request.
+    ///
+    /// The API v3 only accepts `id`s in the merge request's path, so the merge request is fetched
+    /// using the `iid` filter: `GET /projects/:id/merge_requests?iid=:iid` (`iids[]=:iid` with the
+    /// API v4). Contrary to scanning all the project's merge requests, this is a single query
+    /// whatever the project's size.
+    pub fn try_get_merge_request(&self,
+                                 namespace: &str,
+                                 name: &str,
+                                 iid: i64)
+                                 -> Result<Option<::merge_requests::MergeRequest>> {
+
+        // We first need to find the specific project.
+        let project = self.try_get_project(namespace, name)
+            .chain_err(|| format!("cannot get project '{}/{}'", namespace, name))?;
+        let project = match project {
+            Some(project) => project,
+            None => return Ok(None),
+        };
+
+        self.try_merge_request_by_iid(project.id, iid)
+    }
+
+    fn merge_request_by_iid(&self,
+                            project_id: i64,
+                            iid: i64)
+                            -> Result<::merge_requests::MergeRequest> {
+        let merge_request = self.try_merge_request_by_iid(project_id, iid)?
+            .ok_or_else(|| format!("no merge request {} in project {}", iid, project_id))?;
+
+        Ok(merge_request)
+    }
+
+    fn try_merge_request_by_iid(&self,
+                                project_id: i64,
+                                iid: i64)
+                                -> Result<Option<::merge_requests::MergeRequest>> {
+        let merge_requests = self.merge_requests(project_id)
+            .iid(vec![iid])
+            .list()
+            .chain_err(|| format!("cannot get merge request {} of project {}", iid, project_id))?;
+
+        Ok(merge_requests.into_iter().next())
+    }
+
+    /// The ID identifying `merge_request` in the path of its queries: its `iid` with the API v4,
+    /// its `id` with the API v3.
+    fn merge_request_path_id(&self, merge_request: &::merge_requests::MergeRequest) -> i64 {
+        if self.api_version() >= 4 {
+            merge_request.iid
+        } else {
+            merge_request.id
+        }
+    }
+
+    /// Update the merge request `iid` of project `project_id`: only the fields set in `update`
+    /// are changed.
+    ///
+    /// Returns the updated merge request.
+    pub fn update_merge_request(&self,
+                                project_id: i64,
+                                iid: i64,
+                                update: ::merge_requests::MergeRequestUpdate)
+                                -> Result<::merge_requests::MergeRequest> {
+        let merge_request_id = if self.api_version() >= 4 {
+            iid
+        } else {
+            // API v3 identifies the merge request by its `id`, not its `iid`.
+            self.merge_request_by_iid(project_id, iid)?.id
+        };
+
+        self.merge_requests(project_id)
+            .single(merge_request_id)
+            .update(&update)
+            .chain_err(|| format!("cannot update merge request {} of project {}", iid, project_id))
+    }
+
+    /// Close the merge request `iid` of project `project_id`.
+    ///
+    /// Closing an already closed merge request does nothing. Returns the closed merge request.
+    pub fn close_merge_request(&self,
+                               project_id: i64,
+                               iid: i64)
+                               -> Result<::merge_requests::MergeRequest> {
+        self.set_merge_request_state(project_id,
+                                     iid,
+                                     ::merge_requests::StateEvent::Close,
+                                     ::merge_requests::State::Closed)
+    }
+
+    /// Reopen the closed merge request `iid` of project `project_id`.
+    ///
+    /// Reopening an already opened merge request does nothing. Returns the reopened merge
+    /// request.
+    pub fn reopen_merge_request(&self,
+                                project_id: i64,
+                                iid: i64)
+                                -> Result<::merge_requests::MergeRequest> {
+        self.set_merge_request_state(project_id,
+                                     iid,
+                                     ::merge_requests::StateEvent::Reopen,
+                                     ::merge_requests::State::Opened)
+    }
+
+    fn set_merge_request_state(&self,
+                               project_id: i64,
+                               iid: i64,
+                               state_event: ::merge_requests::StateEvent,
+                               state: ::merge_requests::State)
+                               -> Result<::merge_requests::MergeRequest> {
+        let merge_request = self.merge_request_by_iid(project_id, iid)?;
+        if merge_request.state == state {
+            return Ok(merge_request);
+        }
+
+        let update = ::merge_requests::MergeRequestUpdate {
+            state_event: Some(state_event),
+            ..Default::default()
+        };
+        self.merge_requests(project_id)
+            .single(self.merge_request_path_id(&merge_request))
+            .update(&update)
+            .chain_err(|| {
+                format!("cannot {:?} merge request {} of project {}",
+                        state_event,
+                        iid,
+                        project_id)
+            })
+    }
+
+    /// Accept the merge request `iid` of project `project_id` once its pipeline succeeds, then
+    /// wait for GitLab to merge it.
+    ///
+    /// Polling stops when the merge request is merged or closed, when GitLab cancels the
+    /// automatic merge (e.g. the pipeline failed) or after `timeout`, which is an error.
+    ///
+    /// Returns the merge request in its final state: check its `state` to know if it got merged.
+    pub fn merge_when_ready(&self,
+                            project_id: i64,
+                            iid: i64,
+                            timeout: std::time::Duration)
+                            -> Result<::merge_requests::MergeRequest> {
+        let merge_request = self.merge_request_by_iid(project_id, iid)?;
+
+        let lister = self.merge_requests(project_id)
+            .single(self.merge_request_path_id(&merge_request));
+        let opts = ::merge_requests::single::AcceptOpts {
+            merge_when_build_succeeds: Some(true),
+            ..Default::default()
+        };
+        lister.accept(&opts)
+            .chain_err(|| format!("cannot accept merge request {} of project {}", iid, project_id))?;
+
+        lister.wait_until_settled(timeout)
+    }
+
+    /// Count the open issues, the open merge requests and the successful pipelines of project
+    /// `project_id`, for an overview of the project.
+    ///
+    /// Each count costs a request fetching a single item (see `count()`). The three requests are
+    /// sent concurrently.
+    pub fn dashboard_counts(&self, project_id: i64) -> Result<::DashboardCounts> {
+        let issues_query = {
+            let mut lister = self.issues().project(project_id);
+            lister.state(::issues::ListingState::Opened).build_query()
+        };
+        let merge_requests_query = {
+            let mut lister = self.merge_requests(project_id);
+            lister.state(::merge_requests::State::Opened).build_query()
+        };
+        let pipelines_query = {
+            let mut lister = self.projects().id(::projects::ListingId::Id(project_id)).pipelines();
+            lister.status(::projects::id_pipelines::PipelineStatus::Success).build_query()
+        };
+
+        let count = |query: &str| {
+            self.count(query)
+                .chain_err(|| format!("cannot count query {}", query))
+        };
+        let (open_issues, open_merge_requests, successful_pipelines) = crossbeam::scope(|scope| {
+            let open_issues = scope.spawn(|| count(&issues_query));
+            let open_merge_requests = scope.spawn(|| count(&merge_requests_query));
+            let successful_pipelines = count(&pipelines_query);
+
+            (open_issues.join(), open_merge_requests.join(), successful_pipelines)
+        });
+
+        Ok(::DashboardCounts {
+            open_issues: open_issues?,
+            open_merge_requests: open_merge_requests?,
+            successful_pipelines: successful_pipelines?,
+        })
+    }
+
+    /// Create the project described by `project`, owned by the user `user_id`.
+    ///
+    /// Only administrators can create projects for other users.
+    pub fn create_project_for_user(&self,
+                                   user_id: i64,
+                                   project: &::projects::create::ProjectCreator)
+                                   -> Result<::projects::Project> {
+        let query = project.build_user_query(user_id);
+        debug!("query: {:?}", query);
+
+        match self.post(&query, project) {
+            Err(e @ Error(ErrorKind::Forbidden(_), _)) => {
+                Err(e).chain_err(|| {
+                    format!("cannot create project for user {}: only administrators can create \
+                             projects for other users",
+                            user_id)
+                })
+            }
+            result => result.chain_err(|| format!("cannot create project for user {}", user_id)),
+        }
+    }
+
+    /// Create an impersonation token `name` for the user `user_id` and return it.
+    ///
+    /// `scopes` are for example `api` or `read_user`; `expires_at` is a date (`YYYY-MM-DD`).
+    /// Only administrators can create impersonation tokens.
+    ///
+    /// The token is a secret: the response carrying it is not logged.
+    pub fn create_impersonation_token(&self,
+                                      user_id: i64,
+                                      name: &str,
+                                      scopes: Vec<String>,
+                                      expires_at: Option<String>)
+                                      -> Result<String> {
+        let creator = ::users::ImpersonationTokenCreator::new(user_id,
+                                                              name.to_string(),
+                                                              scopes,
+                                                              expires_at);
+        let query = creator.build_query();
+        debug!("query: {:?}", query);
+
+        let result: Result<::users::ImpersonationToken> =
+            self._send_json(hyper::method::Method::Post,
+                            &query,
+                            &creator,
+                            &RequestOptions::new(),
+                            false);
+        match result {
+            Ok(token) => Ok(token.token),
+            Err(e @ Error(ErrorKind::Forbidden(_), _)) => {
+                Err(e).chain_err(|| {
+                    format!("cannot create impersonation token for user {}: only administrators \
+                             can create impersonation tokens",
+                            user_id)
+                })
+            }
+            Err(e) => {
+                Err(e).chain_err(|| {
+                    format!("cannot create impersonation token for user {}", user_id)
+                })
+            }
+        }
+    }
+
+    /// Create the project `name` in the namespace (user or group) `namespace_path`, for example
+    /// `group/subgroup`.
+    ///
+    /// The namespace's ID is first looked up, failing if no namespace has this exact path.
+    /// Since GitLab only searches the namespaces' names and paths, the namespaces found for the
+    /// last component of `namespace_path` are compared to it, going through all the pages.
+    pub fn create_project_in(&self,
+                             namespace_path: &str,
+                             name: &str)
+                             -> Result<::projects::Project> {
+        let last_component = namespace_path.rsplit('/').next().unwrap_or(namespace_path);
+        let mut lister = self.namespaces();
+        lister.search(last_component.to_string());
+
+        let mut namespace_id = None;
+        for namespace in lister.iter() {
+            let namespace: ::namespaces::Namespace =
+                namespace.chain_err(|| format!("cannot look up namespace '{}'", namespace_path))?;
+            if namespace.has_path(namespace_path) {
+                namespace_id = Some(namespace.id);
+                break;
+            }
+        }
+        let namespace_id = match namespace_id {
+            Some(namespace_id) => namespace_id,
+            None => bail!(format!("namespace '{}' not found", namespace_path)),
+        };
+
+        let mut creator = self.projects().create(name);
+
+        creator.namespace_id(namespace_id)
+            .create()
+            .chain_err(|| {
+                format!("cannot create project '{}' in namespace '{}'", name, namespace_path)
+            })
+    }
+
+    /// Create a commit in the project `project_id`, applying multiple file actions at once.
+    ///
+    /// This allows committing files without having to clone the repository.
+    pub fn create_commit(&self,
+                         project_id: i64,
+                         commit: ::projects::CommitCreate)
+                         -> Result<::projects::RepoCommit> {
+        self.projects()
+            .id(::projects::ListingId::Id(project_id))
+            .commits()
+            .create(&commit)
+            .chain_err(|| format!("cannot create commit in project {}", project_id))
+    }
+}
+
+/// The reason of an error, from the `message` (or `error`) field of a JSON body, else the body.
+fn error_reason(body: &str) -> String {
+    let value: serde_json::Value = match serde_json::from_str(body) {
+        Ok(value) => value,
+        Err(_) => return body.trim().to_string(),
+    };
+
+    match value.get("message").or_else(|| value.get("error")) {
+        Some(&serde_json::Value::String(ref reason)) => reason.clone(),
+        // Validation errors are objects, for example `{"name": ["has already been taken"]}`.
+        Some(reason) => reason.to_string(),
+        None => body.trim().to_string(),
+    }
+}
+
+/// The error reporting a response with the (unexpected) `status` and `body`.
+fn status_error(status: hyper::status::StatusCode, body: &str) -> ErrorKind {
+    match status {
+        hyper::status::StatusCode::BadRequest => ErrorKind::BadRequest(error_reason(body)),
+        hyper::status::StatusCode::Unauthorized => ErrorKind::Unauthorized(error_reason(body)),
+        hyper::status::StatusCode::Forbidden => ErrorKind::Forbidden(error_reason(body)),
+        hyper::status::StatusCode::NotFound => ErrorKind::NotFound(error_reason(body)),
+        hyper::status::StatusCode::TooManyRequests => ErrorKind::RateLimited,
+        status if status.is_server_error() => ErrorKind::ServerError(status.to_u16()),
+        status => ErrorKind::UnexpectedStatus(status.to_u16()),
+    }
+}
+
+/// Warn if GitLab paginated the response to a query which did not ask for a specific page.
+///
+/// `list()` only returns the first page: the other items would otherwise silently be missing.
+fn warn_if_truncated(query: &str, headers: &hyper::header::Headers) {
+    if let Some(next_page) = ::PageInfo::from_headers(headers).next_page {
+        warn!("results of query '{}' truncated to the first page (next page: {}), use a \
+               paginated listing to get all of them",
+              query,
+              next_page);
+    }
+}
+
+/// Verify if a `project` is named `name` and, if given, is part of the `namespace`.
+///
+/// The `namespace` can either be its name or its path.
+fn project_matches(project: &::projects::Project, namespace: Option<&str>, name: &str) -> bool {
+    let namespace_matches = match namespace {
+        None => true,
+        Some(namespace) => {
+            project.namespace.name == namespace || project.namespace.path == namespace
+        }
+    };
+
+    namespace_matches && project.name == name
+}
+
+/// Deserialize the JSON `body` of the response to `query` while it is read.
+fn parse_json<T, R>(query: &str, body: R) -> Result<T>
+    where T: serde::Deserialize,
+          R: Read
+{
+    serde_json::from_reader(body)
+        .chain_err(|| format!("cannot build Rust struct from JSON data of query '{}'", query))
+}
+
+/// Deserialize a JSON string both into a GitLab type and into a raw `serde_json::Value`.
+fn parse_with_raw<T>(body: &str) -> Result<(T, serde_json::Value)>
+    where T: serde::Deserialize
+{
+    let raw: serde_json::Value = serde_json::from_str(body)
+        .chain_err(|| format!("cannot parse JSON data: {}", body))?;
+    let typed: T = serde_json::from_value(raw.clone())
+        .chain_err(|| format!("cannot build Rust struct from JSON data: {}", body))?;
+
+    Ok((typed, raw))
+}
+
+/// Whether `status` asks to follow a redirect to the `Location` header.
+fn is_redirect(status: hyper::status::StatusCode) -> bool {
+    match status {
+        hyper::status::StatusCode::MovedPermanently |
+        hyper::status::StatusCode::Found |
+        hyper::status::StatusCode::SeeOther |
+        hyper::status::StatusCode::TemporaryRedirect |
+        hyper::status::StatusCode::PermanentRedirect => true,
+        _ => false,
+    }
+}
+
+/// Build the URL to request when `url` got redirected to `location`.
+///
+/// Redirects are only followed on the same host and port, in which case the private token is
+/// attached to the new URL. Redirects to another host or port are refused so the token is never
+/// sent there, and so are redirects from `https` to `http` so it is never sent in cleartext.
+///
+/// The only port change allowed is the upgrade from `http` to `https` on the default ports.
+fn redirect_target(url: &str, location: &str, private_token: &str) -> Result<String> {
+    let url = url::Url::parse(url).chain_err(|| "cannot parse the redirected URL")?;
+    // `location` might be relative.
+    let target = url.join(location).chain_err(|| "cannot parse the redirect's location")?;
+
+    if target.host_str() != url.host_str() {
+        bail!(format!("refusing to follow redirect from host '{}' to host '{}'",
+                      url.host_str().unwrap_or(""),
+                      target.host_str().unwrap_or("")));
+    }
+    let upgrade = match (url.scheme(), target.scheme()) {
+        ("http", "http") | ("https", "https") => false,
+        ("http", "https") => true,
+        ("https", "http") => bail!("refusing to follow redirect from https to http"),
+        (_, scheme) => bail!(format!("refusing to follow redirect to scheme '{}'", scheme)),
+    };
+    let same_port = target.port_or_known_default() == url.port_or_known_default();
+    let default_ports = url.port().is_none() && target.port().is_none();
+    if !same_port && !(upgrade && default_ports) {
+        bail!(format!("refusing to follow redirect from port {} to port {}",
+                      url.port_or_known_default().unwrap_or(0),
+                      target.port_or_known_default().unwrap_or(0)));
+    }
+
+    // Make sure the token is there exactly once.
+    let pairs: Vec<(String, String)> = target.query_pairs()
+        .filter(|&(ref key, _)| key != "private_token")
+        .map(|(key, value)| (key.into_owned(), value.into_owned()))
+        .collect();
+    let mut target = target;
+    target.query_pairs_mut()
+        .clear()
+        .extend_pairs(pairs)
+        .append_pair("private_token", private_token);
+
+    Ok(target.into_string())
+}
+
+/// Make sure a response's `Content-Type` is JSON.
+///
+/// Parameters like `charset=utf-8` are ignored. A missing header is accepted since there
+/// is nothing to contradict the body being JSON.
+fn check_json_content_type(content_type: Option<&str>) -> Result<()> {
+    let content_type = match content_type {
+        Some(content_type) => content_type,
+        None => return Ok(()),
+    };
+
+    let mime = content_type.split(';').next().unwrap_or("").trim();
+    if !mime.eq_ignore_ascii_case("application/json") {
+        bail!(format!("content type '{}', not 'application/json' (is the GitLab url or token \
+                       wrong?)",
+                      content_type));
+    }
+
+    Ok(())
+}
+
+
+/// Whether `request` can safely be sent again after a transport error (see
+/// `RequestOptions::retries()`).
+fn is_idempotent(request: &Request) -> bool {
+    match request.method {
+        hyper::method::Method::Get |
+        hyper::method::Method::Head |
+        hyper::method::Method::Delete => true,
+        _ => false,
+    }
+}
+
+
+/// Verify the status code returned by GitLab after a DELETE.
+///
+/// When `idempotent` is `true`, a `404 Not Found` is a success since the item is already gone.
+fn check_delete_status(res: &Response, idempotent: bool) -> Result<()> {
+    match res.status {
+        hyper::status::StatusCode::Ok |
+        hyper::status::StatusCode::Accepted |
+        hyper::status::StatusCode::NoContent => Ok(()),
+        hyper::status::StatusCode::NotFound if idempotent => {
+            info!("item to delete not found, considering it already deleted");
+            Ok(())
+        }
+        status => bail!(status_error(status, &res.body)),
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std;
+    use std::cell::RefCell;
+    use std::fmt;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use hyper;
+    use log;
+    use serde_json;
+    use url;
+
+    use gitlab::*;
+    use errors::{Error, ErrorKind};
+    use Lister;
+    use PageInfo;
+    use tests::mock_gitlab;
+    use transport::{MockTransport, RequestOptions, Response, RetryConfig};
+
+    fn verify_ok<T>(result: &Result<T>) {
+        if let &Err(ref e) = result {
+            println!("error: {}", e);
+
+            for e in e.iter().skip(1) {
+                println!("caused by: {}", e);
+            }
+
+            // The backtrace is not always generated. Try to run this example
+            // with `RUST_BACKTRACE=1`.
+            if let Some(backtrace) = e.backtrace() {
+                println!("backtrace: {:?}", backtrace);
+            }
+        }
+        assert!(result.is_ok());
+    }
+
+    fn verify_err<T>(result: &Result<T>)
+        where T: fmt::Debug
+    {
+        match result {
+            &Err(_) => {
+                // pass
+            }
+            &Ok(ref t) => {
+                panic!(format!("Expected an Err(), got an Ok(t), with t: {:?}", t));
+            }
+        }
+    }
+
+    #[test]
+    fn impl_debug_for_gitlab() {
+        let gl = GitLab::new("gitlab.com", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+
+        let debug = format!("{:?}", gl);
+        assert_eq!("GitLab { scheme: https, domain: gitlab.com, port: no port provided, \
+                    private_token: XXXXXXXXXXXXXXXXXXXX }",
+                   debug);
+
+        let gl = gl.scheme("http").unwrap().port(80).unwrap();
+        let debug = format!("{:?}", gl);
+        assert_eq!("GitLab { scheme: http, domain: gitlab.com, port: no port provided, \
+                    private_token: XXXXXXXXXXXXXXXXXXXX }",
+                   debug);
+
+        let gl = gl.port(81).unwrap();
+        let debug = format!("{:?}", gl);
+        assert_eq!("GitLab { scheme: http, domain: gitlab.com, port: 81, private_token: \
+                    XXXXXXXXXXXXXXXXXXXX }",
+                   debug);
+    }
+
+    #[test]
+    fn gitlab_is_send_and_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+
+        assert_send_sync::<GitLab>();
+    }
+
+    #[test]
+    fn unsupported_scheme() {
+        let gl = GitLab::new("gitlab.com", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+        verify_err(&gl.scheme("ftp"));
+
+        let gl = GitLab::new("gitlab.com", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+        verify_err(&gl.scheme(""));
+
+        let gl = GitLab::new("gitlab.com", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+        verify_ok(&gl.scheme("https"));
+    }
+
+    #[test]
+    fn port_on_url_without_host() {
+        let mut url = url::Url::parse("mailto:admin@example.com").unwrap();
+        verify_err(&set_url_port(&mut url, 80));
+
+        let mut url = url::Url::parse("https://gitlab.com/api/v3/").unwrap();
+        verify_ok(&set_url_port(&mut url, 8443));
+        assert_eq!(url.port(), Some(8443));
+    }
+
+    #[test]
+    fn gitlab_listers_groups() {
+        let gl = GitLab::new("gitlab.com", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+        let groups_lister = gl.groups();
+        let debug = format!("{:?}", groups_lister);
+        assert_eq!("GroupsLister { gl: GitLab { scheme: https, domain: gitlab.com, port: no \
+                    port provided, private_token: XXXXXXXXXXXXXXXXXXXX }, \
+                    internal: GroupsListerInternal { skip_groups: None, all_available: None, \
+                    search: None, order_by: None, sort: None } }",
+                   debug);
+    }
+
+    #[test]
+    fn gitlab_listers_projects() {
+        let gl = GitLab::new("gitlab.com", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+        let projects_lister = gl.projects();
+        let debug = format!("{:?}", projects_lister);
+        assert_eq!("ProjectsLister { gl: GitLab { scheme: https, domain: gitlab.com, port: no \
+                    port provided, private_token: XXXXXXXXXXXXXXXXXXXX }, \
+                    internal: ProjectListerInternal { archived: None, visibility: None, \
+                    order_by: None, sort: None, search: None, simple: None, topic: None, \
+                    id_after: None, id_before: None, last_activity_after: None, \
+                    last_activity_before: None } }",
+                   debug);
+    }
+
+    #[test]
+    fn gitlab_listers_issues() {
+        let gl = GitLab::new("gitlab.com", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+        let issues_lister = gl.issues();
+        let debug = format!("{:?}", issues_lister);
+        assert_eq!("IssuesLister { gl: GitLab { scheme: https, domain: gitlab.com, port: no port \
+                    provided, private_token: XXXXXXXXXXXXXXXXXXXX }, internal: \
+                    IssuesListerInternal { state: None, labels: None, order_by: None, sort: None \
+                    } }",
+                   debug);
+    }
+
+    #[test]
+    fn new_valid() {
+        let gl = GitLab::new("gitlab.com", "XXXXXXXXXXXXXXXXXXXX");
+        verify_ok(&gl);
+
+        let gl = GitLab::new_insecure("gitlab.com", "XXXXXXXXXXXXXXXXXXXX");
+        verify_ok(&gl);
+
+        let gl = GitLab::new("localhost", "XXXXXXXXXXXXXXXXXXXX");
+        verify_ok(&gl);
+
+        let gl = GitLab::new_insecure("localhost", "XXXXXXXXXXXXXXXXXXXX");
+        verify_ok(&gl);
+    }
+
+    #[test]
+    fn new_invalid_url_1() {
+        let gl = GitLab::new("", "XXXXXXXXXXXXXXXXXXXX");
+        verify_err(&gl);
+
+        let gl = GitLab::new_insecure("", "XXXXXXXXXXXXXXXXXXXX");
+        verify_err(&gl);
+    }
+
+    #[test]
+    fn new_invalid_url_2() {
+        let gl = GitLab::new("gitla/b.com", "XXXXXXXXXXXXXXXXXXXX");
+        verify_err(&gl);
+
+        let gl = GitLab::new_insecure("gitla/b.com", "XXXXXXXXXXXXXXXXXXXX");
+        verify_err(&gl);
+    }
+
+    #[test]
+    fn new_invalid_url_3() {
+        let gl = GitLab::new("/gitlab.com", "XXXXXXXXXXXXXXXXXXXX");
+        verify_err(&gl);
+
+        let gl = GitLab::new_insecure("/gitlab.com", "XXXXXXXXXXXXXXXXXXXX");
+        verify_err(&gl);
+    }
+
+    #[test]
+    fn new_invalid_url_4() {
+        let gl = GitLab::new("http:/gitlab.com", "XXXXXXXXXXXXXXXXXXXX");
+        verify_err(&gl);
+
+        let gl = GitLab::new_insecure("http:/gitlab.com", "XXXXXXXXXXXXXXXXXXXX");
+        verify_err(&gl);
+    }
+
+    #[test]
+    fn new_invalid_url_5() {
+        let gl = GitLab::new("http:///gitlab.com", "XXXXXXXXXXXXXXXXXXXX");
+        verify_err(&gl);
+
+        let gl = GitLab::new_insecure("http:///gitlab.com", "XXXXXXXXXXXXXXXXXXXX");
+        verify_err(&gl);
+    }
+
+    #[test]
+    fn new_invalid_url_6() {
+        let gl = GitLab::new(".gitlab", "XXXXXXXXXXXXXXXXXXXX");
+        verify_err(&gl);
+
+        let gl = GitLab::new_insecure(".gitlab", "XXXXXXXXXXXXXXXXXXXX");
+        verify_err(&gl);
+    }
+
+    #[test]
+    fn new_invalid_url_7() {
+        let gl = GitLab::new(".gitlab.com", "XXXXXXXXXXXXXXXXXXXX");
+        verify_err(&gl);
+
+        let gl = GitLab::new_insecure(".gitlab.com", "XXXXXXXXXXXXXXXXXXXX");
+        verify_err(&gl);
+    }
+
+    #[test]
+    fn new_invalid_url_8() {
+        let gl = GitLab::new("gitlab.", "XXXXXXXXXXXXXXXXXXXX");
+        verify_err(&gl);
+
+        let gl = GitLab::new_insecure("gitlab.", "XXXXXXXXXXXXXXXXXXXX");
+        verify_err(&gl);
+    }
+
+    #[test]
+    fn new_invalid_url_10() {
+        let gl = GitLab::new("gitlab.com.", "XXXXXXXXXXXXXXXXXXXX");
+        verify_err(&gl);
+
+        let gl = GitLab::new_insecure("gitlab.com.", "XXXXXXXXXXXXXXXXXXXX");
+        verify_err(&gl);
+    }
+
+    #[test]
+    fn new_invalid_token() {
+        let gl = GitLab::new("gitlab.com", "");
+        verify_err(&gl);
+
+        let gl = GitLab::new("gitlab.com", "X");
+        verify_err(&gl);
+
+        let gl = GitLab::new("gitlab.com", "XXXXXXXXXXXXXXXXXXX");
+        verify_err(&gl);
+
+        let gl = GitLab::new("gitlab.com", "XXXXXXXXXXXXXXXXXXXXX");
+        verify_err(&gl);
+    }
+
+    #[test]
+    fn build_url_doc() {
+        let expected_url = "https://gitlab.example.com\
+                            /api/v3/groups?order_by=path&private_token=XXXXXXXXXXXXXXXXXXXX";
+        let gl = GitLab::new("gitlab.example.com", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+        let url = gl.build_url("groups?order_by=path").unwrap();
+        assert_eq!(url, expected_url);
+    }
+
+    #[test]
+    fn build_url_safe() {
+        let gl = GitLab::new("gitlab.example.com", "ABCDEFGHIJKLMNOPQRST").unwrap();
+
+        let url = gl.build_url_safe("groups?order_by=path").unwrap();
+        assert_eq!(url, "https://gitlab.example.com/api/v3/groups?order_by=path");
+        assert!(!url.contains("ABCDEFGHIJKLMNOPQRST"));
+        assert!(!url.contains("private_token"));
+
+        let url = gl.build_url_safe("version").unwrap();
+        assert_eq!(url, "https://gitlab.example.com/api/v3/version");
+    }
+
+
+    #[test]
+    fn json_content_type() {
+        assert!(check_json_content_type(Some("application/json")).is_ok());
+        assert!(check_json_content_type(Some("application/json; charset=utf-8")).is_ok());
+        assert!(check_json_content_type(Some("Application/JSON;charset=UTF-8")).is_ok());
+        assert!(check_json_content_type(None).is_ok());
+    }
+
+
+    #[test]
+    fn json_content_type_html() {
+        let err = check_json_content_type(Some("text/html; charset=utf-8")).unwrap_err();
+        assert!(err.to_string().contains("text/html"));
+
+        assert!(check_json_content_type(Some("text/plain")).is_err());
+        assert!(check_json_content_type(Some("")).is_err());
+    }
+
+
+    #[test]
+    fn delete_status_strict() {
+        use hyper::status::StatusCode;
+
+        verify_ok(&check_delete_status(&Response::new(StatusCode::Ok), false));
+        verify_ok(&check_delete_status(&Response::new(StatusCode::Accepted), false));
+        verify_ok(&check_delete_status(&Response::new(StatusCode::NoContent), false));
+        verify_err(&check_delete_status(&Response::new(StatusCode::NotFound), false));
+        verify_err(&check_delete_status(&Response::new(StatusCode::Forbidden), false));
+    }
+
+    #[test]
+    fn delete_status_idempotent() {
+        use hyper::status::StatusCode;
+
+        verify_ok(&check_delete_status(&Response::new(StatusCode::Ok), true));
+        verify_ok(&check_delete_status(&Response::new(StatusCode::NoContent), true));
+        verify_ok(&check_delete_status(&Response::new(StatusCode::NotFound), true));
+        verify_err(&check_delete_status(&Response::new(StatusCode::Forbidden), true));
+        verify_err(&check_delete_status(&Response::new(StatusCode::InternalServerError), true));
+    }
+
+    #[test]
+    fn status_errors() {
+        use hyper::status::StatusCode;
+
+        let body = r#"{"message": "404 Project Not Found"}"#;
+        match status_error(StatusCode::NotFound, body) {
+            ErrorKind::NotFound(reason) => assert_eq!(reason, "404 Project Not Found"),
+            other => panic!("expected a not found error, got {:?}", other),
+        }
+        match status_error(StatusCode::Unauthorized, r#"{"message": "401 Unauthorized"}"#) {
+            ErrorKind::Unauthorized(reason) => assert_eq!(reason, "401 Unauthorized"),
+            other => panic!("expected an unauthorized error, got {:?}", other),
+        }
+        match status_error(StatusCode::TooManyRequests, "Retry later") {
+            ErrorKind::RateLimited => {}
+            other => panic!("expected a rate limited error, got {:?}", other),
+        }
+        match status_error(StatusCode::BadGateway, "") {
+            ErrorKind::ServerError(502) => {}
+            other => panic!("expected a server error, got {:?}", other),
+        }
+        match status_error(StatusCode::Conflict, "") {
+            ErrorKind::UnexpectedStatus(409) => {}
+            other => panic!("expected an unexpected status error, got {:?}", other),
+        }
+    }
+
+
+    #[test]
+    fn get_status_errors() {
+        let (gl, mock) = mock_gitlab();
+        let mut response = Response::json(r#"{"message": "401 Unauthorized"}"#);
+        response.status = hyper::status::StatusCode::Unauthorized;
+        mock.push_response(response);
+        mock.push_response(Response::new(hyper::status::StatusCode::ServiceUnavailable));
+        let mut response = Response::json(r#"{"message": "404 Project Not Found"}"#);
+        response.status = hyper::status::StatusCode::NotFound;
+        mock.push_response(response);
+        mock.push_response(Response::new(hyper::status::StatusCode::TooManyRequests));
+
+        match *gl.version().unwrap_err().typed_kind() {
+            ErrorKind::Unauthorized(_) => {}
+            ref other => panic!("expected an unauthorized error, got {:?}", other),
+        }
+        match *gl.version().unwrap_err().typed_kind() {
+            ErrorKind::ServerError(503) => {}
+            ref other => panic!("expected a server error, got {:?}", other),
+        }
+        match *gl.put::<serde_json::Value, _>("projects/4", ()).unwrap_err().typed_kind() {
+            ErrorKind::NotFound(ref reason) => assert_eq!(reason, "404 Project Not Found"),
+            ref other => panic!("expected a not found error, got {:?}", other),
+        }
+        match *gl.delete("projects/4").unwrap_err().typed_kind() {
+            ErrorKind::RateLimited => {}
+            ref other => panic!("expected a rate limited error, got {:?}", other),
+        }
+    }
+
+
+    #[test]
+    fn lister_status_errors() {
+        let (gl, mock) = mock_gitlab();
+        let mut response = Response::json(r#"{"message": "404 Project Not Found"}"#);
+        response.status = hyper::status::StatusCode::NotFound;
+        mock.push_response(response);
+        let mut response = Response::json(r#"{"message": "403 Forbidden"}"#);
+        response.status = hyper::status::StatusCode::Forbidden;
+        mock.push_response(response);
+
+        // The listers add some context to the error, but keep its kind.
+        let error = gl.merge_requests(4).list().unwrap_err();
+        assert!(error.to_string().starts_with("cannot get query"), "{}", error);
+        match *error.typed_kind() {
+            ErrorKind::NotFound(ref reason) => assert_eq!(reason, "404 Project Not Found"),
+            ref other => panic!("expected a not found error, got {:?}", other),
+        }
+        match *gl.issues().project(4).list().unwrap_err().typed_kind() {
+            ErrorKind::Forbidden(_) => {}
+            ref other => panic!("expected a forbidden error, got {:?}", other),
+        }
+    }
+
+
+    #[test]
+    fn parse_with_raw_version() {
+        use serde_json;
+
+        let body = r#"{"version":"8.13.0-pre","revision":"4e963fe","unknown":42}"#;
+        let result: Result<(::Version, serde_json::Value)> = parse_with_raw(body);
+        verify_ok(&result);
+
+        let (version, raw) = result.unwrap();
+        assert_eq!(version.version, "8.13.0-pre");
+        assert_eq!(version.revision, "4e963fe");
+        let raw = raw.as_object().unwrap();
+        assert_eq!(raw.get("version").and_then(|v| v.as_str()), Some("8.13.0-pre"));
+        assert_eq!(raw.get("unknown").and_then(|v| v.as_i64()), Some(42));
+    }
+
+    #[test]
+    fn parse_with_raw_invalid() {
+        use serde_json;
+
+        let result: Result<(::Version, serde_json::Value)> = parse_with_raw("<html></html>");
+        assert!(result.is_err());
+        let result: Result<(::Version, serde_json::Value)> = parse_with_raw(r#"{"version":1}"#);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn error_reason_bodies() {
+        let body = r#"{"message": "403 Forbidden - Two-factor authentication is required"}"#;
+        assert_eq!(error_reason(body),
+                   "403 Forbidden - Two-factor authentication is required");
+        assert_eq!(error_reason(r#"{"error": "insufficient_scope"}"#), "insufficient_scope");
+        assert_eq!(error_reason(r#"{"message": {"name": ["has already been taken"]}}"#),
+                   r#"{"name":["has already been taken"]}"#);
+        assert_eq!(error_reason("<html>Forbidden</html>\n"), "<html>Forbidden</html>");
+    }
+
+
+    #[test]
+    fn get_forbidden_reason() {
+        let (gl, mock) = mock_gitlab();
+        let mut response =
+            Response::json(r#"{"message": "403 Forbidden - Your IP address is not allowed"}"#);
+        response.status = hyper::status::StatusCode::Forbidden;
+        mock.push_response(response);
+
+        let error = gl.version().unwrap_err();
+        match *error.typed_kind() {
+            ErrorKind::Forbidden(ref reason) => {
+                assert_eq!(reason, "403 Forbidden - Your IP address is not allowed")
+            }
+            ref other => panic!("expected a forbidden error, got {:?}", other),
+        }
+    }
+
+
+    #[test]
+    fn project_matches_across_namespaces() {
+        fn project(namespace: &str, name: &str) -> ::projects::Project {
+            let mut project = ::projects::Project { name: name.to_string(), ..Default::default() };
+            project.namespace.name = namespace.to_string();
+            project.namespace.path = namespace.to_lowercase();
+            project
+        }
+
+        let projects = vec![project("Group1", "tools"),
+                            project("Group2", "tools"),
+                            project("Group1", "tools-extra"),
+                            project("Group3", "other")];
+
+        let found: Vec<_> = projects.iter().filter(|p| project_matches(p, None, "tools")).collect();
+        assert_eq!(found.len(), 2);
+        assert_eq!(found[0].namespace.name, "Group1");
+        assert_eq!(found[1].namespace.name, "Group2");
+
+        let found: Vec<_> =
+            projects.iter().filter(|p| project_matches(p, Some("Group2"), "tools")).collect();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].namespace.name, "Group2");
+
+        // Namespace's path matches too.
+        let found: Vec<_> =
+            projects.iter().filter(|p| project_matches(p, Some("group1"), "tools")).collect();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].namespace.name, "Group1");
+
+        let found: Vec<_> =
+            projects.iter().filter(|p| project_matches(p, Some("Group3"), "tools")).collect();
+        assert!(found.is_empty());
+    }
+
+
+    #[test]
+    fn mock_get() {
+        let (gl, mock) = mock_gitlab();
+        let mut response = Response::json(r#"[{"id": 1}, {"id": 2}]"#);
+        response.headers.set_raw("X-Total-Pages", vec![b"4".to_vec()]);
+        mock.push_response(response);
+
+        let (items, page_info): (Vec<serde_json::Value>, PageInfo) =
+            gl.get_with_headers("projects", 2, 2).unwrap();
+        assert_eq!(items.len(), 2);
+        assert_eq!(page_info.total_pages, Some(4));
+
+        let requests = mock.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].method, hyper::method::Method::Get);
+        assert_eq!(requests[0].url,
+                   "https://gitlab.example.com/api/v3/projects\
+                    ?private_token=XXXXXXXXXXXXXXXXXXXX&page=2&per_page=2");
+    }
+
+
+    /// Held while a test sets environment variables.
+    static ENV_LOCKED: AtomicBool = AtomicBool::new(false);
+
+    /// Gives a test exclusive access to the environment variables, removing the ones it set
+    /// when dropped.
+    struct EnvGuard {
+        names: Vec<&'static str>,
+    }
+
+    impl EnvGuard {
+        fn new() -> EnvGuard {
+            while ENV_LOCKED.compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst)
+                .is_err() {
+                std::thread::yield_now();
+            }
+            EnvGuard { names: Vec::new() }
+        }
+
+        fn set(&mut self, name: &'static str, value: &str) {
+            std::env::set_var(name, value);
+            self.names.push(name);
+        }
+    }
+
+    impl Drop for EnvGuard {
+        fn drop(&mut self) {
+            for name in &self.names {
+                std::env::remove_var(name);
+            }
+            ENV_LOCKED.store(false, Ordering::SeqCst);
+        }
+    }
+
+
+    #[test]
+    fn per_page_from_env() {
+        let mut env = EnvGuard::new();
+        env.set("GITLAB_HOSTNAME", "gitlab.example.com");
+        env.set("GITLAB_TOKEN", "XXXXXXXXXXXXXXXXXXXX");
+        env.set("GITLAB_PER_PAGE", "50");
+
+        let mock = MockTransport::new();
+        mock.push_response(Response::json("[]"));
+        mock.push_response(Response::json("[]"));
+        mock.push_response(Response::json(r#"{"version": "9.5.0", "revision": "a"}"#));
+        let gl = GitLab::from_env().unwrap().transport(Box::new(mock.clone()));
+
+        let _: ::projects::Projects = gl.projects().list().unwrap();
+        let _: Vec<serde_json::Value> = gl.get("projects", 2, 10).unwrap();
+        // Not a listing.
+        verify_ok(&gl.get::<::Version, _>("version", None, None));
+
+        assert_eq!(gl.per_page, Some(50));
+        let requests = mock.requests();
+        assert!(requests[0].url.ends_with("&per_page=50"));
+        assert!(requests[1].url.ends_with("&page=2&per_page=10"));
+        assert!(!requests[2].url.contains("per_page"));
+
+        for per_page in &["many", "0", "101"] {
+            env.set("GITLAB_PER_PAGE", per_page);
+            verify_err(&GitLab::from_env());
+        }
+    }
+
+
+    /// Write `content` to a new file of the temporary directory, removed when the returned guard
+    /// is dropped.
+    ///
+    /// The file name contains the process ID, so that concurrent `cargo test` runs don't share
+    /// their files.
+    struct TempFile {
+        path: std::path::PathBuf,
+    }
+
+    impl TempFile {
+        fn new(name: &str, content: &str) -> TempFile {
+            use std::io::Write;
+
+            let path = std::env::temp_dir()
+                .join(format!("gitlab-api-rs-test-{}-{}", std::process::id(), name));
+            let mut file = std::fs::File::create(&path).unwrap();
+            file.write_all(content.as_bytes()).unwrap();
+            TempFile { path: path }
+        }
+    }
+
+    impl Drop for TempFile {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_file(&self.path);
+        }
+    }
+
+
+    #[test]
+    fn from_token_file() {
+        let file = TempFile::new("token", "  XXXXXXXXXXXXXXXXXXXX\n");
+        let mock = MockTransport::new();
+        mock.push_response(Response::json(r#"{"version": "9.5.0", "revision": "a"}"#));
+        let gl = GitLab::from_token_file("gitlab.example.com", &file.path)
+            .unwrap()
+            .transport(Box::new(mock.clone()));
+
+        verify_ok(&gl.version());
+        assert_eq!(mock.requests()[0].url,
+                   "https://gitlab.example.com/api/v3/version?private_token=XXXXXXXXXXXXXXXXXXXX");
+
+        // Missing file.
+        let missing = std::env::temp_dir()
+            .join(format!("gitlab-api-rs-test-{}-no-such-token-file", std::process::id()));
+        verify_err(&GitLab::from_token_file("gitlab.example.com", &missing));
+
+        // Malformed tokens: empty, too short, with forbidden characters. `from_token_file()`
+        // doesn't check the length itself: the "short" token is refused by `_new()`'s check of
+        // the token's 20 characters.
+        for &(name, content) in &[("empty", " \n"),
+                                  ("short", "XXXXXXXXXX\n"),
+                                  ("spaces", "XXXXXXXXX XXXXXXXXXX"),
+                                  ("two-lines", "XXXXXXXXXXXXXXXXXXXX\nYYYY")] {
+            let file = TempFile::new(name, content);
+            let error = GitLab::from_token_file("gitlab.example.com", &file.path).unwrap_err();
+            assert!(!error.to_string().contains("XXXXXXXXXX"), "token leaked: {}", error);
+        }
+    }
+
+
+    #[test]
+    fn insecure_from_env() {
+        let mut env = EnvGuard::new();
+        env.set("GITLAB_HOSTNAME", "gitlab.example.com");
+        env.set("GITLAB_TOKEN", "XXXXXXXXXXXXXXXXXXXX");
+
+        assert!(!GitLab::from_env().unwrap().accepts_invalid_certs());
+
+        env.set("GITLAB_API_INSECURE", "0");
+        assert!(!GitLab::from_env().unwrap().accepts_invalid_certs());
+
+        env.set("GITLAB_API_INSECURE", "1");
+        env.set("GITLAB_PER_PAGE", "50");
+        let gl = GitLab::from_env().unwrap();
+        assert!(gl.accepts_invalid_certs());
+        // The other settings survive the change of transport.
+        assert_eq!(gl.per_page, Some(50));
+        assert_eq!(gl.url.as_str(), "https://gitlab.example.com/api/v3/");
+        // Another transport is not affected.
+        assert!(!gl.transport(Box::new(MockTransport::new())).accepts_invalid_certs());
+    }
+
+
+    #[test]
+    fn try_get_project() {
+        let (gl, mock) = mock_gitlab();
+        let mut response = Response::json(r#"{"message": "404 Project Not Found"}"#);
+        response.status = hyper::status::StatusCode::NotFound;
+        mock.push_response(response.clone());
+        // The search finds no project in this namespace.
+        mock.push_response(Response::json(::tests::TEST_PROJECTS_JSON));
+        mock.push_response(response);
+        mock.push_response(Response::json("[]"));
+        let mut response = Response::json(r#"{"message": "401 Unauthorized"}"#);
+        response.status = hyper::status::StatusCode::Unauthorized;
+        mock.push_response(response);
+
+        assert!(gl.try_get_project("group", "gitlab-api-rs").unwrap().is_none());
+        match gl.get_project("group", "gitlab-api-rs") {
+            Err(Error(ErrorKind::NotFound(_), _)) => {}
+            other => panic!("expected a not found error, got {:?}", other),
+        }
+        // Failures other than a missing project are errors.
+        verify_err(&gl.try_get_project("group", "gitlab-api-rs"));
+
+        let requests = mock.requests();
+        assert_eq!(requests.len(), 5);
+        assert!(requests[1].url.starts_with("https://gitlab.example.com/api/v3/projects/search/\
+                                             gitlab-api-rs?"));
+    }
+
+
+    #[test]
+    fn try_get_merge_request() {
+        let (gl, mock) = mock_gitlab();
+        let project_json = ::tests::test_project_json();
+        mock.push_response(Response::json(project_json));
+        mock.push_response(Response::json("[]"));
+
+        let merge_request = gl.try_get_merge_request("nbigaouette1", "gitlab-api-rs", 42);
+        assert!(merge_request.unwrap().is_none());
+    }
+
+
+    #[test]
+    fn get_merge_request_by_iid() {
+        let (gl, mock) = mock_gitlab();
+        let project_json = ::tests::test_project_json();
+        mock.push_response(Response::json(project_json));
+        // No merge request matches the `iid`.
+        mock.push_response(Response::json("[]"));
+
+        verify_err(&gl.get_merge_request("nbigaouette1", "gitlab-api-rs", 42));
+
+        let requests = mock.requests();
+        assert_eq!(requests.len(), 2);
+        assert!(requests[0].url.starts_with("https://gitlab.example.com/api/v3/projects/\
+                                             nbigaouette1%2Fgitlab-api-rs?"));
+        assert!(requests[1].url.starts_with("https://gitlab.example.com/api/v3/projects/517564/\
+                                             merge_requests?iid=42&"));
+    }
+
+
+    #[test]
+    fn find_projects_follows_pagination_headers() {
+        let (gl, mock) = mock_gitlab();
+        // A partial page which is not the last one, according to the `Link` header.
+        let mut response = Response::json(::tests::TEST_PROJECTS_JSON);
+        let link = "<https://gitlab.example.com/api/v3/projects/search/gitlab-api-rs\
+                    ?page=2&per_page=20>; rel=\"next\"";
+        response.headers.set_raw("Link", vec![link.as_bytes().to_vec()]);
+        mock.push_response(response);
+        // The last page.
+        let mut response = Response::json(::tests::TEST_PROJECTS_JSON);
+        response.headers.set_raw("X-Page", vec![b"2".to_vec()]);
+        response.headers.set_raw("X-Next-Page", vec![b"".to_vec()]);
+        mock.push_response(response);
+
+        let projects = gl.find_projects(Some("nbigaouette1"), "gitlab-api-rs").unwrap();
+        assert_eq!(projects.len(), 2);
+
+        let requests = mock.requests();
+        assert_eq!(requests.len(), 2);
+        assert!(requests[1].url.contains("&page=2&per_page=20"));
+    }
+
+
+    fn redirect(location: &str) -> Response {
+        let mut response = Response::new(hyper::status::StatusCode::MovedPermanently);
+        response.headers.set_raw("Location", vec![location.as_bytes().to_vec()]);
+        response
+    }
+
+
+    #[test]
+    fn redirect_same_host() {
+        let mock = MockTransport::new();
+        mock.push_response(redirect("https://gitlab.example.com/api/v3/version"));
+        mock.push_response(Response::json(r#"{"version": "8.16.4", "revision": "abcdef"}"#));
+        let gl = GitLab::new_insecure("gitlab.example.com", "XXXXXXXXXXXXXXXXXXXX")
+            .unwrap()
+            .transport(Box::new(mock.clone()));
+
+        verify_ok(&gl.version());
+
+        let requests = mock.requests();
+        assert_eq!(requests.len(), 2);
+        assert!(requests[0].url.starts_with("http://"));
+        assert_eq!(requests[1].url,
+                   "https://gitlab.example.com/api/v3/version?private_token=XXXXXXXXXXXXXXXXXXXX");
     }
-}
 
-/// Remove the private token from a URL string, replacing it with `${GITLAB_TOKEN}`.
-/// This allows setting the environment variable `${GITLAB_TOKEN}` and still be able
-/// to copy-paste a printed URL.
-fn remove_gitlab_token_from_url(url: &str) -> String {
-    let re = regex::Regex::new(r"private_token=\w{20}").unwrap();
-    re.replace_all(url, "private_token=$${GITLAB_TOKEN}").into()
-}
 
+    #[test]
+    fn redirect_other_host() {
+        let (gl, mock) = mock_gitlab();
+        mock.push_response(redirect("https://attacker.example.org/api/v3/version"));
 
-#[cfg(test)]
-mod tests {
-    use std::fmt;
-    use gitlab::*;
+        verify_err(&gl.version());
 
-    fn verify_ok<T>(result: &Result<T>) {
-        if let &Err(ref e) = result {
-            println!("error: {}", e);
+        // The token was not sent to the other host.
+        let requests = mock.requests();
+        assert_eq!(requests.len(), 1);
+        assert!(requests[0].url.starts_with("https://gitlab.example.com/"));
+    }
 
-            for e in e.iter().skip(1) {
-                println!("caused by: {}", e);
-            }
 
-            // The backtrace is not always generated. Try to run this example
-            // with `RUST_BACKTRACE=1`.
-            if let Some(backtrace) = e.backtrace() {
-                println!("backtrace: {:?}", backtrace);
-            }
+    #[test]
+    fn redirect_target_token() {
+        let url = "https://gitlab.example.com/api/v3/projects?private_token=XXXXXXXXXXXXXXXXXXXX";
+
+        // Relative location, echoing the token: it appears only once.
+        let location = "/gitlab/api/v3/projects?page=2&private_token=XXXXXXXXXXXXXXXXXXXX";
+        let target = redirect_target(url, location, "XXXXXXXXXXXXXXXXXXXX").unwrap();
+        assert_eq!(target,
+                   "https://gitlab.example.com/gitlab/api/v3/projects\
+                    ?page=2&private_token=XXXXXXXXXXXXXXXXXXXX");
+
+        verify_err(&redirect_target(url, "ftp://gitlab.example.com/", "XXXXXXXXXXXXXXXXXXXX"));
+    }
+
+
+    #[test]
+    fn redirect_target_downgrade() {
+        let url = "https://gitlab.example.com/api/v3/projects";
+
+        // The token would be sent in cleartext.
+        verify_err(&redirect_target(url,
+                                    "http://gitlab.example.com/api/v3/projects",
+                                    "XXXXXXXXXXXXXXXXXXXX"));
+
+        // Upgrading is fine.
+        let target = redirect_target("http://gitlab.example.com/api/v3/projects",
+                                     url,
+                                     "XXXXXXXXXXXXXXXXXXXX")
+            .unwrap();
+        assert!(target.starts_with("https://gitlab.example.com/api/v3/projects?"));
+    }
+
+
+    #[test]
+    fn redirect_target_port() {
+        let url = "https://gitlab.example.com/api/v3/projects";
+
+        verify_err(&redirect_target(url,
+                                    "https://gitlab.example.com:8443/api/v3/projects",
+                                    "XXXXXXXXXXXXXXXXXXXX"));
+        verify_err(&redirect_target("http://gitlab.example.com:8080/api/v3/projects",
+                                    "https://gitlab.example.com/api/v3/projects",
+                                    "XXXXXXXXXXXXXXXXXXXX"));
+
+        // An explicit default port is the same port.
+        let target = redirect_target(url,
+                                     "https://gitlab.example.com:443/gitlab/api/v3/projects",
+                                     "XXXXXXXXXXXXXXXXXXXX")
+            .unwrap();
+        assert!(target.starts_with("https://gitlab.example.com/gitlab/api/v3/projects?"));
+    }
+
+
+    #[test]
+    fn mock_get_error_status() {
+        let (gl, mock) = mock_gitlab();
+        mock.push_response(Response::new(hyper::status::StatusCode::Unauthorized));
+
+        verify_err(&gl.version());
+    }
+
+
+    thread_local! {
+        /// The messages logged by the current thread, see `capture_logs()`.
+        static LOGGED: RefCell<Vec<(log::LogLevel, String)>> = RefCell::new(Vec::new());
+    }
+
+    const TEST_SECRET_TOKEN: &'static str = "EsMo-vhKfXGwX9RKrwiy";
+
+    struct TestLogger;
+
+    impl log::Log for TestLogger {
+        fn enabled(&self, _metadata: &log::LogMetadata) -> bool {
+            true
+        }
+
+        fn log(&self, record: &log::LogRecord) {
+            LOGGED.with(|logged| {
+                logged.borrow_mut().push((record.level(), record.args().to_string()))
+            });
         }
-        assert!(result.is_ok());
     }
 
-    fn verify_err<T>(result: &Result<T>)
-        where T: fmt::Debug
+    /// Run `f`, returning the messages it logged.
+    ///
+    /// Only one logger can be set per process, and the tests run concurrently: the messages are
+    /// kept per thread so that each test only sees its own.
+    fn capture_logs<F>(f: F) -> Vec<(log::LogLevel, String)>
+        where F: FnOnce()
     {
-        match result {
-            &Err(_) => {
-                // pass
-            }
-            &Ok(ref t) => {
-                panic!(format!("Expected an Err(), got an Ok(t), with t: {:?}", t));
-            }
-        }
+        let _ = log::set_logger(|max_log_level| {
+            max_log_level.set(log::LogLevelFilter::Trace);
+            Box::new(TestLogger)
+        });
+
+        LOGGED.with(|logged| logged.borrow_mut().clear());
+        f();
+        LOGGED.with(|logged| logged.borrow_mut().drain(..).collect())
     }
 
+
     #[test]
-    fn impl_debug_for_gitlab() {
-        let gl = GitLab::new("gitlab.com", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+    fn warn_truncated_list() {
+        let (gl, mock) = mock_gitlab();
+        let mut response = Response::json("[]");
+        response.headers.set_raw("X-Next-Page", vec![b"2".to_vec()]);
+        mock.push_response(response);
+
+        let logged = capture_logs(|| {
+            let mut lister = gl.projects();
+            let _ = lister.with_tag(String::from("truncation-test")).list().unwrap();
+        });
+
+        assert!(logged.iter().any(|&(level, ref message)| {
+            level == log::LogLevel::Warn && message.contains("truncation-test") &&
+            message.contains("first page")
+        }));
+    }
 
-        let debug = format!("{:?}", gl);
-        assert_eq!("GitLab { scheme: https, domain: gitlab.com, port: no port provided, \
-                    private_token: XXXXXXXXXXXXXXXXXXXX }",
-                   debug);
 
-        let gl = gl.scheme("http").port(80);
-        let debug = format!("{:?}", gl);
-        assert_eq!("GitLab { scheme: http, domain: gitlab.com, port: no port provided, \
-                    private_token: XXXXXXXXXXXXXXXXXXXX }",
-                   debug);
+    #[test]
+    fn request_options_override() {
+        let (gl, mock) = mock_gitlab();
+        let gl = gl.request_options(RequestOptions::new()
+            .timeout(std::time::Duration::from_secs(10))
+            .header("X-Client", "default"));
+        mock.push_response(Response::json("[]"));
+        mock.push_response(Response::json("[]"));
+
+        let options = RequestOptions::new()
+            .timeout(std::time::Duration::from_secs(300))
+            .header("X-Export", "yes");
+        gl.projects().list_with(&options).unwrap();
+        let _: Vec<serde_json::Value> = gl.get("projects", None, None).unwrap();
+
+        let requests = mock.requests();
+        // Overridden for the call only...
+        assert_eq!(requests[0].timeout, Some(std::time::Duration::from_secs(300)));
+        assert!(requests[0].headers.get_raw("X-Export").is_some());
+        assert!(requests[0].headers.get_raw("X-Client").is_some());
+        // ...the next calls use the client's defaults.
+        assert_eq!(requests[1].timeout, Some(std::time::Duration::from_secs(10)));
+        assert!(requests[1].headers.get_raw("X-Export").is_none());
+        assert!(requests[1].headers.get_raw("X-Client").is_some());
+    }
 
-        let gl = gl.port(81);
-        let debug = format!("{:?}", gl);
-        assert_eq!("GitLab { scheme: http, domain: gitlab.com, port: 81, private_token: \
-                    XXXXXXXXXXXXXXXXXXXX }",
-                   debug);
+
+    #[test]
+    fn accept_header() {
+        let (gl, mock) = mock_gitlab();
+        mock.push_response(Response::json("[]"));
+        let mut response = Response::new(hyper::status::StatusCode::Ok);
+        response.body = String::from("diff --git a/README.md b/README.md");
+        mock.push_response(response);
+
+        let _: Vec<serde_json::Value> = gl.get("projects", None, None).unwrap();
+        let options = RequestOptions::new().accept("text/plain");
+        let query = "projects/1/repository/commits/abc/diff";
+        let diff = gl.get_raw_with_options(query, None, None, &options).unwrap();
+        assert!(diff.starts_with("diff --git"));
+
+        let requests = mock.requests();
+        assert_eq!(requests[0].headers.get_raw("Accept"),
+                   Some(&[b"application/json".to_vec()][..]));
+        assert_eq!(requests[1].headers.get_raw("Accept"), Some(&[b"text/plain".to_vec()][..]));
     }
 
+
     #[test]
-    fn gitlab_listers_groups() {
-        let gl = GitLab::new("gitlab.com", "XXXXXXXXXXXXXXXXXXXX").unwrap();
-        let groups_lister = gl.groups();
-        let debug = format!("{:?}", groups_lister);
-        assert_eq!("GroupsLister { gl: GitLab { scheme: https, domain: gitlab.com, port: no \
-                    port provided, private_token: XXXXXXXXXXXXXXXXXXXX }, \
-                    internal: GroupsListerInternal { skip_groups: None, all_available: None, \
-                    search: None, order_by: None, sort: None } }",
-                   debug);
+    fn mock_post() {
+        let (gl, mock) = mock_gitlab();
+        let mut response = Response::json(r#"{"id": 42}"#);
+        response.status = hyper::status::StatusCode::Created;
+        mock.push_response(response);
+
+        let created: serde_json::Value = gl.post("projects", &vec![1, 2]).unwrap();
+        assert_eq!(created.pointer("/id").and_then(|id| id.as_i64()), Some(42));
+
+        let requests = mock.requests();
+        assert_eq!(requests[0].method, hyper::method::Method::Post);
+        assert_eq!(requests[0].body, Some(String::from("[1,2]")));
     }
 
+
     #[test]
-    fn gitlab_listers_projects() {
-        let gl = GitLab::new("gitlab.com", "XXXXXXXXXXXXXXXXXXXX").unwrap();
-        let projects_lister = gl.projects();
-        let debug = format!("{:?}", projects_lister);
-        assert_eq!("ProjectsLister { gl: GitLab { scheme: https, domain: gitlab.com, port: no \
-                    port provided, private_token: XXXXXXXXXXXXXXXXXXXX }, \
-                    internal: ProjectListerInternal { archived: None, visibility: None, \
-                    order_by: None, sort: None, search: None, simple: None } }",
-                   debug);
+    fn deadline_stops_pagination() {
+        let (gl, mock) = mock_gitlab();
+        let project_json = ::tests::test_project_json();
+        for next_page in &["2", "3", ""] {
+            let mut response = Response::json(&format!("[{}]", project_json));
+            response.headers.set_raw("X-Next-Page", vec![next_page.as_bytes().to_vec()]);
+            mock.push_response(response);
+        }
+
+        // Each page takes 400ms: the deadline passes while the first one is received, so the
+        // second one is not requested.
+        mock.set_delay(std::time::Duration::from_millis(400));
+        let deadline = std::time::Instant::now() + std::time::Duration::from_millis(200);
+        let gl = gl.request_options(RequestOptions::new().deadline(deadline));
+
+        let partial = ::pagination::list_all_partial(&gl.projects(), 1).unwrap();
+        assert!(!partial.complete);
+        assert_eq!(partial.items.len(), 1);
+        assert_eq!(mock.requests().len(), 1);
+
+        // Without the partial results, the exceeded deadline is an error.
+        let error = ::pagination::list_all(&gl.projects(), 1).unwrap_err();
+        match *error.typed_kind() {
+            ErrorKind::DeadlineExceeded => {}
+            ref other => panic!("expected an exceeded deadline, got {:?}", other),
+        }
+        assert_eq!(mock.requests().len(), 1);
     }
 
+
     #[test]
-    fn gitlab_listers_issues() {
-        let gl = GitLab::new("gitlab.com", "XXXXXXXXXXXXXXXXXXXX").unwrap();
-        let issues_lister = gl.issues();
-        let debug = format!("{:?}", issues_lister);
-        assert_eq!("IssuesLister { gl: GitLab { scheme: https, domain: gitlab.com, port: no port \
-                    provided, private_token: XXXXXXXXXXXXXXXXXXXX }, internal: \
-                    IssuesListerInternal { state: None, labels: None, order_by: None, sort: None \
-                    } }",
-                   debug);
+    fn deadline_already_passed() {
+        let (gl, mock) = mock_gitlab();
+        mock.push_response(Response::json("[]"));
+        let gl = gl.request_options(RequestOptions::new().deadline(std::time::Instant::now()));
+
+        let partial = ::pagination::list_all_partial(&gl.projects(), 1).unwrap();
+        assert!(!partial.complete);
+        assert!(partial.items.is_empty());
+
+        // Nothing was sent.
+        assert!(mock.requests().is_empty());
     }
 
+
     #[test]
-    fn new_valid() {
-        let gl = GitLab::new("gitlab.com", "XXXXXXXXXXXXXXXXXXXX");
-        verify_ok(&gl);
+    fn mock_put() {
+        let (gl, mock) = mock_gitlab();
+        mock.push_response(Response::json(r#"{"name": "master", "protected": true}"#));
+        mock.push_response(Response::json(r#"{"id": 3, "name": "renamed"}"#));
+        let mut response = Response::new(hyper::status::StatusCode::NotFound);
+        response.body = String::from(r#"{"message": "404 Project Not Found"}"#);
+        mock.push_response(response);
+
+        let branch: serde_json::Value =
+            gl.put("projects/3/repository/branches/master/protect", ()).unwrap();
+        assert_eq!(branch.pointer("/protected").and_then(|p| p.as_bool()), Some(true));
+
+        let update = ::projects::ProjectUpdate {
+            name: Some(String::from("renamed")),
+            ..Default::default()
+        };
+        let _: serde_json::Value = gl.put("projects/3", &update).unwrap();
+
+        let error = gl.put::<serde_json::Value, _>("projects/4", &update).unwrap_err();
+        assert!(error.to_string().contains("404"));
+
+        let requests = mock.requests();
+        assert_eq!(requests[0].method, hyper::method::Method::Put);
+        assert_eq!(requests[0].url,
+                   "https://gitlab.example.com/api/v3/projects/3/repository/branches/master/\
+                    protect?private_token=XXXXXXXXXXXXXXXXXXXX");
+        assert_eq!(requests[0].body, None);
+        assert_eq!(requests[1].method, hyper::method::Method::Put);
+        assert_eq!(requests[1].body, Some(String::from(r#"{"name":"renamed"}"#)));
+    }
 
-        let gl = GitLab::new_insecure("gitlab.com", "XXXXXXXXXXXXXXXXXXXX");
-        verify_ok(&gl);
 
-        let gl = GitLab::new("localhost", "XXXXXXXXXXXXXXXXXXXX");
-        verify_ok(&gl);
+    #[test]
+    fn create_project_in() {
+        let (gl, mock) = mock_gitlab();
+        // GitLab's search matches the names and paths, so "subgroup" finds namespaces of other
+        // groups, and ones only containing it.
+        let mut response = Response::json(r#"[
+            {"id": 3, "name": "subgroup", "path": "subgroup", "kind": "group",
+             "full_path": "other/subgroup", "parent_id": 2, "members_count_with_descendants": 1},
+            {"id": 5, "name": "Subgroup archive", "path": "subgroup-archive", "kind": "group",
+             "full_path": "group/subgroup-archive", "parent_id": 1}
+        ]"#);
+        response.headers.set_raw("X-Next-Page", vec![b"2".to_vec()]);
+        mock.push_response(response);
+        mock.push_response(Response::json(r#"[
+            {"id": 4, "name": "subgroup", "path": "subgroup", "kind": "group",
+             "full_path": "group/subgroup", "parent_id": 1}
+        ]"#));
+        let mut response = Response::json(::tests::test_project_json());
+        response.status = hyper::status::StatusCode::Created;
+        mock.push_response(response);
+        mock.push_response(Response::json("[]"));
+
+        let project = gl.create_project_in("group/subgroup", "onboarding").unwrap();
+        assert_eq!(project.id, 517564);
+
+        let error = gl.create_project_in("group/missing", "onboarding").unwrap_err();
+        assert!(error.to_string().contains("namespace 'group/missing' not found"));
+
+        let requests = mock.requests();
+        assert_eq!(requests.len(), 4);
+        assert!(requests[0].url.starts_with("https://gitlab.example.com/api/v3/namespaces?\
+                                             search=subgroup&"));
+        assert!(requests[1].url.contains("page=2"));
+        assert_eq!(requests[2].method, hyper::method::Method::Post);
+        assert!(requests[2].url.starts_with("https://gitlab.example.com/api/v3/projects?"));
+        assert_eq!(requests[2].body,
+                   Some(String::from(r#"{"name":"onboarding","namespace_id":4}"#)));
+        assert!(requests[3].url.starts_with("https://gitlab.example.com/api/v3/namespaces?\
+                                             search=missing&"));
+    }
 
-        let gl = GitLab::new_insecure("localhost", "XXXXXXXXXXXXXXXXXXXX");
-        verify_ok(&gl);
+
+    #[test]
+    fn post_status_codes() {
+        #[derive(Serialize)]
+        struct Body {
+            name: String,
+        }
+
+        let (gl, mock) = mock_gitlab();
+        mock.push_response(Response::json(r#"{"id": 1}"#));
+        let mut response = Response::json(r#"{"message": "404 Project Not Found"}"#);
+        response.status = hyper::status::StatusCode::NotFound;
+        mock.push_response(response);
+
+        let body = Body { name: String::from("new-project") };
+        // `200 OK` is a success, like `201 Created`.
+        let created: serde_json::Value = gl.post("projects", &body).unwrap();
+        assert_eq!(created.pointer("/id").and_then(|id| id.as_i64()), Some(1));
+
+        let error = gl.post::<serde_json::Value, _>("projects", &body).unwrap_err();
+        assert!(error.to_string().contains("404"));
+
+        let requests = mock.requests();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0].url,
+                   "https://gitlab.example.com/api/v3/projects?private_token=XXXXXXXXXXXXXXXXXXXX");
+        assert_eq!(requests[0].headers.get::<hyper::header::ContentType>(),
+                   Some(&hyper::header::ContentType::json()));
+        assert_eq!(requests[0].body, Some(String::from(r#"{"name":"new-project"}"#)));
     }
 
+
     #[test]
-    fn new_invalid_url_1() {
-        let gl = GitLab::new("", "XXXXXXXXXXXXXXXXXXXX");
-        verify_err(&gl);
+    fn create_project_for_user() {
+        let (gl, mock) = mock_gitlab();
+        let mut response = Response::json(::tests::TEST_PROJECTS_JSON
+            .trim()
+            .trim_left_matches('[')
+            .trim_right_matches(']'));
+        response.status = hyper::status::StatusCode::Created;
+        mock.push_response(response);
+        let mut response = Response::json(r#"{"message": "403 Forbidden"}"#);
+        response.status = hyper::status::StatusCode::Forbidden;
+        mock.push_response(response);
+
+        let mut creator = ::projects::create::ProjectCreator::new(&gl, String::from("onboarding"));
+        let creator = creator.path(String::from("onboarding-jdoe"));
+        let project = gl.create_project_for_user(42, creator).unwrap();
+        assert_eq!(project.id, 517564);
+
+        let error = gl.create_project_for_user(42, creator).unwrap_err();
+        assert!(error.iter().any(|e| e.to_string().contains("only administrators")));
+
+        let requests = mock.requests();
+        assert_eq!(requests[0].method, hyper::method::Method::Post);
+        assert!(requests[0]
+            .url
+            .starts_with("https://gitlab.example.com/api/v3/projects/user/42?"));
+        assert_eq!(requests[0].body,
+                   Some(String::from(r#"{"name":"onboarding","path":"onboarding-jdoe"}"#)));
+    }
 
-        let gl = GitLab::new_insecure("", "XXXXXXXXXXXXXXXXXXXX");
-        verify_err(&gl);
+
+    #[test]
+    fn create_impersonation_token() {
+        let (gl, mock) = mock_gitlab();
+        let mut response = Response::json(&format!(r#"{{"id": 2, "name": "deploy",
+                                                        "scopes": ["api"], "active": true,
+                                                        "revoked": false, "impersonation": true,
+                                                        "expires_at": "2017-12-31",
+                                                        "created_at": "2017-04-29T08:46:00.000Z",
+                                                        "token": "{}"}}"#,
+                                                   TEST_SECRET_TOKEN));
+        response.status = hyper::status::StatusCode::Created;
+        mock.push_response(response);
+        let mut response = Response::json(r#"{"message": "403 Forbidden"}"#);
+        response.status = hyper::status::StatusCode::Forbidden;
+        mock.push_response(response);
+
+        let scopes = vec![String::from("api")];
+        let expires_at = Some(String::from("2017-12-31"));
+        let mut token = String::new();
+        let logged = capture_logs(|| {
+            token = gl.create_impersonation_token(42, "deploy", scopes.clone(), expires_at)
+                .unwrap();
+        });
+        assert_eq!(token, TEST_SECRET_TOKEN);
+        assert!(!logged.is_empty());
+        assert!(logged.iter().all(|&(_, ref message)| !message.contains(TEST_SECRET_TOKEN)));
+
+        let error = gl.create_impersonation_token(42, "deploy", scopes, None).unwrap_err();
+        assert!(error.iter().any(|e| e.to_string().contains("only administrators")));
+
+        let requests = mock.requests();
+        assert_eq!(requests[0].method, hyper::method::Method::Post);
+        assert!(requests[0]
+            .url
+            .starts_with("https://gitlab.example.com/api/v3/users/42/impersonation_tokens?"));
+        let expected_body = r#"{"name":"deploy","scopes":["api"],"expires_at":"2017-12-31"}"#;
+        assert_eq!(requests[0].body, Some(String::from(expected_body)));
     }
 
+
     #[test]
-    fn new_invalid_url_2() {
-        let gl = GitLab::new("gitla/b.com", "XXXXXXXXXXXXXXXXXXXX");
-        verify_err(&gl);
+    fn mock_delete() {
+        let (gl, mock) = mock_gitlab();
+        mock.push_response(Response::new(hyper::status::StatusCode::NotFound));
+        mock.push_response(Response::new(hyper::status::StatusCode::NotFound));
 
-        let gl = GitLab::new_insecure("gitla/b.com", "XXXXXXXXXXXXXXXXXXXX");
-        verify_err(&gl);
+        verify_err(&gl.delete("projects/1"));
+        verify_ok(&gl.delete_idempotent("projects/1"));
+
+        let requests = mock.requests();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[1].method, hyper::method::Method::Delete);
     }
 
+
     #[test]
-    fn new_invalid_url_3() {
-        let gl = GitLab::new("/gitlab.com", "XXXXXXXXXXXXXXXXXXXX");
-        verify_err(&gl);
+    fn get_large_array() {
+        let (gl, mock) = mock_gitlab();
+        let namespaces: Vec<String> = (0..20000)
+            .map(|id| {
+                format!(r#"{{"id": {0}, "name": "user{0}", "path": "user{0}", "kind": "user"}}"#,
+                        id)
+            })
+            .collect();
+        mock.push_response(Response::json(&format!("[{}]", namespaces.join(","))));
+        mock.push_response(Response::json(r#"[{"id": 1, "name": "user1"}]"#));
+
+        let namespaces: Result<::namespaces::Namespaces> = gl.get("namespaces", None, None);
+        verify_ok(&namespaces);
+        let namespaces = namespaces.unwrap();
+        assert_eq!(namespaces.len(), 20000);
+        assert_eq!(namespaces[0].path, "user0");
+        assert_eq!(namespaces[19999].id, 19999);
+        assert_eq!(namespaces[19999].path, "user19999");
+
+        // Missing fields are still reported.
+        verify_err(&gl.get::<::namespaces::Namespaces, _>("namespaces", None, None));
+    }
 
-        let gl = GitLab::new_insecure("/gitlab.com", "XXXXXXXXXXXXXXXXXXXX");
-        verify_err(&gl);
+
+    #[test]
+    fn get_streams_body() {
+        use std::io::{self, Cursor, Read};
+        use transport::{Request, StreamingResponse, Transport};
+
+        /// Fails to be read.
+        struct FailingReader;
+
+        impl Read for FailingReader {
+            fn read(&mut self, _buf: &mut [u8]) -> io::Result<usize> {
+                Err(io::Error::new(io::ErrorKind::Other, "body read past the first item"))
+            }
+        }
+
+        /// Answers with a body whose first item is invalid, and which cannot be read further.
+        struct FirstItemTransport;
+
+        impl Transport for FirstItemTransport {
+            fn send(&self, _request: Request) -> Result<Response> {
+                bail!("the body was not streamed")
+            }
+
+            fn send_streaming(&self, _request: Request) -> Result<StreamingResponse> {
+                let mut headers = hyper::header::Headers::new();
+                headers.set(hyper::header::ContentType::json());
+                let first_item: &'static [u8] = br#"[{"id": 0, "name": "user0"}, "#;
+
+                Ok(StreamingResponse {
+                    status: hyper::status::StatusCode::Ok,
+                    headers: headers,
+                    body: Box::new(Cursor::new(first_item).chain(FailingReader)),
+                })
+            }
+        }
+
+        let gl = ::GitLab::new(&"gitlab.example.com", "XXXXXXXXXXXXXXXXXXXX")
+            .unwrap()
+            .transport(Box::new(FirstItemTransport));
+
+        // The invalid item is reported without reading the rest of the body.
+        let error = gl.get::<::namespaces::Namespaces, _>("namespaces", None, None).unwrap_err();
+        assert!(error.iter().any(|e| e.to_string().contains("missing field")));
+        assert!(!error.iter().any(|e| e.to_string().contains("read past")));
     }
 
+
     #[test]
-    fn new_invalid_url_4() {
-        let gl = GitLab::new("http:/gitlab.com", "XXXXXXXXXXXXXXXXXXXX");
-        verify_err(&gl);
+    fn api_v3_server() {
+        let (gl, mock) = mock_gitlab();
+        let merge_request_json = ::merge_requests::tests::TEST_MERGE_REQUEST_JSON
+            .replace("\"iid\": 1,", "\"iid\": 7,");
+        mock.push_response(Response::json(r#"{"version": "8.17.4", "revision": "a"}"#));
+        mock.push_response(Response::json("[]"));
+        mock.push_response(Response::json(&format!("[{}]", merge_request_json)));
+        mock.push_response(Response::json(&merge_request_json));
+
+        assert_eq!(gl.api_version(), 3);
+        assert_eq!(gl.server_version().unwrap().major, 8);
+        assert_eq!(gl.api_version(), 3);
+        verify_ok(&gl.issues().project(9).iid(42).list());
+        verify_ok(&gl.update_merge_request(9, 7, Default::default()));
+
+        let requests = mock.requests();
+        assert_eq!(requests.len(), 4);
+        assert!(requests[0].url.starts_with("https://gitlab.example.com/api/v3/version?"));
+        assert!(requests[1]
+            .url
+            .starts_with("https://gitlab.example.com/api/v3/projects/9/issues?iid=42&"));
+        // The merge request is identified by its `id` (1), found from its `iid` (7).
+        assert!(requests[2]
+            .url
+            .starts_with("https://gitlab.example.com/api/v3/projects/9/merge_requests?iid=7&"));
+        assert!(requests[3]
+            .url
+            .starts_with("https://gitlab.example.com/api/v3/projects/9/merge_requests/1?"));
+    }
 
-        let gl = GitLab::new_insecure("http:/gitlab.com", "XXXXXXXXXXXXXXXXXXXX");
-        verify_err(&gl);
+
+    #[test]
+    fn api_v4_server() {
+        let (gl, mock) = mock_gitlab();
+        mock.push_response(Response::json(r#"{"version": "10.8.0", "revision": "a"}"#));
+        mock.push_response(Response::json("[]"));
+        mock.push_response(Response::json("[]"));
+        mock.push_response(Response::json(::merge_requests::tests::TEST_MERGE_REQUEST_JSON));
+
+        assert!(gl.supports_api_v4().unwrap());
+        assert_eq!(gl.api_version(), 4);
+        verify_ok(&gl.issues().project(9).iid(42).list());
+        verify_ok(&gl.merge_requests(9).iid(vec![42]).list());
+        verify_ok(&gl.update_merge_request(9, 7, Default::default()));
+
+        let requests = mock.requests();
+        assert_eq!(requests.len(), 4);
+        assert!(requests[0].url.starts_with("https://gitlab.example.com/api/v3/version?"));
+        assert!(requests[1]
+            .url
+            .starts_with("https://gitlab.example.com/api/v4/projects/9/issues?iids[]=42&"));
+        assert!(requests[2]
+            .url
+            .starts_with("https://gitlab.example.com/api/v4/projects/9/merge_requests?iids[]=42&"));
+        // The merge request is identified by its `iid`, without fetching it first.
+        assert!(requests[3]
+            .url
+            .starts_with("https://gitlab.example.com/api/v4/projects/9/merge_requests/7?"));
+        assert_eq!(gl.build_url_safe("version").unwrap(),
+                   "https://gitlab.example.com/api/v4/version");
     }
 
+
     #[test]
-    fn new_invalid_url_5() {
-        let gl = GitLab::new("http:///gitlab.com", "XXXXXXXXXXXXXXXXXXXX");
-        verify_err(&gl);
+    fn api_v4_project_listings() {
+        let (gl, mock) = mock_gitlab();
+        mock.push_response(Response::json(r#"{"version": "10.8.0", "revision": "a"}"#));
+        for _ in 0..3 {
+            mock.push_response(Response::json("[]"));
+        }
+        // `get_project()` falls back to searching for the project.
+        let mut response = Response::json(r#"{"message": "404 Project Not Found"}"#);
+        response.status = hyper::status::StatusCode::NotFound;
+        mock.push_response(response);
+        mock.push_response(Response::json(::tests::TEST_PROJECTS_JSON));
+
+        assert_eq!(gl.server_version().unwrap().major, 10);
+        verify_ok(&gl.projects().owned().archived(true).list());
+        verify_ok(&gl.projects().all().list());
+        let mut lister = gl.projects().search(String::from("gitlab api"));
+        verify_ok(&lister.sort(::ListingSort::Asc).list());
+        verify_ok(&gl.get_project("nbigaouette1", "gitlab-api-rs"));
+
+        let requests = mock.requests();
+        assert_eq!(requests.len(), 6);
+        assert!(requests[1]
+            .url
+            .starts_with("https://gitlab.example.com/api/v4/projects?owned=true&archived=true&"));
+        assert!(requests[2].url.starts_with("https://gitlab.example.com/api/v4/projects?"));
+        assert!(requests[3]
+            .url
+            .starts_with("https://gitlab.example.com/api/v4/projects?search=gitlab+api&sort=asc&"));
+        assert!(requests[4].url.starts_with("https://gitlab.example.com/api/v4/projects/\
+                                             nbigaouette1%2Fgitlab-api-rs?"));
+        assert!(requests[5]
+            .url
+            .starts_with("https://gitlab.example.com/api/v4/projects?search=gitlab-api-rs&"));
+    }
 
-        let gl = GitLab::new_insecure("http:///gitlab.com", "XXXXXXXXXXXXXXXXXXXX");
-        verify_err(&gl);
+
+    #[test]
+    fn api_v4_only_server() {
+        let (gl, mock) = mock_gitlab();
+        // GitLab 11 removed the API v3.
+        mock.push_response(Response::new(hyper::status::StatusCode::NotFound));
+        mock.push_response(Response::json(r#"{"version": "11.2.0", "revision": "a"}"#));
+        mock.push_response(Response::json("[]"));
+
+        assert_eq!(gl.server_version().unwrap().major, 11);
+        verify_ok(&gl.projects().list());
+
+        let requests = mock.requests();
+        assert!(requests[0].url.starts_with("https://gitlab.example.com/api/v3/version?"));
+        assert!(requests[1].url.starts_with("https://gitlab.example.com/api/v4/version?"));
+        assert!(requests[2].url.starts_with("https://gitlab.example.com/api/v4/projects?"));
     }
 
+
     #[test]
-    fn new_invalid_url_6() {
-        let gl = GitLab::new(".gitlab", "XXXXXXXXXXXXXXXXXXXX");
-        verify_err(&gl);
+    fn cache() {
+        let (gl, mock) = mock_gitlab();
+        let gl = gl.enable_cache(std::time::Duration::from_secs(60));
+        mock.push_response(Response::json(r#"{"version": "9.5.0", "revision": "a"}"#));
+        mock.push_response(Response::json(r#"{"version": "9.5.1", "revision": "b"}"#));
+        mock.push_response(Response::json(r#"{"version": "9.5.2", "revision": "c"}"#));
+
+        assert_eq!(gl.version().unwrap().version, "9.5.0");
+        assert_eq!(gl.version().unwrap().version, "9.5.0");
+        assert_eq!(mock.requests().len(), 1);
+
+        // Another URL is not answered from the cache.
+        verify_ok(&gl.get::<::Version, _>("version?a=b", None, None));
+        assert_eq!(mock.requests().len(), 2);
+
+        gl.clear_cache();
+        assert_eq!(gl.version().unwrap().version, "9.5.2");
+        assert_eq!(mock.requests().len(), 3);
+    }
 
-        let gl = GitLab::new_insecure(".gitlab", "XXXXXXXXXXXXXXXXXXXX");
-        verify_err(&gl);
+
+    #[test]
+    fn cache_key_headers() {
+        let (gl, mock) = mock_gitlab();
+        let gl = gl.enable_cache(std::time::Duration::from_secs(60));
+        mock.push_response(Response::json(r#"{"version": "9.5.0", "revision": "a"}"#));
+        mock.push_response(Response::json(r#"{"version": "9.5.1", "revision": "b"}"#));
+        mock.push_response(Response::json(r#"{"version": "9.5.2", "revision": "c"}"#));
+
+        let get = |options: &RequestOptions| {
+            gl.get_with_options::<::Version, _>("version", None, None, options).unwrap().version
+        };
+        assert_eq!(get(&RequestOptions::new()), "9.5.0");
+        // Another `Accept` header, or any other header, is not answered from the cache.
+        assert_eq!(get(&RequestOptions::new().accept("application/vnd.example+json")),
+                   "9.5.1");
+        assert_eq!(get(&RequestOptions::new().header("Sudo", "other-user")), "9.5.2");
+        assert_eq!(get(&RequestOptions::new().header("sudo", "other-user")), "9.5.2");
+        assert_eq!(get(&RequestOptions::new()), "9.5.0");
+        assert_eq!(mock.requests().len(), 3);
     }
 
+
     #[test]
-    fn new_invalid_url_7() {
-        let gl = GitLab::new(".gitlab.com", "XXXXXXXXXXXXXXXXXXXX");
-        verify_err(&gl);
+    fn cache_evicts_expired() {
+        let (gl, mock) = mock_gitlab();
+        let gl = gl.enable_cache(std::time::Duration::from_millis(100));
+        for _ in 0..3 {
+            mock.push_response(Response::json(r#"{"version": "9.5.0", "revision": "a"}"#));
+        }
 
-        let gl = GitLab::new_insecure(".gitlab.com", "XXXXXXXXXXXXXXXXXXXX");
-        verify_err(&gl);
+        verify_ok(&gl.get::<::Version, _>("version?a=1", None, None));
+        verify_ok(&gl.get::<::Version, _>("version?a=2", None, None));
+        std::thread::sleep(std::time::Duration::from_millis(200));
+        verify_ok(&gl.get::<::Version, _>("version?a=3", None, None));
+
+        // Only the entry inserted last is kept.
+        let cache = gl.cache.lock().unwrap();
+        assert_eq!(cache.as_ref().unwrap().entries.len(), 1);
     }
 
+
     #[test]
-    fn new_invalid_url_8() {
-        let gl = GitLab::new("gitlab.", "XXXXXXXXXXXXXXXXXXXX");
-        verify_err(&gl);
+    fn cache_expired_and_errors() {
+        let (gl, mock) = mock_gitlab();
+        let gl = gl.enable_cache(std::time::Duration::from_millis(0));
+        mock.push_response(Response::json(r#"{"version": "9.5.0", "revision": "a"}"#));
+        mock.push_response(Response::json(r#"{"version": "9.5.1", "revision": "b"}"#));
+        assert_eq!(gl.version().unwrap().version, "9.5.0");
+        assert_eq!(gl.version().unwrap().version, "9.5.1");
+
+        let (gl, mock) = mock_gitlab();
+        let gl = gl.enable_cache(std::time::Duration::from_secs(60));
+        mock.push_response(Response::new(hyper::status::StatusCode::InternalServerError));
+        mock.push_response(Response::json(r#"{"version": "9.5.0", "revision": "a"}"#));
+        verify_err(&gl.version());
+        assert_eq!(gl.version().unwrap().version, "9.5.0");
+        assert_eq!(mock.requests().len(), 2);
+    }
 
-        let gl = GitLab::new_insecure("gitlab.", "XXXXXXXXXXXXXXXXXXXX");
-        verify_err(&gl);
+
+    #[test]
+    fn delete_empty_body() {
+        let (gl, mock) = mock_gitlab();
+        // DELETE responses are often empty: their body is not parsed.
+        mock.push_response(Response::new(hyper::status::StatusCode::NoContent));
+        mock.push_response(Response::new(hyper::status::StatusCode::Ok));
+
+        verify_ok(&gl.delete("projects/9"));
+        verify_ok(&gl.delete("projects/9/hooks/3"));
+
+        let requests = mock.requests();
+        assert_eq!(requests[0].method, hyper::method::Method::Delete);
+        assert_eq!(requests[0].url,
+                   "https://gitlab.example.com/api/v3/projects/9\
+                    ?private_token=XXXXXXXXXXXXXXXXXXXX");
+        assert_eq!(requests[0].body, None);
+        assert_eq!(requests[1].url,
+                   "https://gitlab.example.com/api/v3/projects/9/hooks/3\
+                    ?private_token=XXXXXXXXXXXXXXXXXXXX");
     }
 
+
     #[test]
-    fn new_invalid_url_10() {
-        let gl = GitLab::new("gitlab.com.", "XXXXXXXXXXXXXXXXXXXX");
-        verify_err(&gl);
+    fn dashboard_counts() {
+        let (gl, mock) = mock_gitlab();
+        // The requests are sent concurrently: answer each one according to its URL.
+        for &(query, total) in &[("/issues?", "12"), ("/merge_requests?", "3")] {
+            let mut response = Response::json("[{}]");
+            response.headers.set_raw("X-Total", vec![total.as_bytes().to_vec()]);
+            mock.push_response_for(query, response);
+        }
+        // Too many pipelines: GitLab did not count them.
+        mock.push_response_for("/pipelines?", Response::json("[{}]"));
+
+        let counts = gl.dashboard_counts(9).unwrap();
+        assert_eq!(counts,
+                   ::DashboardCounts {
+                       open_issues: Some(12),
+                       open_merge_requests: Some(3),
+                       successful_pipelines: None,
+                   });
+
+        let mut urls: Vec<String> = mock.requests().into_iter().map(|r| r.url).collect();
+        urls.sort();
+        assert_eq!(urls.len(), 3);
+        assert!(urls[0].starts_with("https://gitlab.example.com/api/v3/projects/9/issues\
+                                     ?state=opened&"));
+        assert!(urls[0].ends_with("&page=1&per_page=1"));
+        assert!(urls[1].starts_with("https://gitlab.example.com/api/v3/projects/9/merge_requests\
+                                     ?state=opened&"));
+        assert!(urls[2].starts_with("https://gitlab.example.com/api/v3/projects/9/pipelines\
+                                     ?status=success&"));
+
+        // An error is reported, not a missing count.
+        mock.push_response_for("/issues?", Response::json("[]"));
+        mock.push_response_for("/merge_requests?", Response::json("[]"));
+        mock.push_response_for("/pipelines?",
+                               Response::new(hyper::status::StatusCode::Forbidden));
+        verify_err(&gl.dashboard_counts(9));
+    }
 
-        let gl = GitLab::new_insecure("gitlab.com.", "XXXXXXXXXXXXXXXXXXXX");
-        verify_err(&gl);
+
+    #[test]
+    fn dashboard_counts_concurrently() {
+        let (gl, mock) = mock_gitlab();
+        for query in &["/issues?", "/merge_requests?", "/pipelines?"] {
+            mock.push_response_for(query, Response::json("[]"));
+        }
+        let delay = std::time::Duration::from_millis(300);
+        mock.set_delay(delay);
+
+        // The slow server answers the 3 requests at the same time.
+        let start = std::time::Instant::now();
+        verify_ok(&gl.dashboard_counts(9));
+        assert!(start.elapsed() < delay * 3);
     }
 
+
     #[test]
-    fn new_invalid_token() {
-        let gl = GitLab::new("gitlab.com", "");
-        verify_err(&gl);
+    fn last_response_meta() {
+        let (gl, mock) = mock_gitlab();
+        assert_eq!(gl.last_response_meta(), None);
+
+        let mut response = Response::new(hyper::status::StatusCode::InternalServerError);
+        response.headers.set_raw("X-Request-Id", vec![b"01F8MECHZX3TBDSZ7XRADM79XV".to_vec()]);
+        response.headers.set_raw("X-Gitlab-Meta", vec![b"{\"correlation_id\":\"abc\"}".to_vec()]);
+        response.headers.set_raw("X-Total", vec![b"2".to_vec()]);
+        mock.push_response(response);
+
+        verify_err(&gl.version());
+
+        let meta = gl.last_response_meta().unwrap();
+        assert_eq!(meta.request_id, Some(String::from("01F8MECHZX3TBDSZ7XRADM79XV")));
+        assert_eq!(meta.gitlab_headers,
+                   vec![(String::from("X-Gitlab-Meta"),
+                         String::from("{\"correlation_id\":\"abc\"}"))]);
+    }
 
-        let gl = GitLab::new("gitlab.com", "X");
-        verify_err(&gl);
 
-        let gl = GitLab::new("gitlab.com", "XXXXXXXXXXXXXXXXXXX");
-        verify_err(&gl);
+    #[test]
+    fn retry_transient_statuses() {
+        let (gl, mock) = mock_gitlab();
+        let gl = gl.with_retry(RetryConfig::new(2, std::time::Duration::from_millis(0)));
+        let version = r#"{"version": "9.5.0", "revision": "a"}"#;
+
+        let mut response = Response::new(hyper::status::StatusCode::TooManyRequests);
+        response.headers.set_raw("Retry-After", vec![b"0".to_vec()]);
+        mock.push_response(response);
+        mock.push_response(Response::new(hyper::status::StatusCode::BadGateway));
+        mock.push_response(Response::json(version));
+        verify_ok(&gl.version());
+        assert_eq!(mock.requests().len(), 3);
+
+        // Retries exhausted: the last response is returned.
+        for _ in 0..3 {
+            mock.push_response(Response::new(hyper::status::StatusCode::ServiceUnavailable));
+        }
+        verify_err(&gl.version());
+        assert_eq!(mock.requests().len(), 6);
+
+        // Not retried. `get()` is used since `version()` would fall back to the API v4.
+        mock.push_response(Response::new(hyper::status::StatusCode::NotFound));
+        mock.push_response(Response::json(version));
+        verify_err(&gl.get::<::Version, _>("version", None, None));
+        assert_eq!(mock.requests().len(), 7);
+    }
 
-        let gl = GitLab::new("gitlab.com", "XXXXXXXXXXXXXXXXXXXXX");
-        verify_err(&gl);
+
+    #[test]
+    fn retry_transport_errors() {
+        let (gl, mock) = mock_gitlab();
+        let gl = gl.request_options(RequestOptions::new().retries(2));
+
+        // Without any response left, the mock transport fails every request.
+        verify_err(&gl.get::<::Version, _>("version", None, None));
+        assert_eq!(mock.requests().len(), 3);
+        verify_err(&gl.delete("projects/1"));
+        assert_eq!(mock.requests().len(), 6);
+
+        // Not idempotent: sent once.
+        verify_err(&gl.post::<serde_json::Value, _>("projects", &()));
+        verify_err(&gl.put::<serde_json::Value, _>("projects/1", &()));
+        let requests = mock.requests();
+        assert_eq!(requests.len(), 8);
+        assert_eq!(requests[6].method, hyper::method::Method::Post);
+        assert_eq!(requests[7].method, hyper::method::Method::Put);
     }
 
+
     #[test]
-    fn build_url_doc() {
-        let expected_url = "https://gitlab.example.com\
-                            /api/v3/groups?order_by=path&private_token=XXXXXXXXXXXXXXXXXXXX";
-        let gl = GitLab::new("gitlab.example.com", "XXXXXXXXXXXXXXXXXXXX").unwrap();
-        let url = gl.build_url("groups?order_by=path").unwrap();
-        assert_eq!(url, expected_url);
+    fn deadline_stops_retries() {
+        let (gl, mock) = mock_gitlab();
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(60);
+        let gl = gl.with_retry(RetryConfig::new(5, std::time::Duration::from_millis(0)))
+            .request_options(RequestOptions::new().deadline(deadline));
+
+        // Waiting an hour would overrun the deadline: give up without waiting.
+        let mut response = Response::new(hyper::status::StatusCode::ServiceUnavailable);
+        response.headers.set_raw("Retry-After", vec![b"3600".to_vec()]);
+        mock.push_response(response);
+        mock.push_response(Response::json(r#"{"version": "9.5.0", "revision": "a"}"#));
+
+        let error = gl.version().unwrap_err();
+        assert!(error.iter().any(|e| e.to_string() == "deadline exceeded"));
+        assert_eq!(mock.requests().len(), 1);
+    }
+
+
+    #[test]
+    fn retry_max_elapsed() {
+        let (gl, mock) = mock_gitlab();
+        let gl = gl.with_retry(RetryConfig {
+            max_elapsed: Some(std::time::Duration::from_secs(1)),
+            seed: Some(7),
+            ..RetryConfig::new(5, std::time::Duration::from_millis(0))
+        });
+
+        // Waiting 2 seconds would exceed the budget: the response is returned right away.
+        let mut response = Response::new(hyper::status::StatusCode::TooManyRequests);
+        response.headers.set_raw("Retry-After", vec![b"2".to_vec()]);
+        mock.push_response(response);
+        mock.push_response(Response::json(r#"{"version": "9.5.0", "revision": "a"}"#));
+        verify_err(&gl.version());
+        assert_eq!(mock.requests().len(), 1);
+    }
+
+
+    #[test]
+    fn rate_limit() {
+        let (gl, mock) = mock_gitlab();
+        assert_eq!(gl.rate_limit(), None);
+
+        let mut response = Response::json(r#"{"version": "9.5.0", "revision": "a"}"#);
+        response.headers.set_raw("RateLimit-Limit", vec![b"600".to_vec()]);
+        response.headers.set_raw("RateLimit-Remaining", vec![b"0".to_vec()]);
+        response.headers.set_raw("RateLimit-Reset", vec![b"1493455560".to_vec()]);
+        mock.push_response(response);
+        mock.push_response(Response::json(r#"{"version": "9.5.0", "revision": "a"}"#));
+
+        verify_ok(&gl.version());
+        let rate_limit = gl.rate_limit().unwrap();
+        assert_eq!((rate_limit.limit, rate_limit.remaining), (600, 0));
+        assert_eq!(rate_limit.reset, ::datetime::from_timestamp(1493455560).unwrap());
+
+        // The last response had no rate limit headers.
+        verify_ok(&gl.version());
+        assert_eq!(gl.rate_limit(), None);
     }
 }
@@ -16,6 +16,30 @@
 //! | --------- | ---- | -------- | ----------- |
 //! | `id` | integer/string | yes | The ID or path of a group |
 //!
+//! # Languages
+//!
+//! Not a GitLab endpoint: the languages of all the group's projects are aggregated, weighted by
+//! the size of each project's repository.
+//!
+//! ```text
+//! GET /groups/ID/projects?statistics=true
+//! GET /projects/ID/languages
+//! ```
+//!
+//! # Update group
+//!
+//! Updates the project group. Only available to group owners and administrators.
+//!
+//! ```text
+//! PUT /groups/ID
+//! ```
+//!
+//! The parameters are the ones of group creation (see `::groups`), all optional.
+//!
+//! # List a group's subgroups
+//!
+//! See `::groups::subgroups`.
+//!
 
 
 use BuildQuery;
@@ -44,6 +68,65 @@ impl<'a> GroupLister<'a> {
 
         self.gl.get(&query, None, None).chain_err(|| format!("cannot get query {}", query))
     }
+
+    /// Query GitLab and return the languages of all the group's projects.
+    ///
+    /// The percentages of each project are weighted by the size of its repository (the projects
+    /// count equally if GitLab doesn't return their statistics). Projects without any language,
+    /// for example empty ones, are ignored.
+    ///
+    /// One request per project is sent, on top of the listing of the group's projects.
+    pub fn languages(&self) -> Result<::projects::Languages> {
+        let query = format!("{}/projects?statistics=true", self.build_query());
+        debug!("query: {:?}", query);
+
+        let mut projects: ::projects::Projects = Vec::new();
+        let mut page = 1;
+        loop {
+            let (mut items, page_info): (::projects::Projects, ::PageInfo) = self.gl
+                .get_with_headers(&query, page, ::pagination::MAX_PER_PAGE)
+                .chain_err(|| format!("cannot get query {}", query))?;
+            projects.append(&mut items);
+
+            match page_info.next_page {
+                Some(next_page) => page = next_page,
+                None => break,
+            }
+        }
+
+        let mut weighted: Vec<(::projects::Languages, f64)> = Vec::new();
+        for project in &projects {
+            let languages = self.gl
+                .projects()
+                .id(::projects::ListingId::Id(project.id))
+                .languages()
+                .chain_err(|| format!("cannot get the languages of project {}", project.id))?;
+            let weight = project.statistics
+                .as_ref()
+                .map(|statistics| statistics.repository_size as f64)
+                .unwrap_or(1.0);
+            weighted.push((languages, weight));
+        }
+
+        Ok(aggregate_languages(&weighted))
+    }
+
+    /// Return a lister for the direct subgroups of the group.
+    ///
+    /// The subgroups are paginated, see `::groups::subgroups`.
+    pub fn subgroups(&self) -> ::groups::subgroups::SubgroupsLister<'a> {
+        ::groups::subgroups::SubgroupsLister::new(self.gl, self.id.clone())
+    }
+
+    /// Update the group: only the fields set in `update` are changed.
+    ///
+    /// Returns the updated group.
+    pub fn update(&self, update: &::groups::GroupUpdate) -> Result<Group> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl.put(&query, update).chain_err(|| format!("cannot put query {}", query))
+    }
 }
 
 
@@ -51,19 +134,44 @@ impl<'a> BuildQuery for GroupLister<'a> {
     fn build_query(&self) -> String {
         let mut query = String::from("groups/");
 
-        query.push_str(&match self.id {
-            ::groups::ListingId::Id(id) => id.to_string(),
-            ::groups::ListingId::NamespaceProject(ref s) => s.replace("/", "%2F"),
-        });
+        query.push_str(&self.id.encode());
 
         query
     }
 }
 
 
+/// Weighted average of the languages' percentages.
+///
+/// If all the weights are zero (empty repositories), the projects count equally.
+fn aggregate_languages(weighted: &[(::projects::Languages, f64)]) -> ::projects::Languages {
+    let weighted: Vec<&(::projects::Languages, f64)> =
+        weighted.iter().filter(|&&(ref languages, _)| !languages.is_empty()).collect();
+    let total_weight: f64 = weighted.iter().map(|&&(_, weight)| weight).sum();
+
+    let mut aggregate = ::projects::Languages::new();
+    for &&(ref languages, weight) in &weighted {
+        let weight = if total_weight > 0.0 {
+            weight / total_weight
+        } else {
+            1.0 / weighted.len() as f64
+        };
+        for (language, percentage) in languages {
+            *aggregate.entry(language.clone()).or_insert(0.0) += percentage * weight;
+        }
+    }
+
+    aggregate
+}
+
+
 #[cfg(test)]
 mod tests {
+    use hyper;
+
     use BuildQuery;
+    use tests::mock_gitlab;
+    use transport::Response;
 
     const TEST_GROUP_ID_I64: i64 = 123;
     const TEST_GROUP_ID_STRING: &'static str = "group/project";
@@ -113,4 +221,89 @@ mod tests {
             .build_query();
         assert_eq!(query, expected_string);
     }
+
+
+    #[test]
+    fn update_ldap() {
+        let (gl, mock) = mock_gitlab();
+        mock.push_response(Response::json(::tests::TEST_GROUP_JSON));
+
+        let update = ::groups::GroupUpdate {
+            ldap_cn: Some(String::from("cn=twitter,ou=groups")),
+            ldap_access: Some(::AccessLevel::Reporter),
+            ..Default::default()
+        };
+        gl.groups().details(::groups::ListingId::Id(TEST_GROUP_ID_I64)).update(&update).unwrap();
+
+        let requests = mock.requests();
+        assert_eq!(requests[0].method, hyper::method::Method::Put);
+        assert!(requests[0].url.starts_with("https://gitlab.example.com/api/v3/groups/123?"));
+        assert_eq!(requests[0].body,
+                   Some(String::from(r#"{"ldap_cn":"cn=twitter,ou=groups","ldap_access":20}"#)));
+    }
+
+
+    fn project_json(id: i64, repository_size: i64) -> String {
+        ::tests::test_project_json()
+            .replace("\"id\": 517564,", &format!("\"id\": {},", id))
+            .replace("\"approvals_before_merge\": 0",
+                     &format!(r#""approvals_before_merge": 0,
+                                 "statistics": {{
+                                     "commit_count": 37,
+                                     "storage_size": {0},
+                                     "repository_size": {0},
+                                     "lfs_objects_size": 0
+                                 }}"#,
+                              repository_size))
+    }
+
+
+    #[test]
+    fn languages_weighted_by_repository_size() {
+        let (gl, mock) = mock_gitlab();
+        mock.push_response(Response::json(&format!("[{}, {}]",
+                                                   project_json(1, 300),
+                                                   project_json(2, 100))));
+        mock.push_response(Response::json(r#"{"Rust": 100.0}"#));
+        mock.push_response(Response::json(r#"{"Rust": 50.0, "Python": 50.0}"#));
+
+        let languages =
+            gl.groups().id(::groups::ListingId::Id(TEST_GROUP_ID_I64)).languages().unwrap();
+        assert_eq!(languages.len(), 2);
+        assert!((languages["Rust"] - 87.5).abs() < 1e-9);
+        assert!((languages["Python"] - 12.5).abs() < 1e-9);
+
+        let requests = mock.requests();
+        assert_eq!(requests.len(), 3);
+        assert!(requests[0].url
+            .starts_with("https://gitlab.example.com/api/v3/groups/123/projects?statistics=true&"));
+        assert!(requests[1]
+            .url
+            .starts_with("https://gitlab.example.com/api/v3/projects/1/languages?"));
+        assert!(requests[2]
+            .url
+            .starts_with("https://gitlab.example.com/api/v3/projects/2/languages?"));
+    }
+
+
+    #[test]
+    fn aggregate_languages_without_weights() {
+        let languages = |pairs: &[(&str, f64)]| -> ::projects::Languages {
+            pairs.iter().map(|&(language, percentage)| (language.to_string(), percentage)).collect()
+        };
+
+        // Empty repositories: the projects count equally.
+        let aggregate = super::aggregate_languages(&[(languages(&[("Rust", 100.0)]), 0.0),
+                                                     (languages(&[("C", 100.0)]), 0.0)]);
+        assert!((aggregate["Rust"] - 50.0).abs() < 1e-9);
+        assert!((aggregate["C"] - 50.0).abs() < 1e-9);
+
+        // Projects without any language don't dilute the others.
+        let aggregate = super::aggregate_languages(&[(languages(&[("Rust", 100.0)]), 10.0),
+                                                     (languages(&[]), 30.0)]);
+        assert_eq!(aggregate.len(), 1);
+        assert!((aggregate["Rust"] - 100.0).abs() < 1e-9);
+
+        assert!(super::aggregate_languages(&[]).is_empty());
+    }
 }
@@ -19,9 +19,35 @@
 //! | `search` | string | no | Return list of authorized groups matching the search criteria |
 //! | `order_by` | string | no | Order groups by `name` or `path`. Default is `name` |
 //! | `sort` | string | no | Order groups in `asc` or `desc` order. Default is `asc` |
+//! | `top_level_only` | boolean | no | Limit to top level groups, excluding all subgroups |
 //!
 //! You can search for groups by name or path.
 //!
+//! The groups are listed flattened: subgroups come along with their parents. To walk the group
+//! hierarchy, list only the root groups with `top_level_only(true)`, then descend into each of
+//! them with `gl.groups().id(..).subgroups()`, going through all the pages of subgroups.
+//!
+//! # New group
+//!
+//! Creates a new group, see `GroupsLister::create()`.
+//!
+//! ```text
+//! POST /groups
+//! ```
+//!
+//! Parameters:
+//!
+//! | Attribute | Type | Required | Description |
+//! | --------- | ---- | -------- | ----------- |
+//! | `name` | string | yes | The name of the group |
+//! | `path` | string | yes | The path of the group |
+//! | `description` | string | no | The group's description |
+//! | `visibility_level` | integer | no | The group's visibility. 0 for private, 10 for internal, 20 for public. |
+//! | `lfs_enabled` | boolean | no | Enable/disable Large File Storage (LFS) for the projects in this group |
+//! | `request_access_enabled` | boolean | no | Allow users to request member access. |
+//! | `ldap_cn` | string | no | The LDAP group to sync the group with |
+//! | `ldap_access` | integer | no | The access level given to the LDAP group's members |
+//!
 //! **NOTE**: The _Search for group_ (from
 //! https://docs.gitlab.com/ce/api/groups.html#search-for-group) is performed in this module.
 //!
@@ -31,11 +57,13 @@
 // use serde_urlencoded;
 
 use BuildQuery;
+use Group;
 use Groups;
 
 pub mod owned;
 pub mod projects;
 pub mod details;
+pub mod subgroups;
 
 use ::errors::*;
 
@@ -49,12 +77,69 @@ pub enum ListingOrderBy {
 }
 
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub enum ListingId {
-    Id(i64),
-    NamespaceProject(String),
+pub use id::ResourceId as ListingId;
+
+/// Body of a group creation.
+///
+/// Only the optional fields set are sent, GitLab uses its defaults for the others.
+#[derive(Debug, Clone, Serialize)]
+pub struct GroupCreate {
+    pub name: String,
+    pub path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub visibility_level: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lfs_enabled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_access_enabled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ldap_cn: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ldap_access: Option<::AccessLevel>,
 }
 
+impl GroupCreate {
+    pub fn new(name: String, path: String) -> GroupCreate {
+        GroupCreate {
+            name: name,
+            path: path,
+            description: None,
+            visibility_level: None,
+            lfs_enabled: None,
+            request_access_enabled: None,
+            ldap_cn: None,
+            ldap_access: None,
+        }
+    }
+}
+
+
+/// Body of a group update.
+///
+/// Only the fields set are sent to GitLab, the others are left unchanged.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct GroupUpdate {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub visibility_level: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lfs_enabled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_access_enabled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ldap_cn: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ldap_access: Option<::AccessLevel>,
+}
+
+
 #[derive(Default, Debug, Clone, Serialize, Deserialize)]
 struct GroupsListerInternal {
     /// Skip the group IDs passes
@@ -67,6 +152,8 @@ struct GroupsListerInternal {
     order_by: Option<ListingOrderBy>,
     /// Order groups in `asc` or `desc` order. Default is `asc`
     sort: Option<::ListingSort>,
+    /// Limit to top level groups, excluding all subgroups
+    top_level_only: Option<bool>,
 }
 
 #[derive(Default, Debug, Clone, Serialize, Deserialize)]
@@ -104,6 +191,7 @@ impl<'a> GroupsLister<'a> {
                 search: None,
                 order_by: None,
                 sort: None,
+                top_level_only: None,
             },
         }
     }
@@ -114,6 +202,11 @@ impl<'a> GroupsLister<'a> {
         details::GroupLister::new(self.gl, id)
     }
 
+    /// Same as `details()`.
+    pub fn id(self, id: ListingId) -> details::GroupLister<'a> {
+        self.details(id)
+    }
+
     pub fn owned(self) -> owned::GroupsLister<'a> {
         // assert_eq!(self, GroupsLister::new(self.gl));
         owned::GroupsLister::new(self.gl)
@@ -150,13 +243,29 @@ impl<'a> GroupsLister<'a> {
         self
     }
 
+    /// Only list the root groups, not their subgroups (see the module's documentation).
+    pub fn top_level_only(&'a mut self, top_level_only: bool) -> &'a mut GroupsLister {
+        self.internal.top_level_only = Some(top_level_only);
+        self
+    }
+
 
     /// Commit the lister: Query GitLab and return a list of groups.
     pub fn list(&self) -> Result<Groups> {
         let query = self.build_query();
         debug!("query: {:?}", query);
 
-        self.gl.get(&query, None, None).chain_err(|| format!("cannot get query {}", query))
+        self.gl.get_listing(&query).chain_err(|| format!("cannot get query {}", query))
+    }
+
+    /// Create a new group.
+    ///
+    /// Returns the created group.
+    pub fn create(&self, group: &GroupCreate) -> Result<Group> {
+        let query = "groups";
+        debug!("query: {:?}", query);
+
+        self.gl.post(query, group).chain_err(|| format!("cannot post query {}", query))
     }
 }
 
@@ -179,8 +288,9 @@ impl<'a> BuildQuery for GroupsLister<'a> {
                               &self.internal.all_available,
                               &self.internal.search,
                               &self.internal.order_by,
-                              &self.internal.sort) {
-            (&None, &None, &None, &None, &None) => "",
+                              &self.internal.sort,
+                              &self.internal.top_level_only) {
+            (&None, &None, &None, &None, &None, &None) => "",
             _ => "?",
         });
 
@@ -238,6 +348,17 @@ impl<'a> BuildQuery for GroupsLister<'a> {
             });
         });
 
+        self.internal.top_level_only.map(|top_level_only| {
+            query.push_str(split_char);
+            split_char = &amp_char;
+
+            if top_level_only {
+                query.push_str("top_level_only=true")
+            } else {
+                query.push_str("top_level_only=false")
+            }
+        });
+
         query
     }
 }
@@ -245,6 +366,8 @@ impl<'a> BuildQuery for GroupsLister<'a> {
 
 #[cfg(test)]
 mod tests {
+    use serde_json;
+
     use BuildQuery;
 
 
@@ -301,6 +424,21 @@ mod tests {
     }
 
 
+    #[test]
+    fn groups_build_query_top_level_only() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+        // let gl: ::GitLab = Default::default();
+
+        let expected_string = "groups?top_level_only=true";
+        let query = gl.groups().top_level_only(true).build_query();
+        assert_eq!(query, expected_string);
+
+        let expected_string = "groups?all_available=true&top_level_only=true";
+        let query = gl.groups().all_available(true).top_level_only(true).build_query();
+        assert_eq!(query, expected_string);
+    }
+
+
     #[test]
     fn groups_build_query_order_by_name() {
         let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
@@ -355,4 +493,21 @@ mod tests {
             .build_query();
         assert_eq!(query, expected_string);
     }
+
+
+    #[test]
+    fn serialize_group_create() {
+        let group = ::groups::GroupCreate::new(String::from("Twitter"), String::from("twitter"));
+        assert_eq!(serde_json::to_string(&group).unwrap(),
+                   r#"{"name":"Twitter","path":"twitter"}"#);
+
+        let group = ::groups::GroupCreate {
+            ldap_cn: Some(String::from("cn=twitter,ou=groups")),
+            ldap_access: Some(::AccessLevel::Master),
+            ..::groups::GroupCreate::new(String::from("Twitter"), String::from("twitter"))
+        };
+        assert_eq!(serde_json::to_string(&group).unwrap(),
+                   concat!(r#"{"name":"Twitter","path":"twitter","#,
+                           r#""ldap_cn":"cn=twitter,ou=groups","ldap_access":40}"#));
+    }
 }
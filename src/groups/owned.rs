@@ -33,7 +33,7 @@ impl<'a> GroupsLister<'a> {
         let query = self.build_query();
         debug!("query: {:?}", query);
 
-        self.gl.get(&query, None, None).chain_err(|| format!("cannot get query {}", query))
+        self.gl.get_listing(&query).chain_err(|| format!("cannot get query {}", query))
     }
 }
 
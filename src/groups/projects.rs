@@ -94,7 +94,7 @@ impl<'a> ProjectsLister<'a> {
         let query = self.build_query();
         debug!("query: {:?}", query);
 
-        self.gl.get(&query, None, None).chain_err(|| format!("cannot get query {}", query))
+        self.gl.get_listing(&query).chain_err(|| format!("cannot get query {}", query))
     }
 }
 
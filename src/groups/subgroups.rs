@@ -0,0 +1,124 @@
+//! List a group's subgroups
+//!
+//! https://docs.gitlab.com/ce/api/groups.html#list-a-group-s-subgroups
+//!
+//! # List a group's subgroups
+//!
+//! Get a list of the direct subgroups of a group. Their own subgroups are not listed.
+//!
+//! ```text
+//! GET /groups/ID/subgroups
+//! ```
+//!
+//! The subgroups are paginated: `list()` only returns the first page, use the `Lister`'s
+//! `paginated_iter()` or `pagination::list_all()` to get all of them.
+
+
+use BuildQuery;
+use Groups;
+use Lister;
+use PageInfo;
+
+use ::errors::*;
+
+
+#[derive(Debug, Clone)]
+pub struct SubgroupsLister<'a> {
+    gl: &'a ::GitLab,
+    /// The ID of the parent group
+    id: ::groups::ListingId,
+}
+
+
+impl<'a> SubgroupsLister<'a> {
+    pub fn new(gl: &'a ::GitLab, id: ::groups::ListingId) -> SubgroupsLister {
+        SubgroupsLister { gl: gl, id: id }
+    }
+}
+
+
+impl<'a> Lister<Groups> for SubgroupsLister<'a> {
+    /// Commit the lister: Query GitLab and return the first page of subgroups.
+    fn list(&self) -> Result<Groups> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl.get_listing(&query).chain_err(|| format!("cannot get query {}", query))
+    }
+
+    fn list_paginated(&self, page: u16, per_page: u16) -> Result<Groups> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl.get(&query, page, per_page).chain_err(|| format!("cannot get query {}", query))
+    }
+
+    /// Commit the lister: Query GitLab and return a page of subgroups with its pagination
+    /// information.
+    fn list_page(&self, page: u16, per_page: u16) -> Result<(Groups, PageInfo)> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl
+            .get_with_headers(&query, page, per_page)
+            .chain_err(|| format!("cannot get query {}", query))
+    }
+}
+
+
+impl<'a> BuildQuery for SubgroupsLister<'a> {
+    fn build_query(&self) -> String {
+        format!("groups/{}/subgroups", self.id.encode())
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use BuildQuery;
+    use Lister;
+    use tests::mock_gitlab;
+    use transport::Response;
+
+    const TEST_GROUP_ID_I64: i64 = 123;
+
+
+    #[test]
+    fn build_query_default() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+
+        let expected_string = format!("groups/{}/subgroups", TEST_GROUP_ID_I64);
+        let query = gl.groups()
+            .id(::groups::ListingId::Id(TEST_GROUP_ID_I64))
+            .subgroups()
+            .build_query();
+        assert_eq!(query, expected_string);
+
+        let query = gl.groups()
+            .id(::groups::ListingId::NamespaceProject(String::from("group/subgroup")))
+            .subgroups()
+            .build_query();
+        assert_eq!(query, "groups/group%2Fsubgroup/subgroups");
+    }
+
+
+    #[test]
+    fn all_pages() {
+        let (gl, mock) = mock_gitlab();
+        let group = ::tests::TEST_GROUP_JSON;
+        let mut response = Response::json(&format!("[{}, {}]", group, group));
+        response.headers.set_raw("X-Next-Page", vec![b"2".to_vec()]);
+        mock.push_response(response);
+        mock.push_response(Response::json(&format!("[{}]", group)));
+
+        let lister = gl.groups().id(::groups::ListingId::Id(TEST_GROUP_ID_I64)).subgroups();
+        let subgroups: Vec<::Group> =
+            lister.paginated_iter(2).map(|group| group.unwrap()).collect();
+        assert_eq!(subgroups.len(), 3);
+
+        let requests = mock.requests();
+        assert_eq!(requests.len(), 2);
+        assert!(requests[1].url.contains("/groups/123/subgroups?"));
+        assert!(requests[1].url.ends_with("&page=2&per_page=2"));
+    }
+}
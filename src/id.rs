@@ -0,0 +1,71 @@
+//! Identify a GitLab resource (project, group, ...) in a query's path.
+//!
+//! GitLab accepts either the numerical ID of a resource or its URL-encoded path, for example
+//! `namespace%2Fproject` for the project `namespace/project`.
+
+
+use url::percent_encoding::{utf8_percent_encode, PATH_SEGMENT_ENCODE_SET};
+
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ResourceId {
+    /// The numerical ID of the resource
+    Id(i64),
+    /// The full path of the resource, including its namespace(s)
+    NamespaceProject(String),
+}
+
+
+impl ResourceId {
+    /// Encode the ID so it can be used as a single segment of a query's path.
+    pub fn encode(&self) -> String {
+        match *self {
+            ResourceId::Id(id) => id.to_string(),
+            ResourceId::NamespaceProject(ref path) => encode_path_segment(path),
+        }
+    }
+}
+
+
+/// URL-encode `segment` so it can be used as a single segment of a query's path.
+///
+/// Slashes are encoded too, as GitLab expects for paths like `namespace/project` or for wiki
+/// pages' slugs.
+pub fn encode_path_segment(segment: &str) -> String {
+    utf8_percent_encode(segment, PATH_SEGMENT_ENCODE_SET).to_string()
+}
+
+
+#[cfg(test)]
+mod tests {
+    use id::ResourceId;
+
+
+    #[test]
+    fn encode_id() {
+        assert_eq!(ResourceId::Id(123).encode(), "123");
+    }
+
+
+    #[test]
+    fn encode_path() {
+        let id = ResourceId::NamespaceProject(String::from("group/project"));
+        assert_eq!(id.encode(), "group%2Fproject");
+
+        let id = ResourceId::NamespaceProject(String::from("group/subgroup/project"));
+        assert_eq!(id.encode(), "group%2Fsubgroup%2Fproject");
+
+        let id = ResourceId::NamespaceProject(String::from("project"));
+        assert_eq!(id.encode(), "project");
+    }
+
+
+    #[test]
+    fn encode_path_special_characters() {
+        let id = ResourceId::NamespaceProject(String::from("group/my project%"));
+        assert_eq!(id.encode(), "group%2Fmy%20project%25");
+
+        let id = ResourceId::NamespaceProject(String::from("group/project.rs"));
+        assert_eq!(id.encode(), "group%2Fproject.rs");
+    }
+}
@@ -0,0 +1,186 @@
+//! Edit an issue
+//!
+//! https://docs.gitlab.com/ce/api/issues.html#edit-issue
+//!
+//! # Edit issue
+//!
+//! Updates an existing project issue. This call is also used to mark an issue as closed or to
+//! reopen it.
+//!
+//! ```text
+//! PUT /projects/ID/issues/ISSUE_ID
+//! ```
+//!
+//! | Attribute | Type | Required | Description |
+//! | --------- | ---- | -------- | ----------- |
+//! | `id`      | integer | yes   | The ID of a project |
+//! | `issue_id`| integer | yes   | The ID of a project's issue |
+//! | `title`   | string  | no    | The title of an issue |
+//! | `description` | string | no | The description of an issue |
+//! | `assignee_id` | integer | no | The ID of a user to assign the issue to |
+//! | `labels`  | string  | no    | Comma-separated label names for an issue. Set to an empty string to unassign all labels |
+//! | `state_event` | string | no | The state event of an issue. Set `close` to close the issue and `reopen` to reopen it |
+//!
+//! Only the attributes set on the `IssueEditor` are sent: the others are left unchanged.
+
+
+
+use BuildQuery;
+
+use ::errors::*;
+
+
+/// The changes to make to an issue.
+#[derive(Debug, Clone, Serialize)]
+pub struct IssueEditor<'a> {
+    #[serde(skip_serializing)]
+    gl: &'a ::GitLab,
+    /// The ID of a project
+    #[serde(skip_serializing)]
+    id: i64,
+    /// The ID of a project's issue
+    #[serde(skip_serializing)]
+    issue_id: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    assignee_id: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none", serialize_with = "::serialize_labels")]
+    labels: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    state_event: Option<::issues::StateEvent>,
+}
+
+
+impl<'a> IssueEditor<'a> {
+    pub fn new(gl: &'a ::GitLab, id: i64, issue_id: i64) -> IssueEditor {
+        IssueEditor {
+            gl: gl,
+            id: id,
+            issue_id: issue_id,
+            title: None,
+            description: None,
+            assignee_id: None,
+            labels: None,
+            state_event: None,
+        }
+    }
+
+    pub fn title(&'a mut self, title: String) -> &'a mut IssueEditor {
+        self.title = Some(title);
+        self
+    }
+
+    pub fn description(&'a mut self, description: String) -> &'a mut IssueEditor {
+        self.description = Some(description);
+        self
+    }
+
+    pub fn assignee_id(&'a mut self, assignee_id: i64) -> &'a mut IssueEditor {
+        self.assignee_id = Some(assignee_id);
+        self
+    }
+
+    /// Replace the issue's labels. An empty list removes all the labels.
+    pub fn labels(&'a mut self, labels: Vec<String>) -> &'a mut IssueEditor {
+        self.labels = Some(labels);
+        self
+    }
+
+    /// Close or reopen the issue.
+    pub fn state_event(&'a mut self, state_event: ::issues::StateEvent) -> &'a mut IssueEditor {
+        self.state_event = Some(state_event);
+        self
+    }
+
+    /// Commit the editor: Update the issue in GitLab and return the updated issue.
+    pub fn update(&self) -> Result<::issues::Issue> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl.put(&query, self).chain_err(|| format!("cannot put query {}", query))
+    }
+}
+
+
+impl<'a> BuildQuery for IssueEditor<'a> {
+    fn build_query(&self) -> String {
+        format!("projects/{}/issues/{}", self.id, self.issue_id)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use hyper;
+    use serde_json;
+
+    use BuildQuery;
+    use issues::StateEvent;
+    use issues::tests::TEST_ISSUE_JSON;
+    use tests::mock_gitlab;
+    use transport::Response;
+
+    const TEST_PROJECT_ID: i64 = 123;
+    const TEST_ISSUE_ID: i64 = 456;
+
+
+    #[test]
+    fn build_query_default() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+        // let gl: ::GitLab = Default::default();
+
+        let expected_string = format!("projects/{}/issues/{}", TEST_PROJECT_ID, TEST_ISSUE_ID);
+        let query = gl.issues().edit(TEST_PROJECT_ID, TEST_ISSUE_ID).build_query();
+        assert_eq!(query, expected_string);
+    }
+
+
+    #[test]
+    fn serialize_only_set_fields() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+
+        let mut editor = gl.issues().edit(TEST_PROJECT_ID, TEST_ISSUE_ID);
+        assert_eq!(serde_json::to_string(&editor).unwrap(), "{}");
+        let editor = editor.state_event(StateEvent::Close);
+        assert_eq!(serde_json::to_string(&*editor).unwrap(),
+                   r#"{"state_event":"close"}"#);
+
+        let mut editor = gl.issues().edit(TEST_PROJECT_ID, TEST_ISSUE_ID);
+        let editor = editor.title(String::from("Fix the login page"))
+            .assignee_id(5)
+            .labels(vec![String::from("bug"), String::from("ui")]);
+        assert_eq!(serde_json::to_string(&*editor).unwrap(),
+                   r#"{"title":"Fix the login page","assignee_id":5,"labels":"bug,ui"}"#);
+
+        // An empty list removes all the labels.
+        let mut editor = gl.issues().edit(TEST_PROJECT_ID, TEST_ISSUE_ID);
+        let editor = editor.labels(vec![]);
+        assert_eq!(serde_json::to_string(&*editor).unwrap(), r#"{"labels":""}"#);
+    }
+
+
+    #[test]
+    fn close_and_reopen() {
+        let (gl, mock) = mock_gitlab();
+        let closed = TEST_ISSUE_JSON.replace("\"state\": \"opened\"", "\"state\": \"closed\"");
+        mock.push_response(Response::json(&closed));
+        mock.push_response(Response::json(TEST_ISSUE_JSON));
+
+        let issue = gl.close_issue(TEST_PROJECT_ID, TEST_ISSUE_ID).unwrap();
+        assert_eq!(issue.state, ::issues::IssueState::Closed);
+        let issue = gl.reopen_issue(TEST_PROJECT_ID, TEST_ISSUE_ID).unwrap();
+        assert_eq!(issue.state, ::issues::IssueState::Opened);
+
+        let requests = mock.requests();
+        assert_eq!(requests[0].method, hyper::method::Method::Put);
+        let expected_url = format!("https://gitlab.example.com/api/v3/projects/{}/issues/{}?",
+                                   TEST_PROJECT_ID,
+                                   TEST_ISSUE_ID);
+        assert!(requests[0].url.starts_with(&expected_url));
+        assert_eq!(requests[0].body, Some(String::from(r#"{"state_event":"close"}"#)));
+        assert_eq!(requests[1].body, Some(String::from(r#"{"state_event":"reopen"}"#)));
+    }
+}
@@ -10,6 +10,7 @@
 //! GET /groups/ID/issues
 //! GET /groups/ID/issues?state=opened
 //! GET /groups/ID/issues?state=closed
+//! GET /groups/ID/issues?state=all
 //! GET /groups/ID/issues?labels=foo
 //! GET /groups/ID/issues?labels=foo,bar
 //! GET /groups/ID/issues?labels=foo,bar&state=opened
@@ -20,7 +21,7 @@
 //! | Attribute | Type | Required | Description |
 //! | --------- | ---- | -------- | ----------- |
 //! | `id`      | integer | yes   | The ID of a group |
-//! | `state`   | string  | no    | Return all issues or just those that are `opened` or `closed`|
+//! | `state`   | string  | no    | Return `all` issues or just those that are `opened` or `closed`|
 //! | `labels`  | string  | no    | Comma-separated list of label names, issues must have all labels to be returned |
 //! | `milestone` | string| no    | The milestone title |
 //! | `order_by`| string  | no    | Return requests ordered by `created_at` or `updated_at` fields. Default is `created_at` |
@@ -60,7 +61,7 @@ impl<'a> IssuesLister<'a> {
     }
 
 
-    pub fn state(&'a mut self, state: ::issues::State) -> &'a mut IssuesLister {
+    pub fn state(&'a mut self, state: ::issues::ListingState) -> &'a mut IssuesLister {
         self.internal.state = Some(state);
         self
     }
@@ -91,7 +92,7 @@ impl<'a> IssuesLister<'a> {
         let query = self.build_query();
         debug!("query: {:?}", query);
 
-        self.gl.get(&query, None, None).chain_err(|| format!("cannot get query {}", query))
+        self.gl.get_listing(&query).chain_err(|| format!("cannot get query {}", query))
     }
 }
 
@@ -127,8 +128,9 @@ impl<'a> BuildQuery for IssuesLister<'a> {
 
             query.push_str("state=");
             query.push_str(match state {
-                ::issues::State::Opened => "opened",
-                ::issues::State::Closed => "closed",
+                ::issues::ListingState::Opened => "opened",
+                ::issues::ListingState::Closed => "closed",
+                ::issues::ListingState::All => "all",
             });
         });
 
@@ -214,11 +216,24 @@ mod tests {
         // let gl: ::GitLab = Default::default();
 
         let expected_string = "groups/123/issues?state=opened";
-        let query = gl.issues().group(TEST_PROJECT_ID).state(::issues::State::Opened).build_query();
+        let query = gl.issues()
+            .group(TEST_PROJECT_ID)
+            .state(::issues::ListingState::Opened)
+            .build_query();
         assert_eq!(query, expected_string);
 
         let expected_string = "groups/123/issues?state=closed";
-        let query = gl.issues().group(TEST_PROJECT_ID).state(::issues::State::Closed).build_query();
+        let query = gl.issues()
+            .group(TEST_PROJECT_ID)
+            .state(::issues::ListingState::Closed)
+            .build_query();
+        assert_eq!(query, expected_string);
+
+        let expected_string = "groups/123/issues?state=all";
+        let query = gl.issues()
+            .group(TEST_PROJECT_ID)
+            .state(::issues::ListingState::All)
+            .build_query();
         assert_eq!(query, expected_string);
     }
 
@@ -0,0 +1,299 @@
+//! Issue links
+//!
+//! https://docs.gitlab.com/ee/api/issue_links.html
+//!
+//! # List issue relations
+//!
+//! Get a list of related issues of a given issue, sorted by the relationship creation datetime
+//! (ascending).
+//!
+//! ```text
+//! GET /projects/ID/issues/ISSUE_IID/links
+//! ```
+//!
+//! # Create an issue link
+//!
+//! Creates a two-way relation between two issues.
+//!
+//! ```text
+//! POST /projects/ID/issues/ISSUE_IID/links
+//! ```
+//!
+//! | Attribute | Type | Required | Description |
+//! | --------- | ---- | -------- | ----------- |
+//! | `id` | integer/string | yes | The ID or `NAMESPACE/PROJECT_NAME` of the project |
+//! | `issue_iid` | integer | yes | The internal ID of a project's issue |
+//! | `target_project_id` | integer/string | yes | The ID of the project of the target issue |
+//! | `target_issue_iid` | integer | yes | The internal ID of the target project's issue |
+//!
+//! # Delete an issue link
+//!
+//! Deletes an issue link, removing the two-way relationship.
+//!
+//! ```text
+//! DELETE /projects/ID/issues/ISSUE_IID/links/ISSUE_LINK_ID
+//! ```
+
+
+use serde;
+use serde_json;
+
+use BuildQuery;
+
+use issues::Issue;
+
+use ::errors::*;
+
+
+/// An issue related to another one.
+#[derive(Debug)]
+pub struct IssueLink {
+    /// The ID of the relation, used to delete it
+    pub issue_link_id: i64,
+    /// For example `relates_to` (only returned by recent GitLab versions)
+    pub link_type: Option<String>,
+    /// The related issue
+    pub issue: Issue,
+}
+
+pub type IssueLinks = Vec<IssueLink>;
+
+
+impl serde::Deserialize for IssueLink {
+    fn deserialize<D>(deserializer: D) -> ::std::result::Result<IssueLink, D::Error>
+        where D: serde::Deserializer
+    {
+        // GitLab returns the related issue with the link's fields added to it.
+        let value: serde_json::Value = serde::Deserialize::deserialize(deserializer)?;
+
+        let issue_link_id = match value.get("issue_link_id").and_then(|id| id.as_i64()) {
+            Some(issue_link_id) => issue_link_id,
+            None => {
+                return Err(<D::Error as serde::de::Error>::custom("missing field `issue_link_id`"))
+            }
+        };
+        let link_type = value.get("link_type").and_then(|t| t.as_str()).map(String::from);
+        let issue = serde_json::from_value(value)
+            .map_err(|e| <D::Error as serde::de::Error>::custom(e.to_string()))?;
+
+        Ok(IssueLink {
+            issue_link_id: issue_link_id,
+            link_type: link_type,
+            issue: issue,
+        })
+    }
+}
+
+
+/// The two issues of a created (or deleted) link.
+#[derive(Debug, Deserialize)]
+pub struct IssueLinkCreated {
+    pub source_issue: Issue,
+    pub target_issue: Issue,
+    pub link_type: Option<String>,
+}
+
+
+#[derive(Debug, Serialize)]
+struct IssueLinkCreateInternal {
+    target_project_id: i64,
+    target_issue_iid: i64,
+}
+
+
+/// An issue of a project, identified by its `iid`.
+#[derive(Debug, Clone)]
+pub struct ProjectIssueLister<'a> {
+    gl: &'a ::GitLab,
+    id: ::projects::ListingId,
+    iid: i64,
+}
+
+
+impl<'a> ProjectIssueLister<'a> {
+    pub fn new(gl: &'a ::GitLab, id: ::projects::ListingId, iid: i64) -> ProjectIssueLister {
+        ProjectIssueLister {
+            gl: gl,
+            id: id,
+            iid: iid,
+        }
+    }
+
+    /// Return a lister for the issues related to this one.
+    pub fn links(self) -> IssueLinksLister<'a> {
+        IssueLinksLister::new(self.gl, self.id, self.iid)
+    }
+}
+
+
+impl<'a> BuildQuery for ProjectIssueLister<'a> {
+    fn build_query(&self) -> String {
+        let mut query = String::from("projects/");
+
+        query.push_str(&self.id.encode());
+        query.push_str("/issues/");
+        query.push_str(&self.iid.to_string());
+
+        query
+    }
+}
+
+
+#[derive(Debug, Clone)]
+pub struct IssueLinksLister<'a> {
+    gl: &'a ::GitLab,
+    id: ::projects::ListingId,
+    iid: i64,
+}
+
+
+impl<'a> IssueLinksLister<'a> {
+    pub fn new(gl: &'a ::GitLab, id: ::projects::ListingId, iid: i64) -> IssueLinksLister {
+        IssueLinksLister {
+            gl: gl,
+            id: id,
+            iid: iid,
+        }
+    }
+
+
+    /// Commit the lister: Query GitLab and return the related issues.
+    pub fn list(&self) -> Result<IssueLinks> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl.get_listing(&query).chain_err(|| format!("cannot get query {}", query))
+    }
+
+    /// Relate the issue to the issue `target_iid` of the project `target_project_id`.
+    pub fn create_link(&self, target_project_id: i64, target_iid: i64) -> Result<IssueLinkCreated> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        let body = IssueLinkCreateInternal {
+            target_project_id: target_project_id,
+            target_issue_iid: target_iid,
+        };
+
+        self.gl.post(&query, &body).chain_err(|| format!("cannot post query {}", query))
+    }
+
+    /// Remove the relation `link_id` (see `IssueLink::issue_link_id`).
+    pub fn delete_link(&self, link_id: i64) -> Result<()> {
+        let query = self.build_link_query(link_id);
+        debug!("query: {:?}", query);
+
+        self.gl.delete(&query).chain_err(|| format!("cannot delete query {}", query))
+    }
+
+    fn build_link_query(&self, link_id: i64) -> String {
+        format!("{}/{}", self.build_query(), link_id)
+    }
+}
+
+
+impl<'a> BuildQuery for IssueLinksLister<'a> {
+    fn build_query(&self) -> String {
+        let mut query = String::from("projects/");
+
+        query.push_str(&self.id.encode());
+        query.push_str("/issues/");
+        query.push_str(&self.iid.to_string());
+        query.push_str("/links");
+
+        query
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use serde_json;
+
+    use BuildQuery;
+    use issues::links::IssueLinks;
+    use issues::tests::TEST_ISSUE_JSON;
+    use tests::mock_gitlab;
+    use transport::Response;
+
+    const TEST_PROJECT_ID: i64 = 123;
+    const TEST_PROJECT_NAME: &'static str = "group/project";
+    const TEST_ISSUE_IID: i64 = 7;
+
+
+    #[test]
+    fn build_query_links() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+        // let gl: ::GitLab = Default::default();
+
+        let expected_string = format!("projects/{}/issues/{}", TEST_PROJECT_ID, TEST_ISSUE_IID);
+        let query = gl.projects()
+            .id(::projects::ListingId::Id(TEST_PROJECT_ID))
+            .issue(TEST_ISSUE_IID)
+            .build_query();
+        assert_eq!(query, expected_string);
+
+        let expected_string = format!("projects/{}/issues/{}/links",
+                                      TEST_PROJECT_ID,
+                                      TEST_ISSUE_IID);
+        let query = gl.projects()
+            .id(::projects::ListingId::Id(TEST_PROJECT_ID))
+            .issue(TEST_ISSUE_IID)
+            .links()
+            .build_query();
+        assert_eq!(query, expected_string);
+
+        let expected_string = "projects/group%2Fproject/issues/7/links/42";
+        let lister = gl.projects()
+            .id(::projects::ListingId::NamespaceProject(TEST_PROJECT_NAME.to_string()))
+            .issue(TEST_ISSUE_IID)
+            .links();
+        assert_eq!(lister.build_link_query(42), expected_string);
+    }
+
+
+    #[test]
+    fn create_link() {
+        let (gl, mock) = mock_gitlab();
+        let mut response = Response::json(&format!(r#"{{"source_issue": {0},
+                                                        "target_issue": {0},
+                                                        "link_type": "relates_to"}}"#,
+                                                   TEST_ISSUE_JSON));
+        response.status = ::hyper::status::StatusCode::Created;
+        mock.push_response(response);
+
+        let created = gl.projects()
+            .id(::projects::ListingId::Id(TEST_PROJECT_ID))
+            .issue(TEST_ISSUE_IID)
+            .links()
+            .create_link(5, 3)
+            .unwrap();
+        assert_eq!(created.link_type, Some(String::from("relates_to")));
+
+        let requests = mock.requests();
+        assert!(requests[0]
+            .url
+            .starts_with("https://gitlab.example.com/api/v3/projects/123/issues/7/links?"));
+        assert_eq!(requests[0].body,
+                   Some(String::from(r#"{"target_project_id":5,"target_issue_iid":3}"#)));
+    }
+
+
+    #[test]
+    fn deserialize_issue_links() {
+        let json_reply = format!("[{}]",
+                                 TEST_ISSUE_JSON.replace("\"confidential\": false,",
+                                                         "\"confidential\": false,
+                                                          \"issue_link_id\": 42,
+                                                          \"link_type\": \"relates_to\","));
+
+        let links: IssueLinks = serde_json::from_str(&json_reply)
+            .expect("JSON deserialization failed");
+        assert_eq!(links[0].issue_link_id, 42);
+        assert_eq!(links[0].link_type, Some(String::from("relates_to")));
+        assert_eq!(links[0].issue.confidential, false);
+
+        let json_reply = format!("[{}]", TEST_ISSUE_JSON);
+        assert!(serde_json::from_str::<IssueLinks>(&json_reply).is_err());
+    }
+}
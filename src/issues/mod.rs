@@ -12,7 +12,7 @@
 //!
 //! | Attribute | Type | Required | Description |
 //! | --------- | ---- | -------- | ----------- |
-//! | `state`   | string  | no    | Return all issues or just those that are `opened` or `closed`|
+//! | `state`   | string  | no    | Return `all` issues or just those that are `opened` or `closed`|
 //! | `labels`  | string  | no    | Comma-separated list of label names, issues with any of the labels will be returned |
 //! | `order_by`| string  | no    | Return requests ordered by `created_at` or `updated_at` fields. Default is `created_at` |
 //! | `sort`    | string  | no    | Return requests sorted in `asc` or `desc` order. Default is `desc`  |
@@ -23,22 +23,40 @@
 
 use BuildQuery;
 use Lister;
+use PageInfo;
 
+pub mod edit;
 pub mod group;
+pub mod links;
 pub mod project;
 pub mod single;
 
 use ::errors::*;
 
 
-#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
-pub enum State {
+/// State of the issues to list.
+///
+/// This is distinct from `merge_requests::State`, which also has a `Merged` state.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ListingState {
+    /// Only list the opened issues (`state=opened`)
     #[serde(rename = "opened")]
     Opened,
+    /// Only list the closed issues (`state=closed`)
     #[serde(rename = "closed")]
     Closed,
+    /// List all issues (`state=all`)
+    #[serde(rename = "all")]
+    All,
 }
 
+/// Former name of `ListingState`.
+pub use self::ListingState as State;
+
+
+/// Change of state of an issue, see `edit::IssueEditor`.
+pub use StateEvent;
+
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub enum ListingOrderBy {
@@ -52,7 +70,7 @@ pub enum ListingOrderBy {
 #[derive(Default, Debug, Clone, Serialize, Deserialize)]
 struct IssuesListerInternal {
     /// State of issues to return.
-    state: Option<State>,
+    state: Option<ListingState>,
     /// Labels of issues to return.
     labels: Option<Vec<String>>,
     /// Return requests ordered by. Default is `ListingOrderBy::CreatedAt`.
@@ -65,7 +83,7 @@ struct IssuesListerInternal {
 #[derive(Default, Debug, Clone, Serialize, Deserialize)]
 struct GroupIssuesListerInternal {
     /// State of issues to return.
-    state: Option<State>,
+    state: Option<ListingState>,
     /// Labels of issues to return.
     labels: Option<Vec<String>>,
     /// The milestone title
@@ -81,11 +99,13 @@ struct GroupIssuesListerInternal {
 struct ProjectsIssuesListerInternal {
     iid: Option<i64>,
     /// State of issues to return.
-    state: Option<State>,
+    state: Option<ListingState>,
     /// Labels of issues to return.
     labels: Option<Vec<String>>,
     /// The milestone title
     milestone: Option<String>,
+    /// Assignee of issues to return.
+    assignee_id: Option<::AssigneeFilter>,
     /// Return requests ordered by. Default is `ListingOrderBy::CreatedAt`.
     order_by: Option<ListingOrderBy>,
     /// Return requests sorted. Default is `::ListingSort::Desc`.
@@ -93,7 +113,7 @@ struct ProjectsIssuesListerInternal {
 }
 
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
 pub enum IssueState {
     #[serde(rename = "opened")]
     Opened,
@@ -112,24 +132,44 @@ pub struct Issue {
     pub title: String,
     pub description: String,
     pub state: IssueState,
-    pub created_at: String,  // FIXME: Use date type?
-    pub updated_at: String,  // FIXME: Use date type?
+    #[serde(with = "::datetime")]
+    pub created_at: ::datetime::DateTime,
+    #[serde(with = "::datetime")]
+    pub updated_at: ::datetime::DateTime,
     pub labels: Vec<String>,
     pub milestone: Option<::Milestone>,
     pub assignee: Option<::User>,
     pub author: ::User,
     pub subscribed: bool,
     pub user_notes_count: i64,
+    /// Number of upvotes (`0` when not returned by GitLab)
+    #[serde(default)]
     pub upvotes: i64,
+    /// Number of downvotes (`0` when not returned by GitLab)
+    #[serde(default)]
     pub downvotes: i64,
-    pub due_date: Option<String>,  // FIXME: Use date type?
+    #[serde(default, with = "::datetime::option_date")]
+    pub due_date: Option<::datetime::DateTime>,
     pub confidential: bool,
-    pub web_url: Option<String>
+    pub web_url: Option<String>,
+    /// Only returned by GitLab versions supporting discussion locking.
+    pub discussion_locked: Option<bool>,
+    #[serde(rename = "_links")]
+    pub links: Option<::Links>,
+    /// Whether the description contains checkbox tasks (API v4).
+    pub has_tasks: Option<bool>,
+    pub task_completion_status: Option<::TaskCompletionStatus>,
 }
 
 
 pub type Issues = Vec<Issue>;
 
+impl ::pagination::Identifiable for Issue {
+    fn id(&self) -> i64 {
+        self.id
+    }
+}
+
 
 #[derive(Debug, Clone)]
 pub struct IssuesLister<'a> {
@@ -144,7 +184,7 @@ impl<'a> Lister<Issues> for IssuesLister<'a> {
         let query = self.build_query();
         debug!("query: {:?}", query);
 
-        self.gl.get(&query, None, None).chain_err(|| format!("cannot get query {}", query))
+        self.gl.get_listing(&query).chain_err(|| format!("cannot get query {}", query))
     }
 
     /// Commit the lister: Query GitLab and return a list of issues.
@@ -154,6 +194,16 @@ impl<'a> Lister<Issues> for IssuesLister<'a> {
 
         self.gl.get(&query, page, per_page).chain_err(|| format!("cannot get query {}", query))
     }
+
+    /// Commit the lister: Query GitLab and return a page of issues with its pagination information.
+    fn list_page(&self, page: u16, per_page: u16) -> Result<(Issues, PageInfo)> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl
+            .get_with_headers(&query, page, per_page)
+            .chain_err(|| format!("cannot get query {}", query))
+    }
 }
 
 
@@ -171,23 +221,35 @@ impl<'a> IssuesLister<'a> {
     }
 
 
+    /// Return a lister for the issues of the group `id` (`groups/ID/issues`).
     pub fn group(self, id: i64) -> group::IssuesLister<'a> {
         // assert_eq!(self, IssuesLister::new(self.gl));
         group::IssuesLister::new(self.gl, id)
     }
 
+    /// Return a lister for the issues of the project `id` (`projects/ID/issues`).
+    ///
+    /// Contrary to `gl.issues()` which lists the issues created by the authenticated user
+    /// (`issues`), the returned lister lists all the project's issues.
     pub fn project(self, id: i64) -> project::IssuesLister<'a> {
         // assert_eq!(self, IssuesLister::new(self.gl));
         project::IssuesLister::new(self.gl, id)
     }
 
+    /// Return a lister for the single issue `issue_id` of project `id`
+    /// (`projects/ID/issues/ISSUE_ID`).
     pub fn single(self, id: i64, issue_id: i64) -> single::IssueLister<'a> {
         // assert_eq!(self, IssuesLister::new(self.gl));
         single::IssueLister::new(self.gl, id, issue_id)
     }
 
+    /// Return an editor for the issue `issue_id` of the project `id`.
+    pub fn edit(self, id: i64, issue_id: i64) -> edit::IssueEditor<'a> {
+        edit::IssueEditor::new(self.gl, id, issue_id)
+    }
+
 
-    pub fn state(&'a mut self, state: State) -> &'a mut IssuesLister {
+    pub fn state(&'a mut self, state: ListingState) -> &'a mut IssuesLister {
         self.internal.state = Some(state);
         self
     }
@@ -207,6 +269,17 @@ impl<'a> IssuesLister<'a> {
         self
     }
 
+    /// Same as `list()` but `options` (timeout, retries, headers) override the `GitLab`'s
+    /// request options for this call.
+    pub fn list_with(&self, options: &::transport::RequestOptions) -> Result<Issues> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl
+            .get_with_options(&query, None, None, options)
+            .chain_err(|| format!("cannot get query {}", query))
+    }
+
 
     // /// Commit the lister: Query GitLab and return a list of issues.
     // pub fn list(&self) -> Result<Issues> {
@@ -248,8 +321,9 @@ impl<'a> BuildQuery for IssuesLister<'a> {
 
             query.push_str("state=");
             query.push_str(match state {
-                State::Opened => "opened",
-                State::Closed => "closed",
+                ListingState::Opened => "opened",
+                ListingState::Closed => "closed",
+                ListingState::All => "all",
             });
         });
 
@@ -296,7 +370,40 @@ impl<'a> BuildQuery for IssuesLister<'a> {
 
 #[cfg(test)]
 mod tests {
+    use serde_json;
+
     use BuildQuery;
+    use issues::Issue;
+
+
+    pub const TEST_ISSUE_JSON: &'static str = r##"{
+        "id": 76,
+        "iid": 6,
+        "project_id": 8,
+        "title": "Consequatur vero maxime deserunt laboriosam est voluptas dolorem.",
+        "description": "Ratione dolores corrupti mollitia soluta quia.",
+        "state": "opened",
+        "created_at": "2016-01-04T15:31:51.081Z",
+        "updated_at": "2016-01-04T15:31:51.081Z",
+        "labels": [],
+        "milestone": null,
+        "assignee": null,
+        "author": {
+            "id": 1,
+            "name": "Administrator",
+            "username": "root",
+            "state": "active",
+            "avatar_url": null,
+            "web_url": "https://gitlab.example.com/root"
+        },
+        "subscribed": false,
+        "user_notes_count": 1,
+        "upvotes": 4,
+        "downvotes": 1,
+        "due_date": null,
+        "confidential": false,
+        "web_url": "http://example.com/example/example/issues/6"
+    }"##;
 
 
     #[test]
@@ -315,17 +422,45 @@ mod tests {
     }
 
 
+    #[test]
+    fn build_query_project() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+        // let gl: ::GitLab = Default::default();
+
+        let expected_string = "issues";
+        let query = gl.issues().build_query();
+        assert_eq!(query, expected_string);
+
+        let expected_string = "projects/123/issues";
+        let query = gl.issues().project(123).build_query();
+        assert_eq!(query, expected_string);
+
+        let expected_string = "projects/123/issues?state=opened";
+        let query = gl.issues().project(123).state(::issues::ListingState::Opened).build_query();
+        assert_eq!(query, expected_string);
+    }
+
+
     #[test]
     fn build_query_state() {
         let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
         // let gl: ::GitLab = Default::default();
 
         let expected_string = "issues?state=opened";
-        let query = gl.issues().state(::issues::State::Opened).build_query();
+        let query = gl.issues().state(::issues::ListingState::Opened).build_query();
         assert_eq!(query, expected_string);
 
         let expected_string = "issues?state=closed";
-        let query = gl.issues().state(::issues::State::Closed).build_query();
+        let query = gl.issues().state(::issues::ListingState::Closed).build_query();
+        assert_eq!(query, expected_string);
+
+        let expected_string = "issues?state=all";
+        let query = gl.issues().state(::issues::ListingState::All).build_query();
+        assert_eq!(query, expected_string);
+
+        // The former name is still accepted.
+        let expected_string = "issues?state=opened";
+        let query = gl.issues().state(::issues::State::Opened).build_query();
         assert_eq!(query, expected_string);
     }
 
@@ -385,4 +520,81 @@ mod tests {
             .build_query();
         assert_eq!(query, expected_string);
     }
+
+
+    #[test]
+    fn deserialize_issue() {
+        let issue: Issue = serde_json::from_str(TEST_ISSUE_JSON)
+            .expect("JSON deserialization failed");
+        assert_eq!(issue.iid, 6);
+        assert_eq!(issue.upvotes, 4);
+        assert_eq!(issue.downvotes, 1);
+        assert_eq!(issue.discussion_locked, None);
+        assert!(issue.links.is_none());
+    }
+
+
+    #[test]
+    fn deserialize_issue_discussion_locked() {
+        let json_reply = TEST_ISSUE_JSON.replace("\"confidential\": false,",
+            "\"confidential\": false, \"discussion_locked\": true,");
+        let issue: Issue = serde_json::from_str(&json_reply)
+            .expect("JSON deserialization failed");
+        assert_eq!(issue.discussion_locked, Some(true));
+    }
+
+
+    #[test]
+    fn deserialize_issue_links() {
+        let json_reply = TEST_ISSUE_JSON.replace("\"confidential\": false,",
+            r#""confidential": false,
+               "_links": {
+                   "self": "http://example.com/api/v4/projects/1/issues/2",
+                   "notes": "http://example.com/api/v4/projects/1/issues/2/notes",
+                   "award_emoji": "http://example.com/api/v4/projects/1/issues/2/award_emoji",
+                   "project": "http://example.com/api/v4/projects/1"
+               },"#);
+        let issue: Issue = serde_json::from_str(&json_reply)
+            .expect("JSON deserialization failed");
+
+        let links = issue.links.expect("_links not deserialized");
+        assert_eq!(links.self_url, "http://example.com/api/v4/projects/1/issues/2");
+        assert_eq!(links.notes, "http://example.com/api/v4/projects/1/issues/2/notes");
+        assert_eq!(links.project, "http://example.com/api/v4/projects/1");
+    }
+
+
+    #[test]
+    fn deserialize_issue_task_completion_status() {
+        let issue: Issue = serde_json::from_str(TEST_ISSUE_JSON)
+            .expect("JSON deserialization failed");
+        assert_eq!(issue.task_completion_status, None);
+
+        let json_reply = TEST_ISSUE_JSON.replace("\"confidential\": false,",
+            r#""confidential": false,
+               "has_tasks": true,
+               "task_completion_status": {
+                   "count": 5,
+                   "completed_count": 3
+               },"#);
+        let issue: Issue = serde_json::from_str(&json_reply)
+            .expect("JSON deserialization failed");
+        assert_eq!(issue.has_tasks, Some(true));
+        assert_eq!(issue.task_completion_status,
+                   Some(::TaskCompletionStatus {
+                       count: 5,
+                       completed_count: 3,
+                   }));
+    }
+
+
+    #[test]
+    fn deserialize_issue_without_votes() {
+        let json_reply = TEST_ISSUE_JSON.replace("\"upvotes\": 4,", "")
+            .replace("\"downvotes\": 1,", "");
+        let issue: Issue = serde_json::from_str(&json_reply)
+            .expect("JSON deserialization failed");
+        assert_eq!(issue.upvotes, 0);
+        assert_eq!(issue.downvotes, 0);
+    }
 }
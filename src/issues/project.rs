@@ -10,21 +10,25 @@
 //! GET /projects/ID/issues
 //! GET /projects/ID/issues?state=opened
 //! GET /projects/ID/issues?state=closed
+//! GET /projects/ID/issues?state=all
 //! GET /projects/ID/issues?labels=foo
 //! GET /projects/ID/issues?labels=foo,bar
 //! GET /projects/ID/issues?labels=foo,bar&state=opened
 //! GET /projects/ID/issues?milestone=1.0.0
 //! GET /projects/ID/issues?milestone=1.0.0&state=opened
 //! GET /projects/ID/issues?iid=42
+//! GET /projects/ID/issues?iids[]=42
+//! GET /projects/ID/issues?assignee_id=None
 //! ```
 //!
 //! | Attribute | Type | Required | Description |
 //! | --------- | ---- | -------- | ----------- |
 //! | `id`      | integer | yes   | The ID of a project |
-//! | `iid`     | integer | no    | Return the issue having the given `iid` |
-//! | `state`   | string  | no    | Return all issues or just those that are `opened` or `closed`|
+//! | `iid`     | integer | no    | Return the issue having the given `iid` (`iids[]` with the API v4, see `GitLab::api_version()`) |
+//! | `state`   | string  | no    | Return `all` issues or just those that are `opened` or `closed`|
 //! | `labels`  | string  | no    | Comma-separated list of label names, issues with any of the labels will be returned |
 //! | `milestone` | string| no    | The milestone title |
+//! | `assignee_id` | integer/string | no | Return issues assigned to the given user, `None` for unassigned issues or `Any` for issues assigned to someone |
 //! | `order_by`| string  | no    | Return requests ordered by `created_at` or `updated_at` fields. Default is `created_at` |
 //! | `sort`    | string  | no    | Return requests sorted in `asc` or `desc` order. Default is `desc`  |
 //!
@@ -34,6 +38,7 @@ use serde_urlencoded;
 
 use BuildQuery;
 use Lister;
+use PageInfo;
 
 use ::errors::*;
 
@@ -53,7 +58,7 @@ impl<'a> Lister<::issues::Issues> for IssuesLister<'a> {
         let query = self.build_query();
         debug!("query: {:?}", query);
 
-        self.gl.get(&query, None, None).chain_err(|| format!("cannot get query {}", query))
+        self.gl.get_listing(&query).chain_err(|| format!("cannot get query {}", query))
     }
 
     fn list_paginated(&self, page: u16, per_page: u16) -> Result<::issues::Issues> {
@@ -62,6 +67,16 @@ impl<'a> Lister<::issues::Issues> for IssuesLister<'a> {
 
         self.gl.get(&query, page, per_page).chain_err(|| format!("cannot get query {}", query))
     }
+
+    /// Commit the lister: Query GitLab and return a page of issues with its pagination information.
+    fn list_page(&self, page: u16, per_page: u16) -> Result<(::issues::Issues, PageInfo)> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl
+            .get_with_headers(&query, page, per_page)
+            .chain_err(|| format!("cannot get query {}", query))
+    }
 }
 
 
@@ -75,6 +90,7 @@ impl<'a> IssuesLister<'a> {
                 state: None,
                 labels: None,
                 milestone: None,
+                assignee_id: None,
                 order_by: None,
                 sort: None,
             },
@@ -87,7 +103,7 @@ impl<'a> IssuesLister<'a> {
         self
     }
 
-    pub fn state(&'a mut self, state: ::issues::State) -> &'a mut IssuesLister {
+    pub fn state(&'a mut self, state: ::issues::ListingState) -> &'a mut IssuesLister {
         self.internal.state = Some(state);
         self
     }
@@ -102,6 +118,12 @@ impl<'a> IssuesLister<'a> {
         self
     }
 
+    /// Only list the issues matching `assignee`, for example the unassigned ones.
+    pub fn assignee_id(&'a mut self, assignee: ::AssigneeFilter) -> &'a mut IssuesLister {
+        self.internal.assignee_id = Some(assignee);
+        self
+    }
+
     pub fn order_by(&'a mut self, order_by: ::issues::ListingOrderBy) -> &'a mut IssuesLister {
         self.internal.order_by = Some(order_by);
         self
@@ -133,9 +155,10 @@ impl<'a> BuildQuery for IssuesLister<'a> {
                               &self.internal.state,
                               &self.internal.labels,
                               &self.internal.milestone,
+                              &self.internal.assignee_id,
                               &self.internal.order_by,
                               &self.internal.sort) {
-            (&None, &None, &None, &None, &None, &None) => "",
+            (&None, &None, &None, &None, &None, &None, &None) => "",
             _ => "?",
         });
 
@@ -143,7 +166,8 @@ impl<'a> BuildQuery for IssuesLister<'a> {
             query.push_str(split_char);
             split_char = &amp_char;
 
-            query.push_str("iid=");
+            // The API v4 renamed the parameter.
+            query.push_str(if self.gl.api_version() >= 4 { "iids[]=" } else { "iid=" });
             query.push_str(&iid.to_string());
         });
 
@@ -153,8 +177,9 @@ impl<'a> BuildQuery for IssuesLister<'a> {
 
             query.push_str("state=");
             query.push_str(match state {
-                ::issues::State::Opened => "opened",
-                ::issues::State::Closed => "closed",
+                ::issues::ListingState::Opened => "opened",
+                ::issues::ListingState::Closed => "closed",
+                ::issues::ListingState::All => "all",
             });
         });
 
@@ -180,6 +205,14 @@ impl<'a> BuildQuery for IssuesLister<'a> {
             query.push_str(&serde_urlencoded::to_string(&params).unwrap());
         });
 
+        self.internal.assignee_id.map(|assignee_id| {
+            query.push_str(split_char);
+            split_char = &amp_char;
+
+            query.push_str("assignee_id=");
+            query.push_str(&assignee_id.query_value());
+        });
+
         self.internal.order_by.map(|order_by| {
             query.push_str(split_char);
             split_char = &amp_char;
@@ -268,13 +301,24 @@ mod tests {
         // let gl: ::GitLab = Default::default();
 
         let expected_string = format!("projects/{}/issues?state=opened", TEST_PROJECT_ID);
-        let query =
-            gl.issues().project(TEST_PROJECT_ID).state(::issues::State::Opened).build_query();
+        let query = gl.issues()
+            .project(TEST_PROJECT_ID)
+            .state(::issues::ListingState::Opened)
+            .build_query();
         assert_eq!(query, expected_string);
 
         let expected_string = format!("projects/{}/issues?state=closed", TEST_PROJECT_ID);
-        let query =
-            gl.issues().project(TEST_PROJECT_ID).state(::issues::State::Closed).build_query();
+        let query = gl.issues()
+            .project(TEST_PROJECT_ID)
+            .state(::issues::ListingState::Closed)
+            .build_query();
+        assert_eq!(query, expected_string);
+
+        let expected_string = format!("projects/{}/issues?state=all", TEST_PROJECT_ID);
+        let query = gl.issues()
+            .project(TEST_PROJECT_ID)
+            .state(::issues::ListingState::All)
+            .build_query();
         assert_eq!(query, expected_string);
     }
 
@@ -330,6 +374,36 @@ mod tests {
     }
 
 
+    #[test]
+    fn build_query_assignee_id() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+        // let gl: ::GitLab = Default::default();
+
+        let expected_string = format!("projects/{}/issues?assignee_id=None", TEST_PROJECT_ID);
+        let query = gl.issues()
+            .project(TEST_PROJECT_ID)
+            .assignee_id(::AssigneeFilter::None)
+            .build_query();
+        assert_eq!(query, expected_string);
+
+        let expected_string = format!("projects/{}/issues?assignee_id=Any", TEST_PROJECT_ID);
+        let query = gl.issues()
+            .project(TEST_PROJECT_ID)
+            .assignee_id(::AssigneeFilter::Any)
+            .build_query();
+        assert_eq!(query, expected_string);
+
+        let expected_string = format!("projects/{}/issues?state=opened&assignee_id=5",
+                                      TEST_PROJECT_ID);
+        let query = gl.issues()
+            .project(TEST_PROJECT_ID)
+            .assignee_id(::AssigneeFilter::Id(5))
+            .state(::issues::ListingState::Opened)
+            .build_query();
+        assert_eq!(query, expected_string);
+    }
+
+
     #[test]
     fn build_query_multiple() {
         let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
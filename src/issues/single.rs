@@ -15,6 +15,21 @@
 //! | `id`      | integer | yes   | The ID of a project |
 //! | `issue_id`| integer | yes   | The ID of a project's issue |
 //!
+//! # Edit issue
+//!
+//! Updates an existing project issue. Only the `discussion_locked` attribute is supported here,
+//! see `issues::edit::IssueEditor` for the others.
+//!
+//! ```text
+//! PUT /projects/ID/issues/ISSUE_ID
+//! ```
+//!
+//! | Attribute | Type | Required | Description |
+//! | --------- | ---- | -------- | ----------- |
+//! | `id`      | integer | yes   | The ID of a project |
+//! | `issue_id`| integer | yes   | The ID of a project's issue |
+//! | `discussion_locked` | boolean | no | Flag indicating if the issue's discussion is locked |
+//!
 //!
 
 
@@ -49,6 +64,35 @@ impl<'a> IssueLister<'a> {
 
         self.gl.get(&query, None, None).chain_err(|| format!("cannot get query {}", query))
     }
+
+    /// Lock the issue's discussion: only project members can then comment.
+    ///
+    /// Returns the updated issue.
+    pub fn lock_discussion(&self) -> Result<::issues::Issue> {
+        self.set_discussion_locked(true)
+    }
+
+    /// Unlock the issue's discussion.
+    ///
+    /// Returns the updated issue.
+    pub fn unlock_discussion(&self) -> Result<::issues::Issue> {
+        self.set_discussion_locked(false)
+    }
+
+    fn set_discussion_locked(&self, discussion_locked: bool) -> Result<::issues::Issue> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        let body = DiscussionLockInternal { discussion_locked: discussion_locked };
+
+        self.gl.put(&query, &body).chain_err(|| format!("cannot put query {}", query))
+    }
+}
+
+
+#[derive(Debug, Serialize)]
+struct DiscussionLockInternal {
+    discussion_locked: bool,
 }
 
 
@@ -61,7 +105,12 @@ impl<'a> BuildQuery for IssueLister<'a> {
 
 #[cfg(test)]
 mod tests {
+    use hyper;
+
     use BuildQuery;
+    use issues::tests::TEST_ISSUE_JSON;
+    use tests::mock_gitlab;
+    use transport::Response;
 
     const TEST_PROJECT_ID: i64 = 123;
     const TEST_ISSUE_ID: i64 = 456;
@@ -86,4 +135,27 @@ mod tests {
         let query = gl.issues().single(TEST_PROJECT_ID, TEST_ISSUE_ID).build_query();
         assert_eq!(query, expected_string);
     }
+
+
+    #[test]
+    fn lock_discussion() {
+        let (gl, mock) = mock_gitlab();
+        let json_reply = TEST_ISSUE_JSON.replace("\"confidential\": false,",
+            "\"confidential\": false, \"discussion_locked\": true,");
+        mock.push_response(Response::json(&json_reply));
+        mock.push_response(Response::json(TEST_ISSUE_JSON));
+
+        let issue = gl.issues().single(TEST_PROJECT_ID, TEST_ISSUE_ID).lock_discussion().unwrap();
+        assert_eq!(issue.discussion_locked, Some(true));
+        gl.issues().single(TEST_PROJECT_ID, TEST_ISSUE_ID).unlock_discussion().unwrap();
+
+        let requests = mock.requests();
+        assert_eq!(requests[0].method, hyper::method::Method::Put);
+        let expected_url = format!("https://gitlab.example.com/api/v3/projects/{}/issues/{}?",
+                                   TEST_PROJECT_ID,
+                                   TEST_ISSUE_ID);
+        assert!(requests[0].url.starts_with(&expected_url));
+        assert_eq!(requests[0].body, Some(String::from(r#"{"discussion_locked":true}"#)));
+        assert_eq!(requests[1].body, Some(String::from(r#"{"discussion_locked":false}"#)));
+    }
 }
@@ -12,7 +12,116 @@ extern crate error_chain;
 // `error_chain!` creates.
 pub mod errors {
     // Create the Error, ErrorKind, ResultExt, and Result types
-    error_chain!{}
+    error_chain!{
+        // Errors from other crates, converted by `?`. Match on the `ErrorKind` to access them.
+        foreign_links {
+            Hyper(::hyper::Error);
+            Json(::serde_json::Error);
+            Url(::url::ParseError);
+            Io(::std::io::Error);
+        }
+
+        errors {
+            // GitLab rejected the request's parameters (`400 Bad Request`).
+            BadRequest(reason: String) {
+                description("bad request")
+                display("bad request: {}", reason)
+            }
+            // GitLab did not accept the private token (`401 Unauthorized`).
+            Unauthorized(reason: String) {
+                description("unauthorized")
+                display("unauthorized: {}", reason)
+            }
+            // GitLab refused the request (`403 Forbidden`), for example because the user is
+            // not allowed to do it.
+            Forbidden(reason: String) {
+                description("forbidden")
+                display("forbidden: {}", reason)
+            }
+            // The requested item does not exist, or is not visible to the user
+            // (`404 Not Found`).
+            NotFound(reason: String) {
+                description("not found")
+                display("not found: {}", reason)
+            }
+            // Too many requests were sent (`429 Too Many Requests`), see
+            // `GitLab::rate_limit()` and `GitLab::with_retry()`.
+            RateLimited {
+                description("rate limited")
+                display("rate limited: too many requests")
+            }
+            // GitLab failed to handle the request (`5xx` status code).
+            ServerError(status: u16) {
+                description("server error")
+                display("server error: status code {}", status)
+            }
+            // Any other status code not expected for the request.
+            UnexpectedStatus(status: u16) {
+                description("unexpected status code")
+                display("unexpected status code {}", status)
+            }
+
+            // The deadline of the operation (see `RequestOptions::deadline()`) passed.
+            DeadlineExceeded {
+                description("deadline exceeded")
+                display("deadline exceeded")
+            }
+
+            // The commit could not be cherry-picked onto `branch`, for example because of a
+            // conflict.
+            CherryPickConflict(branch: String, reason: String) {
+                description("cherry-pick conflict")
+                display("cannot cherry-pick onto '{}': {}", branch, reason)
+            }
+
+            // The merge request cannot be accepted (`405 Method Not Allowed`), for example
+            // because it has conflicts, is a work in progress or is already merged.
+            NotMergeable(merge_request_id: i64) {
+                description("merge request not mergeable")
+                display("merge request {} cannot be merged", merge_request_id)
+            }
+        }
+    }
+
+    impl Error {
+        /// The kind of the first error of the chain which is not a `Msg`, or the kind of `self` if
+        /// there is none.
+        ///
+        /// The typed kinds (`Forbidden`, `NotFound`...) are hidden behind the `Msg` added by each
+        /// `chain_err()`, for example by the listers and `GitLab::version()`.
+        ///
+        /// # Examples
+        ///
+        /// ```no_run
+        /// use gitlab_api::GitLab;
+        /// use gitlab_api::errors::ErrorKind;
+        ///
+        /// let gl = GitLab::new("gitlab.example.com", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+        ///
+        /// match gl.version() {
+        ///     Ok(_) => {}
+        ///     Err(ref e) => {
+        ///         match *e.typed_kind() {
+        ///             ErrorKind::Forbidden(ref reason) => println!("not allowed: {}", reason),
+        ///             _ => println!("error: {}", e),
+        ///         }
+        ///     }
+        /// }
+        /// ```
+        pub fn typed_kind(&self) -> &ErrorKind {
+            let mut error = self;
+            loop {
+                match *error.kind() {
+                    ErrorKind::Msg(_) => {}
+                    ref kind => return kind,
+                }
+                match error.1.next_error.as_ref().and_then(|next| next.downcast_ref::<Error>()) {
+                    Some(next) => error = next,
+                    None => return self.kind(),
+                }
+            }
+        }
+    }
 }
 
 use ::errors::*;
@@ -20,19 +129,37 @@ use ::errors::*;
 #[macro_use]
 extern crate log;
 extern crate hyper;
+extern crate hyper_native_tls;
+extern crate native_tls;
 
-extern crate regex;
+extern crate crossbeam;
+extern crate semver;
 extern crate url;
+#[cfg(feature = "gzip")]
+extern crate flate2;
+#[cfg(feature = "chrono")]
+extern crate chrono;
 
 
+pub mod datetime;
 pub mod gitlab;
+pub mod id;
 pub mod groups;
 pub mod projects;
 pub mod issues;
 pub mod merge_requests;
+pub mod namespaces;
+pub mod pagination;
+pub mod tolerant;
+pub mod transport;
+pub mod users;
+pub mod version;
 
 // Re-export those structs
 pub use gitlab::GitLab;
+pub use pagination::{PageCursor, PageInfo};
+pub use transport::{RateLimit, RequestOptions, ResponseMeta, RetryConfig};
+pub use version::Version;
 // pub use projects::Project;
 // Re-export those traits
 
@@ -57,6 +184,111 @@ pub enum ListingVisibility {
 }
 
 
+/// A member's permissions on a group or project.
+///
+/// GitLab sends and expects the numerical value of the level. Levels unknown to this crate, for
+/// example added by a newer GitLab version, are kept as `Other`. The levels are ordered by their
+/// numerical value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccessLevel {
+    Guest,
+    Reporter,
+    Developer,
+    Master,
+    Owner,
+    Other(u64),
+}
+
+impl AccessLevel {
+    /// The numerical value GitLab uses for the level.
+    pub fn as_u64(&self) -> u64 {
+        match *self {
+            AccessLevel::Guest => 10,
+            AccessLevel::Reporter => 20,
+            AccessLevel::Developer => 30,
+            AccessLevel::Master => 40,
+            AccessLevel::Owner => 50,
+            AccessLevel::Other(level) => level,
+        }
+    }
+
+    pub fn from_u64(level: u64) -> AccessLevel {
+        match level {
+            10 => AccessLevel::Guest,
+            20 => AccessLevel::Reporter,
+            30 => AccessLevel::Developer,
+            40 => AccessLevel::Master,
+            50 => AccessLevel::Owner,
+            _ => AccessLevel::Other(level),
+        }
+    }
+}
+
+impl PartialOrd for AccessLevel {
+    fn partial_cmp(&self, other: &AccessLevel) -> Option<::std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for AccessLevel {
+    fn cmp(&self, other: &AccessLevel) -> ::std::cmp::Ordering {
+        self.as_u64().cmp(&other.as_u64())
+    }
+}
+
+impl serde::Serialize for AccessLevel {
+    fn serialize<S>(&self, serializer: S) -> ::std::result::Result<S::Ok, S::Error>
+        where S: serde::Serializer
+    {
+        serializer.serialize_u64(self.as_u64())
+    }
+}
+
+impl serde::Deserialize for AccessLevel {
+    fn deserialize<D>(deserializer: D) -> ::std::result::Result<AccessLevel, D::Error>
+        where D: serde::Deserializer
+    {
+        let level: u64 = serde::Deserialize::deserialize(deserializer)?;
+        Ok(AccessLevel::from_u64(level))
+    }
+}
+
+
+/// Filter merge requests or issues on their assignee.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum AssigneeFilter {
+    /// Only the unassigned ones
+    None,
+    /// Only the ones assigned to someone
+    Any,
+    /// Only the ones assigned to the user with this ID
+    Id(i64),
+}
+
+
+impl AssigneeFilter {
+    /// The value of the `assignee_id` parameter selecting this filter.
+    pub fn query_value(&self) -> String {
+        match *self {
+            AssigneeFilter::None => String::from("None"),
+            AssigneeFilter::Any => String::from("Any"),
+            AssigneeFilter::Id(id) => id.to_string(),
+        }
+    }
+}
+
+
+/// Change of state of an issue or merge request, see `issues::edit::IssueEditor` and
+/// `merge_requests::MergeRequestUpdate`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum StateEvent {
+    #[serde(rename = "close")]
+    Close,
+    #[serde(rename = "reopen")]
+    Reopen,
+}
+
+
 #[derive(Debug, Serialize, Deserialize)]
 pub enum UserState {
     #[serde(rename = "active")]
@@ -77,13 +309,6 @@ pub enum MilestoneState {
 }
 
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct Version {
-    pub version: String,
-    pub revision: String,
-}
-
-
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Group {
     pub id: i64,
@@ -95,10 +320,20 @@ pub struct Group {
     pub avatar_url: Option<String>,
     pub web_url: String,
     pub request_access_enabled: bool,
+    /// The LDAP group the group is synced with (LDAP-integrated instances only)
+    pub ldap_cn: Option<String>,
+    /// The access level given to the members of the LDAP group
+    pub ldap_access: Option<AccessLevel>,
 }
 
 pub type Groups = Vec<Group>;
 
+impl pagination::Identifiable for Group {
+    fn id(&self) -> i64 {
+        self.id
+    }
+}
+
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Milestone {
@@ -108,9 +343,12 @@ pub struct Milestone {
     pub title: String,
     pub description: String,
     pub state: MilestoneState,
-    pub created_at: String,  // FIXME: Use date type?
-    pub updated_at: String,  // FIXME: Use date type?
-    pub due_date: Option<String>  // FIXME: Use date type?
+    #[serde(with = "::datetime")]
+    pub created_at: ::datetime::DateTime,
+    #[serde(with = "::datetime")]
+    pub updated_at: ::datetime::DateTime,
+    #[serde(default, with = "::datetime::option_date")]
+    pub due_date: Option<::datetime::DateTime>,
 }
 
 
@@ -125,6 +363,115 @@ pub struct User {
 }
 
 
+/// URLs of an issue's or merge request's related resources in the API (API v4).
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Links {
+    #[serde(rename = "self")]
+    pub self_url: String,
+    pub notes: String,
+    pub award_emoji: String,
+    pub project: String,
+}
+
+
+/// A label, as returned when asking for the labels' details.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Label {
+    pub id: Option<i64>,
+    pub name: String,
+    /// For example `#d9534f`
+    pub color: String,
+    pub text_color: Option<String>,
+    pub description: Option<String>,
+}
+
+
+/// The labels of an issue or merge request.
+///
+/// GitLab only returns the labels' names, unless asked for their details (for example with
+/// `MergeRequestsLister::with_labels_details()`).
+#[derive(Debug, Clone, PartialEq)]
+pub enum Labels {
+    Names(Vec<String>),
+    Details(Vec<Label>),
+}
+
+impl Labels {
+    /// The names of the labels, whatever their representation.
+    pub fn names(&self) -> Vec<&str> {
+        match *self {
+            Labels::Names(ref names) => names.iter().map(|name| name.as_str()).collect(),
+            Labels::Details(ref labels) => labels.iter().map(|label| label.name.as_str()).collect(),
+        }
+    }
+}
+
+impl serde::Serialize for Labels {
+    fn serialize<S>(&self, serializer: S) -> ::std::result::Result<S::Ok, S::Error>
+        where S: serde::Serializer
+    {
+        match *self {
+            Labels::Names(ref names) => serde::Serialize::serialize(names, serializer),
+            Labels::Details(ref labels) => serde::Serialize::serialize(labels, serializer),
+        }
+    }
+}
+
+impl serde::Deserialize for Labels {
+    fn deserialize<D>(deserializer: D) -> ::std::result::Result<Labels, D::Error>
+        where D: serde::Deserializer
+    {
+        // The representation depends on the content: an array of strings or of objects.
+        let value: serde_json::Value = serde::Deserialize::deserialize(deserializer)?;
+        let is_names = value.as_array()
+            .map(|labels| labels.iter().all(|label| label.is_string()))
+            .unwrap_or(false);
+
+        let labels = if is_names {
+            serde_json::from_value(value).map(Labels::Names)
+        } else {
+            serde_json::from_value(value).map(Labels::Details)
+        };
+
+        labels.map_err(|e| serde::de::Error::custom(e.to_string()))
+    }
+}
+
+
+/// GitLab expects the labels of an issue or merge request update as a comma-separated list.
+fn serialize_labels<S>(labels: &Option<Vec<String>>,
+                       serializer: S)
+                       -> ::std::result::Result<S::Ok, S::Error>
+    where S: serde::Serializer
+{
+    match *labels {
+        Some(ref labels) => serializer.serialize_str(&labels.join(",")),
+        None => serializer.serialize_none(),
+    }
+}
+
+
+/// Progress of the checkbox tasks of an issue's or merge request's description (API v4).
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TaskCompletionStatus {
+    /// Number of tasks
+    pub count: i64,
+    /// Number of tasks checked
+    pub completed_count: i64,
+}
+
+
+/// The counts shown by a project's overview, see `GitLab::dashboard_counts()`.
+///
+/// A count is `None` when GitLab did not send it, which it does for large listings.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct DashboardCounts {
+    pub open_issues: Option<u64>,
+    pub open_merge_requests: Option<u64>,
+    /// Number of pipelines which succeeded
+    pub successful_pipelines: Option<u64>,
+}
+
 
 trait BuildQuery {
     fn build_query(&self) -> String;
@@ -133,6 +480,108 @@ trait BuildQuery {
 pub trait Lister<T> {
     fn list(&self) -> Result<T>;
     fn list_paginated(&self, page: u16, per_page: u16) -> Result<T>;
+    /// Same as `list_paginated()` but also return the page's pagination information.
+    ///
+    /// By default, no pagination information is returned: a partial page is then taken as the
+    /// last one. Listers override it to return the information from GitLab's headers.
+    fn list_page(&self, page: u16, per_page: u16) -> Result<(T, PageInfo)> {
+        self.list_paginated(page, per_page).map(|items| (items, PageInfo::default()))
+    }
+
+    /// Fetch page `n` (starting at 1) using GitLab's default number of items per page.
+    ///
+    /// The returned `PageInfo` tells, among others, the total number of pages, allowing to
+    /// jump directly to any page.
+    fn page(&self, n: u16) -> Result<(T, PageInfo)> {
+        self.list_page(n, pagination::DEFAULT_PER_PAGE)
+    }
+
+    /// Drop the `items` of a page which don't match the lister's client-side filters, if any.
+    ///
+    /// Listers filter again the items of the parameters some GitLab versions ignore. The pages
+    /// of `list_paginated()` and `list_page()` are returned as sent by GitLab: the iterators,
+    /// `list_from()` and `pagination::list_all()` drop the items once the following page is
+    /// known, so that a page emptied by the filter doesn't end the listing.
+    fn retain(&self, _items: &mut T) {}
+
+    /// Fetch only the first item, `None` if there is none.
+    ///
+    /// A single-item page is requested (`per_page=1`), for example to get the latest merge
+    /// request or any project matching a search without downloading a full page.
+    fn first<I>(&self) -> Result<Option<I>>
+        where Self: Lister<Vec<I>> + Sized
+    {
+        let items = self.list_paginated(1, 1)?;
+
+        Ok(items.into_iter().next())
+    }
+
+    /// Fetch the page pointed to by `cursor`.
+    ///
+    /// Returns the page's items together with the cursor to the next page (`None` when this was
+    /// the last page). Since a `PageCursor` can be serialized, this allows resuming an interrupted
+    /// listing without fetching the earlier pages again.
+    fn list_from(&self, cursor: PageCursor) -> Result<(T, Option<PageCursor>)> {
+        let (mut items, page_info) = self.list_page(cursor.page(), cursor.per_page())?;
+        self.retain(&mut items);
+
+        Ok((items, cursor.next(&page_info)))
+    }
+
+    /// Iterate over the items of all the pages, fetching `per_page` items at a time.
+    ///
+    /// Pages are fetched lazily: the next one is only requested once all the items of the
+    /// current one were returned. See `pagination::PaginatedIter`.
+    fn paginated_iter<I>(&self, per_page: u16) -> pagination::PaginatedIter<I, Self>
+        where Self: Lister<Vec<I>> + Sized
+    {
+        pagination::PaginatedIter::new(self, per_page)
+    }
+
+    /// Same as `paginated_iter()`, with the maximum number of items per page.
+    fn iter<I>(&self) -> pagination::PaginatedIter<I, Self>
+        where Self: Lister<Vec<I>> + Sized
+    {
+        self.paginated_iter(pagination::MAX_PER_PAGE)
+    }
+
+    /// Iterate over the items of all the pages, transformed by `f`.
+    ///
+    /// Items for which `f` returns `None` are skipped. Like `iter()`, pages are fetched lazily,
+    /// with the maximum number of items per page. See `pagination::StreamFilter`.
+    fn stream_filter<I, F, R>(&self, f: F) -> pagination::StreamFilter<I, Self, F>
+        where Self: Lister<Vec<I>> + Sized,
+              F: FnMut(I) -> Option<R>
+    {
+        pagination::StreamFilter::new(self.iter(), f)
+    }
+
+    /// Add the items of all the pages to `container`, without building an intermediate list.
+    ///
+    /// Like `iter()`, pages are fetched with the maximum number of items per page. If fetching a
+    /// page fails, the items of the previous pages were already added to `container`.
+    fn collect_into<I, C>(&self, container: &mut C) -> Result<()>
+        where Self: Lister<Vec<I>> + Sized,
+              C: Extend<I>
+    {
+        self.collect_into_by(container, |item| item)
+    }
+
+    /// Same as `collect_into()`, adding the items transformed by `f` to `container`.
+    ///
+    /// For example, to index projects by ID in a `HashMap`:
+    /// `projects.collect_into_by(&mut by_id, |project: Project| (project.id, project))`.
+    fn collect_into_by<I, C, F, R>(&self, container: &mut C, mut f: F) -> Result<()>
+        where Self: Lister<Vec<I>> + Sized,
+              C: Extend<R>,
+              F: FnMut(I) -> R
+    {
+        for item in self.iter() {
+            container.extend(Some(f(item?)));
+        }
+
+        Ok(())
+    }
 }
 
 
@@ -142,6 +591,24 @@ mod tests {
     // use hyper;
     use serde_json;
 
+    use transport::MockTransport;
+
+
+    /// A `GitLab` sending its requests to the returned `MockTransport`.
+    pub fn mock_gitlab() -> (::GitLab, MockTransport) {
+        let mock = MockTransport::new();
+        let gl = ::GitLab::new("gitlab.example.com", "XXXXXXXXXXXXXXXXXXXX")
+            .unwrap()
+            .transport(Box::new(mock.clone()));
+
+        (gl, mock)
+    }
+
+    /// The project of `TEST_PROJECTS_JSON`, as answered when getting a single project.
+    pub fn test_project_json() -> &'static str {
+        TEST_PROJECTS_JSON.trim().trim_left_matches('[').trim_right_matches(']')
+    }
+
     // #[test]
     // fn unauthorized() {
     //     let gl = GitLab::new("http", "gitlab.com", 80, "XXXXXXXXXXXXX").unwrap();
@@ -150,63 +617,152 @@ mod tests {
     //                hyper::status::StatusCode::Unauthorized);
     // }
 
+    pub const TEST_PROJECTS_JSON: &'static str = r##"[
+        {
+            "id": 517564,
+            "description": "GitLab API library and client in Rust",
+            "default_branch": "master",
+            "tag_list": [],
+            "public": false,
+            "archived": false,
+            "visibility_level": 0,
+            "ssh_url_to_repo": "git@gitlab.com:nbigaouette1/gitlab-api-rs.git",
+            "http_url_to_repo": "https://gitlab.com/nbigaouette1/gitlab-api-rs.git",
+            "web_url": "https://gitlab.com/nbigaouette1/gitlab-api-rs",
+            "owner": {
+                "name": "Nicolas Bigaouette",
+                "username": "nbigaouette1",
+                "id": 163821,
+                "state": "active",
+                "avatar_url": "https://secure.gravatar.com/avatar/3325e461df2fda8738f35a8bf4fd735e?s=80&d=identicon",
+                "web_url": "https://gitlab.com/nbigaouette1"
+            },
+            "name": "gitlab-api-rs",
+            "name_with_namespace": "Nicolas Bigaouette / gitlab-api-rs",
+            "path": "gitlab-api-rs",
+            "path_with_namespace": "nbigaouette1/gitlab-api-rs",
+            "container_registry_enabled": null,
+            "issues_enabled": true,
+            "merge_requests_enabled": true,
+            "wiki_enabled": true,
+            "builds_enabled": false,
+            "snippets_enabled": false,
+            "created_at": "2015-10-09T00:32:18.646Z",
+            "last_activity_at": "2017-01-31T14:46:26.638Z",
+            "shared_runners_enabled": true,
+            "lfs_enabled": true,
+            "creator_id": 163821,
+            "namespace": {
+                "id": 193119,
+                "name": "nbigaouette1",
+                "path": "nbigaouette1",
+                "kind": "user"
+            },
+            "avatar_url": null,
+            "star_count": 0,
+            "forks_count": 0,
+            "open_issues_count": 1,
+            "public_builds": true,
+            "shared_with_groups": [],
+            "only_allow_merge_if_build_succeeds": false,
+            "request_access_enabled": true,
+            "only_allow_merge_if_all_discussions_are_resolved": null,
+            "approvals_before_merge": 0
+        }
+    ]"##;
+
     #[test]
     fn deserialize_project() {
-        let json_reply = r##"[
-            {
-                "id": 517564,
-                "description": "GitLab API library and client in Rust",
-                "default_branch": "master",
-                "tag_list": [],
-                "public": false,
-                "archived": false,
-                "visibility_level": 0,
-                "ssh_url_to_repo": "git@gitlab.com:nbigaouette1/gitlab-api-rs.git",
-                "http_url_to_repo": "https://gitlab.com/nbigaouette1/gitlab-api-rs.git",
-                "web_url": "https://gitlab.com/nbigaouette1/gitlab-api-rs",
-                "owner": {
-                    "name": "Nicolas Bigaouette",
-                    "username": "nbigaouette1",
-                    "id": 163821,
-                    "state": "active",
-                    "avatar_url": "https://secure.gravatar.com/avatar/3325e461df2fda8738f35a8bf4fd735e?s=80&d=identicon",
-                    "web_url": "https://gitlab.com/nbigaouette1"
-                },
-                "name": "gitlab-api-rs",
-                "name_with_namespace": "Nicolas Bigaouette / gitlab-api-rs",
-                "path": "gitlab-api-rs",
-                "path_with_namespace": "nbigaouette1/gitlab-api-rs",
-                "container_registry_enabled": null,
-                "issues_enabled": true,
-                "merge_requests_enabled": true,
-                "wiki_enabled": true,
-                "builds_enabled": false,
-                "snippets_enabled": false,
-                "created_at": "2015-10-09T00:32:18.646Z",
-                "last_activity_at": "2017-01-31T14:46:26.638Z",
-                "shared_runners_enabled": true,
-                "lfs_enabled": true,
-                "creator_id": 163821,
-                "namespace": {
-                    "id": 193119,
-                    "name": "nbigaouette1",
-                    "path": "nbigaouette1",
-                    "kind": "user"
-                },
-                "avatar_url": null,
-                "star_count": 0,
-                "forks_count": 0,
-                "open_issues_count": 1,
-                "public_builds": true,
-                "shared_with_groups": [],
-                "only_allow_merge_if_build_succeeds": false,
-                "request_access_enabled": true,
-                "only_allow_merge_if_all_discussions_are_resolved": null,
-                "approvals_before_merge": 0
-            }
-        ]"##;
+        let projects: ::projects::Projects = serde_json::from_str(TEST_PROJECTS_JSON)
+            .expect("JSON deserialization failed");
+        assert_eq!(projects[0].open_issues_count, Some(1));
+        assert_eq!(projects[0].creator_id, 163821);
+        assert!(projects[0].tag_list.is_empty());
+    }
+
+    #[test]
+    fn deserialize_project_tag_list() {
+        let json_reply = TEST_PROJECTS_JSON.replace("\"tag_list\": [],",
+                                                    "\"tag_list\": [\"rust\", \"api\"],");
+
+        let projects: ::projects::Projects = serde_json::from_str(&json_reply)
+            .expect("JSON deserialization failed");
+        assert_eq!(projects[0].tag_list, vec!["rust", "api"]);
+    }
+
+    pub const TEST_GROUP_JSON: &'static str = r##"{
+        "id": 4,
+        "name": "Twitter",
+        "path": "twitter",
+        "description": "Aliquid qui quis dignissimos distinctio ut commodi voluptas est.",
+        "visibility_level": 20,
+        "lfs_enabled": true,
+        "avatar_url": null,
+        "web_url": "https://gitlab.example.com/groups/twitter",
+        "request_access_enabled": false
+    }"##;
+
+    #[test]
+    fn deserialize_group_without_ldap() {
+        let group: ::Group = serde_json::from_str(TEST_GROUP_JSON)
+            .expect("JSON deserialization failed");
+        assert_eq!(group.ldap_cn, None);
+        assert_eq!(group.ldap_access, None);
+    }
+
+    #[test]
+    fn deserialize_group_ldap() {
+        let json_reply = TEST_GROUP_JSON.replace("\"request_access_enabled\": false",
+                                                 "\"request_access_enabled\": false,
+                                                  \"ldap_cn\": \"cn=twitter,ou=groups\",
+                                                  \"ldap_access\": 30");
+
+        let group: ::Group = serde_json::from_str(&json_reply)
+            .expect("JSON deserialization failed");
+        assert_eq!(group.ldap_cn, Some(String::from("cn=twitter,ou=groups")));
+        assert_eq!(group.ldap_access, Some(::AccessLevel::Developer));
+
+        let json_reply = TEST_GROUP_JSON.replace("\"request_access_enabled\": false",
+                                                 "\"request_access_enabled\": false,
+                                                  \"ldap_access\": 35");
+        let group: ::Group = serde_json::from_str(&json_reply)
+            .expect("JSON deserialization failed");
+        let ldap_access = group.ldap_access.unwrap();
+        assert_eq!(ldap_access, ::AccessLevel::Other(35));
+        assert!(ldap_access > ::AccessLevel::Developer && ldap_access < ::AccessLevel::Master);
+        assert_eq!(serde_json::to_string(&ldap_access).unwrap(), "35");
+    }
+
+    #[test]
+    fn deserialize_project_without_open_issues_count() {
+        let json_reply = TEST_PROJECTS_JSON.replace("\"open_issues_count\": 1,", "");
+        assert!(!json_reply.contains("open_issues_count"));
+
+        let projects: ::projects::Projects = serde_json::from_str(&json_reply)
+            .expect("JSON deserialization failed");
+        assert_eq!(projects[0].open_issues_count, None);
+        assert_eq!(projects[0].id, 517564);
+    }
+
+    #[test]
+    fn deserialize_project_readme_url() {
+        let projects: ::projects::Projects = serde_json::from_str(TEST_PROJECTS_JSON)
+            .expect("JSON deserialization failed");
+        assert_eq!(projects[0].readme_url, None);
+
+        let readme_url = "https://gitlab.com/nbigaouette1/gitlab-api-rs/blob/master/README.md";
+        let json_reply = TEST_PROJECTS_JSON.replace("\"id\": 517564,",
+                                                    &format!("\"id\": 517564, \
+                                                              \"readme_url\": \"{}\",",
+                                                             readme_url));
+        let projects: ::projects::Projects = serde_json::from_str(&json_reply)
+            .expect("JSON deserialization failed");
+        assert_eq!(projects[0].readme_url, Some(String::from(readme_url)));
 
-        let _: ::projects::Projects = serde_json::from_str(json_reply)
+        let json_reply = TEST_PROJECTS_JSON.replace("\"id\": 517564,",
+                                                    "\"id\": 517564, \"readme_url\": null,");
+        let projects: ::projects::Projects = serde_json::from_str(&json_reply)
             .expect("JSON deserialization failed");
+        assert_eq!(projects[0].readme_url, None);
     }
 }
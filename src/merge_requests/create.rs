@@ -0,0 +1,236 @@
+//! Create a merge request
+//!
+//! https://docs.gitlab.com/ce/api/merge_requests.html#create-mr
+//!
+//! # Create MR
+//!
+//! Creates a new merge request.
+//!
+//! ```text
+//! POST /projects/ID/merge_requests
+//! ```
+//!
+//! Parameters:
+//!
+//! | Attribute | Type | Required | Description |
+//! | --------- | ---- | -------- | ----------- |
+//! | `id` | integer | yes | The ID of the source project |
+//! | `source_branch` | string | yes | The source branch |
+//! | `target_branch` | string | yes | The target branch |
+//! | `title` | string | yes | Title of MR |
+//! | `description` | string | no | Description of MR |
+//! | `assignee_id` | integer | no | Assignee user ID |
+//! | `target_project_id` | integer | no | The target project (numeric id), for a MR from a fork |
+//! | `labels` | string | no | Labels for MR as a comma-separated list |
+//! | `remove_source_branch` | boolean | no | Flag indicating if a merge request should remove the source branch when merging |
+
+
+use BuildQuery;
+
+use ::errors::*;
+
+
+/// The settings of a merge request to create.
+///
+/// Only the optional settings given are sent, GitLab uses its defaults for the others.
+#[derive(Debug, Clone, Serialize)]
+pub struct MergeRequestCreator<'a> {
+    #[serde(skip_serializing)]
+    gl: &'a ::GitLab,
+    /// The ID of the source project
+    #[serde(skip_serializing)]
+    id: i64,
+    source_branch: String,
+    target_branch: String,
+    title: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    assignee_id: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reviewer_ids: Option<Vec<i64>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    target_project_id: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none",
+            serialize_with = "::serialize_labels")]
+    labels: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    remove_source_branch: Option<bool>,
+}
+
+
+impl<'a> MergeRequestCreator<'a> {
+    pub fn new(gl: &'a ::GitLab,
+               id: i64,
+               source_branch: String,
+               target_branch: String,
+               title: String)
+               -> MergeRequestCreator {
+        MergeRequestCreator {
+            gl: gl,
+            id: id,
+            source_branch: source_branch,
+            target_branch: target_branch,
+            title: title,
+            description: None,
+            assignee_id: None,
+            reviewer_ids: None,
+            target_project_id: None,
+            labels: None,
+            remove_source_branch: None,
+        }
+    }
+
+    pub fn description(&'a mut self, description: String) -> &'a mut MergeRequestCreator {
+        self.description = Some(description);
+        self
+    }
+
+    pub fn assignee_id(&'a mut self, assignee_id: i64) -> &'a mut MergeRequestCreator {
+        self.assignee_id = Some(assignee_id);
+        self
+    }
+
+    /// Ask the users `reviewer_ids` to review the merge request (API v4).
+    pub fn reviewer_ids(&'a mut self, reviewer_ids: Vec<i64>) -> &'a mut MergeRequestCreator {
+        self.reviewer_ids = Some(reviewer_ids);
+        self
+    }
+
+    /// Merge into the project `target_project_id` (for example the upstream of a fork) instead
+    /// of the source project.
+    pub fn target_project_id(&'a mut self, target_project_id: i64) -> &'a mut MergeRequestCreator {
+        self.target_project_id = Some(target_project_id);
+        self
+    }
+
+    pub fn labels(&'a mut self, labels: Vec<String>) -> &'a mut MergeRequestCreator {
+        self.labels = Some(labels);
+        self
+    }
+
+    pub fn remove_source_branch(&'a mut self,
+                                remove_source_branch: bool)
+                                -> &'a mut MergeRequestCreator {
+        self.remove_source_branch = Some(remove_source_branch);
+        self
+    }
+
+    /// Commit the creator: Create the merge request in GitLab and return it.
+    ///
+    /// Fails without contacting GitLab if the source and target branches are the same branch of
+    /// the same project.
+    pub fn create(&self) -> Result<::merge_requests::MergeRequest> {
+        let same_project = self.target_project_id.map_or(true, |target| target == self.id);
+        if same_project && self.source_branch == self.target_branch {
+            bail!(format!("cannot create a merge request from branch '{}' into itself",
+                          self.source_branch));
+        }
+
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl
+            .post(&query, self)
+            .chain_err(|| {
+                format!("cannot create merge request '{}' in project {}", self.title, self.id)
+            })
+    }
+}
+
+
+impl<'a> BuildQuery for MergeRequestCreator<'a> {
+    fn build_query(&self) -> String {
+        format!("projects/{}/merge_requests", self.id)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use hyper;
+    use serde_json;
+
+    use BuildQuery;
+    use merge_requests::tests::TEST_MERGE_REQUEST_JSON;
+    use tests::mock_gitlab;
+    use transport::Response;
+
+    const TEST_PROJECT_ID: i64 = 123;
+
+
+    #[test]
+    fn build_query_default() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+
+        let expected_string = format!("projects/{}/merge_requests", TEST_PROJECT_ID);
+        let query = gl.merge_requests(TEST_PROJECT_ID)
+            .create("feature", "master", "Add the feature")
+            .build_query();
+        assert_eq!(query, expected_string);
+    }
+
+
+    #[test]
+    fn serialize_merge_request_creator() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+
+        let creator = gl.merge_requests(TEST_PROJECT_ID)
+            .create("feature", "master", "Add the feature");
+        assert_eq!(serde_json::to_string(&creator).unwrap(),
+                   concat!(r#"{"source_branch":"feature","target_branch":"master","#,
+                           r#""title":"Add the feature"}"#));
+
+        let mut creator = gl.merge_requests(TEST_PROJECT_ID)
+            .create("feature", "master", "Add the feature");
+        let creator = creator.description(String::from("Closes #3"))
+            .assignee_id(5)
+            .target_project_id(7)
+            .labels(vec![String::from("feature"), String::from("needs review")])
+            .remove_source_branch(true);
+        assert_eq!(serde_json::to_string(&*creator).unwrap(),
+                   concat!(r#"{"source_branch":"feature","target_branch":"master","#,
+                           r#""title":"Add the feature","description":"Closes #3","#,
+                           r#""assignee_id":5,"target_project_id":7,"#,
+                           r#""labels":"feature,needs review","remove_source_branch":true}"#));
+    }
+
+
+    #[test]
+    fn create() {
+        let (gl, mock) = mock_gitlab();
+        let mut response = Response::json(TEST_MERGE_REQUEST_JSON);
+        response.status = hyper::status::StatusCode::Created;
+        mock.push_response(response);
+        mock.push_response(Response::json(TEST_MERGE_REQUEST_JSON));
+
+        let merge_request = gl.merge_requests(TEST_PROJECT_ID)
+            .create("feature", "master", "Add the feature")
+            .create()
+            .unwrap();
+        assert_eq!(merge_request.iid, 1);
+
+        // A branch can't be merged into itself...
+        let error = gl.merge_requests(TEST_PROJECT_ID)
+            .create("master", "master", "Nothing")
+            .create()
+            .unwrap_err();
+        assert!(error.to_string().contains("into itself"));
+
+        // ... but it can be merged into the branch of the same name of another project.
+        let mut creator = gl.merge_requests(TEST_PROJECT_ID).create("master", "master", "Sync");
+        creator.target_project_id(7).reviewer_ids(vec![5, 6]).create().unwrap();
+
+        let requests = mock.requests();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0].method, hyper::method::Method::Post);
+        assert!(requests[0]
+            .url
+            .starts_with(&format!("https://gitlab.example.com/api/v3/projects/{}/merge_requests?",
+                                  TEST_PROJECT_ID)));
+        assert_eq!(requests[1].body,
+                   Some(String::from(concat!(r#"{"source_branch":"master","#,
+                                             r#""target_branch":"master","title":"Sync","#,
+                                             r#""reviewer_ids":[5,6],"target_project_id":7}"#))));
+    }
+}
@@ -14,30 +14,51 @@
 //! GET /projects/ID/merge_requests?state=all
 //! GET /projects/ID/merge_requests?iid=42
 //! GET /projects/ID/merge_requests?iid[]=42&iid[]=43
+//! GET /projects/ID/merge_requests?search=fix+login
+//! GET /projects/ID/merge_requests?reviewer_id=5
+//! GET /projects/ID/merge_requests?assignee_id=None
+//! GET /projects/ID/merge_requests?view=simple
+//! GET /projects/ID/merge_requests?with_labels_details=true
 //! ```
 //!
 //! Parameters:
 //!
 //! - `id` (required) - The ID of a project
-//! - `iid` (optional) - Return the request having the given `iid`
+//! - `iid` (optional) - Return the request having the given `iid`, or the requests having one of the given `iid[]`
 //! - `state` (optional) - Return `all` requests or just those that are `merged`, `opened` or `closed`
+//! - `search` (optional) - Return requests whose title or description contains the given term
+//! - `reviewer_id` (optional) - Return requests reviewed by the given user (API v4)
+//! - `assignee_id` (optional) - Return requests assigned to the given user, `None` for unassigned requests or `Any` for requests assigned to someone
+//! - `with_labels_details` (optional) - If `true`, return the details (name, color, description) of the labels instead of their names
+//! - `view` (optional) - If `simple`, return only the `iid`, `title`, `web_url` and a few other fields of the requests (API v4)
 //! - `order_by` (optional) - Return requests ordered by `created_at` or `updated_at` fields. Default is `created_at`
 //! - `sort` (optional) - Return requests sorted in `asc` or `desc` order. Default is `desc`
 //!
+//! Multiple `iid`s are sent as an array (`iid[]=42&iid[]=43`), the encoding GitLab expects.
+//! The API v4 renamed the parameter `iids[]`, also used for a single `iid`: it is used once
+//! `GitLab::server_version()` found a GitLab providing the API v4 (see `GitLab::api_version()`).
+//! Since some GitLab versions ignore the array and return all the merge requests, the listed
+//! merge requests are also filtered client-side: only the ones having a requested `iid` are
+//! returned, looking through all the pages.
 //!
 
 
-// use serde_urlencoded;
+use serde;
+use serde_urlencoded;
 
 use BuildQuery;
 use Lister;
+use PageInfo;
 
+pub mod create;
 pub mod single;
+#[cfg(feature = "ee")]
+pub mod single_approvals;
 
 use ::errors::*;
 
 
-#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
 pub enum State {
     #[serde(rename = "merged")]
     Merged,
@@ -49,7 +70,7 @@ pub enum State {
     All,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Status {
     #[serde(rename = "can_be_merged")]
     CanBeMerged,
@@ -57,10 +78,16 @@ pub enum Status {
     CannotBeMerged,
     #[serde(rename = "unchecked")]
     Unchecked,
+    /// GitLab is checking whether the merge request can be merged (API v4).
+    #[serde(rename = "checking")]
+    Checking,
+    /// The merge request could not be merged and is being checked again (API v4).
+    #[serde(rename = "cannot_be_merged_recheck")]
+    CannotBeMergedRecheck,
 }
 
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum ListingOrderBy {
     #[serde(rename = "created_at")]
     CreatedAt,
@@ -77,6 +104,16 @@ struct MergeRequestsListerInternal {
     iid: Option<Vec<i64>>,
     /// State of the requests
     state: Option<State>,
+    /// Search term to match against the requests' title and description
+    search: Option<String>,
+    /// ID of a user reviewing the requests
+    reviewer_id: Option<i64>,
+    /// Assignee of the requests
+    assignee_id: Option<::AssigneeFilter>,
+    /// Return the lighter `view=simple` payload
+    simple: bool,
+    /// Return the labels' details instead of their names
+    with_labels_details: Option<bool>,
     /// Return requests ordered by. Default is `ListingOrderBy::CreatedAt`.
     order_by: Option<ListingOrderBy>,
     /// Return requests sorted. Default is `ListingSort::Desc`.
@@ -85,6 +122,18 @@ struct MergeRequestsListerInternal {
 
 
 
+/// The latest pipeline of a merge request.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PipelineSummary {
+    pub id: i64,
+    /// For example `pending`, `running`, `success` or `failed`.
+    pub status: String,
+    #[serde(rename = "ref")]
+    pub git_ref: String,
+    pub sha: String,
+}
+
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct MergeRequest {
     pub id: i64,
@@ -93,20 +142,31 @@ pub struct MergeRequest {
     pub title: String,
     pub description: String,
     pub state: State,
-    pub created_at: String,  // FIXME: Use chrono crate
-    pub updated_at: String,  // FIXME: Use chrono crate
+    #[serde(with = "::datetime")]
+    pub created_at: ::datetime::DateTime,
+    #[serde(with = "::datetime")]
+    pub updated_at: ::datetime::DateTime,
     pub target_branch: String,
     pub source_branch: String,
     pub upvotes: i64,
     pub downvotes: i64,
     pub author: ::User,
     pub assignee: Option<::User>,
+    /// Empty when GitLab does not support reviewers (before API v4).
+    #[serde(default)]
+    pub reviewers: Vec<::User>,
     pub source_project_id: i64,
     pub target_project_id: i64,
-    pub labels: Vec<String>,
+    /// Only the names, unless listed with `with_labels_details(true)`
+    pub labels: ::Labels,
     pub work_in_progress: bool,
     pub milestone: Option<::Milestone>,
+    /// Renamed `merge_when_pipeline_succeeds` by the API v4, see `merges_when_pipeline_succeeds()`
+    #[serde(default)]
     pub merge_when_build_succeeds: bool,
+    /// (API v4)
+    #[serde(default)]
+    pub merge_when_pipeline_succeeds: bool,
     pub merge_status: Option<Status>,
     pub sha: Option<String>,
     pub merge_commit_sha: Option<String>,
@@ -114,11 +174,93 @@ pub struct MergeRequest {
     pub user_notes_count: Option<i64>,
     pub should_remove_source_branch: Option<bool>,
     pub force_remove_source_branch: Option<bool>,
-    pub web_url: Option<String>
+    pub web_url: Option<String>,
+    /// Only returned when getting a single merge request.
+    pub has_conflicts: Option<bool>,
+    /// Only returned when getting a single merge request.
+    pub blocking_discussions_resolved: Option<bool>,
+    /// Only returned by GitLab versions supporting discussion locking.
+    pub discussion_locked: Option<bool>,
+    /// Only returned when getting a single merge request (API v4).
+    pub pipeline: Option<PipelineSummary>,
+    #[serde(rename = "_links")]
+    pub links: Option<::Links>,
+    /// Whether the description contains checkbox tasks (API v4).
+    pub has_tasks: Option<bool>,
+    pub task_completion_status: Option<::TaskCompletionStatus>,
 }
 
 pub type MergeRequests = Vec<MergeRequest>;
 
+impl MergeRequest {
+    /// Whether the merge request is merged as soon as its pipeline succeeds, whatever the API
+    /// version it was returned by.
+    pub fn merges_when_pipeline_succeeds(&self) -> bool {
+        self.merge_when_build_succeeds || self.merge_when_pipeline_succeeds
+    }
+}
+
+impl ::pagination::Identifiable for MergeRequest {
+    fn id(&self) -> i64 {
+        self.id
+    }
+}
+
+
+/// The few fields of a merge request returned with `view=simple` (API v4).
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SimpleMergeRequest {
+    pub id: i64,
+    pub iid: i64,
+    pub project_id: i64,
+    pub title: String,
+    pub description: Option<String>,
+    pub state: State,
+    #[serde(with = "::datetime")]
+    pub created_at: ::datetime::DateTime,
+    #[serde(with = "::datetime")]
+    pub updated_at: ::datetime::DateTime,
+    pub web_url: String,
+}
+
+pub type SimpleMergeRequests = Vec<SimpleMergeRequest>;
+
+impl ::pagination::Identifiable for SimpleMergeRequest {
+    fn id(&self) -> i64 {
+        self.id
+    }
+}
+
+
+/// Change of state of a merge request, see `MergeRequestUpdate`.
+pub use StateEvent;
+
+
+/// Body of a merge request update.
+///
+/// Only the fields set are sent to GitLab, the others are left unchanged.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct MergeRequestUpdate {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target_branch: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub assignee_id: Option<i64>,
+    /// Replace the labels. An empty list removes all labels.
+    #[serde(skip_serializing_if = "Option::is_none", serialize_with = "::serialize_labels")]
+    pub labels: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state_event: Option<StateEvent>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub milestone_id: Option<i64>,
+    /// Replace the reviewers (API v4). An empty list removes all reviewers.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reviewer_ids: Option<Vec<i64>>,
+}
+
 
 #[derive(Debug, Clone)]
 pub struct MergeRequestsLister<'a> {
@@ -131,19 +273,57 @@ pub struct MergeRequestsLister<'a> {
 impl<'a> Lister<MergeRequests> for MergeRequestsLister<'a> {
     /// Commit the lister: Query GitLab and return a list of projects.
     fn list(&self) -> Result<MergeRequests> {
-        let query = self.build_query();
+        let query = self.build_full_query();
         debug!("query: {:?}", query);
 
-        self.gl.get(&query, None, None).chain_err(|| format!("cannot get query {}", query))
+        if self.internal.iid.is_some() {
+            return self.list_iids(&query,
+                                  &::transport::RequestOptions::new(),
+                                  |mr: &MergeRequest| mr.iid);
+        }
+
+        self.gl.get_listing(&query).chain_err(|| format!("cannot get query {}", query))
     }
 
     /// Commit the lister: Query GitLab and return a list of issues.
     fn list_paginated(&self, page: u16, per_page: u16) -> Result<MergeRequests> {
-        let query = self.build_query();
+        let query = self.build_full_query();
         debug!("query: {:?}", query);
 
         self.gl.get(&query, page, per_page).chain_err(|| format!("cannot get query {}", query))
     }
+
+    /// Commit the lister: Query GitLab and return a page of merge requests with its pagination information.
+    fn list_page(&self, page: u16, per_page: u16) -> Result<(MergeRequests, PageInfo)> {
+        let query = self.build_full_query();
+        debug!("query: {:?}", query);
+
+        self.gl
+            .get_with_headers(&query, page, per_page)
+            .chain_err(|| format!("cannot get query {}", query))
+    }
+
+    fn retain(&self, merge_requests: &mut MergeRequests) {
+        self.retain_iids(merge_requests, |mr: &MergeRequest| mr.iid)
+    }
+
+    /// Fetch only the first merge request, `None` if there is none.
+    ///
+    /// With an iid filter, which GitLab might ignore, the pages are fetched until a merge
+    /// request has one of the iids.
+    fn first<I>(&self) -> Result<Option<I>>
+        where Self: Lister<Vec<I>> + Sized
+    {
+        if self.internal.iid.is_none() {
+            let items = <Self as Lister<Vec<I>>>::list_paginated(self, 1, 1)?;
+            return Ok(items.into_iter().next());
+        }
+
+        match <Self as Lister<Vec<I>>>::iter(self).next() {
+            Some(item) => item.map(Some),
+            None => Ok(None),
+        }
+    }
 }
 
 
@@ -156,6 +336,11 @@ impl<'a> MergeRequestsLister<'a> {
             internal: MergeRequestsListerInternal {
                 iid: None,
                 state: None,
+                search: None,
+                reviewer_id: None,
+                assignee_id: None,
+                simple: false,
+                with_labels_details: None,
                 order_by: None,
                 sort: None,
             },
@@ -163,17 +348,38 @@ impl<'a> MergeRequestsLister<'a> {
     }
 
 
+    /// The merge request `merge_request_id`.
+    ///
+    /// The API v3 identifies a merge request by its global `id`, but the API v4 by its `iid`
+    /// within the project: `merge_request_id` is one or the other depending on
+    /// `GitLab::api_version()`. `GitLab::update_merge_request()` takes an `iid` with both.
     pub fn single(self, merge_request_id: i64) -> single::MergeRequestLister<'a> {
         // assert_eq!(self, MergeRequestLister::new(self.gl));
         single::MergeRequestLister::new(self.gl, self.id, merge_request_id)
     }
 
 
+    /// Prepare a merge request of `source_branch` into `target_branch`, titled `title`.
+    ///
+    /// Set its other settings on the returned creator, then call its `create()`.
+    pub fn create(self,
+                  source_branch: &str,
+                  target_branch: &str,
+                  title: &str)
+                  -> create::MergeRequestCreator<'a> {
+        create::MergeRequestCreator::new(self.gl,
+                                         self.id,
+                                         source_branch.to_string(),
+                                         target_branch.to_string(),
+                                         title.to_string())
+    }
+
+
+    /// Only list the merge requests having one of the `iid`s (see the module's documentation).
+    ///
+    /// `list()`, `first()` and the iterators go through the pages until all the merge requests
+    /// were found. The pages of `list_paginated()` and `list_page()` are not filtered.
     pub fn iid(&'a mut self, iid: Vec<i64>) -> &'a mut MergeRequestsLister {
-        info!("Using 'idd' fails when there is more than one element!");
-        if iid.len() > 1 {
-            println!("Using 'idd' fails when there is more than one element!");
-        }
         self.internal.iid = Some(iid);
         self
     }
@@ -181,6 +387,35 @@ impl<'a> MergeRequestsLister<'a> {
         self.internal.state = Some(state);
         self
     }
+    /// Only list the merge requests whose title or description contains `search`.
+    pub fn search(&'a mut self, search: String) -> &'a mut MergeRequestsLister {
+        self.internal.search = Some(search);
+        self
+    }
+    /// Only list the merge requests for which the user `reviewer_id` is a reviewer.
+    pub fn reviewer_id(&'a mut self, reviewer_id: i64) -> &'a mut MergeRequestsLister {
+        self.internal.reviewer_id = Some(reviewer_id);
+        self
+    }
+    /// Only list the merge requests matching `assignee`, for example the unassigned ones.
+    pub fn assignee_id(&'a mut self, assignee: ::AssigneeFilter) -> &'a mut MergeRequestsLister {
+        self.internal.assignee_id = Some(assignee);
+        self
+    }
+    /// Ask for the lighter `view=simple` payload, to be listed with `list_simple()`.
+    ///
+    /// `list()` and the other methods returning full merge requests ignore it.
+    pub fn simple(&'a mut self, simple: bool) -> &'a mut MergeRequestsLister {
+        self.internal.simple = simple;
+        self
+    }
+    /// Return the details of the labels (see `::Labels::Details`) instead of their names.
+    pub fn with_labels_details(&'a mut self,
+                               with_labels_details: bool)
+                               -> &'a mut MergeRequestsLister {
+        self.internal.with_labels_details = Some(with_labels_details);
+        self
+    }
     pub fn order_by(&'a mut self, order_by: ListingOrderBy) -> &'a mut MergeRequestsLister {
         self.internal.order_by = Some(order_by);
         self
@@ -189,6 +424,86 @@ impl<'a> MergeRequestsLister<'a> {
         self.internal.sort = Some(sort);
         self
     }
+
+    /// Same as `list()` but `options` (timeout, retries, headers) override the `GitLab`'s
+    /// request options for this call.
+    pub fn list_with(&self, options: &::transport::RequestOptions) -> Result<MergeRequests> {
+        let query = self.build_full_query();
+        debug!("query: {:?}", query);
+
+        if self.internal.iid.is_some() {
+            return self.list_iids(&query, options, |mr: &MergeRequest| mr.iid);
+        }
+
+        self.gl
+            .get_with_options(&query, None, None, options)
+            .chain_err(|| format!("cannot get query {}", query))
+    }
+
+    /// Commit the lister in simple mode (`view=simple`, API v4): Query GitLab and return a list
+    /// of lighter merge requests.
+    ///
+    /// The simple view is used even if `simple(true)` was not called.
+    pub fn list_simple(&self) -> Result<SimpleMergeRequests> {
+        let mut lister = self.clone();
+        lister.internal.simple = true;
+        let query = lister.build_query();
+        debug!("query: {:?}", query);
+
+        if self.internal.iid.is_some() {
+            return self.list_iids(&query,
+                                  &::transport::RequestOptions::new(),
+                                  |mr: &SimpleMergeRequest| mr.iid);
+        }
+
+        self.gl.get_listing(&query).chain_err(|| format!("cannot get query {}", query))
+    }
+
+    /// The merge requests of all the pages of `query` having one of the `iid`s asked for.
+    fn list_iids<T, F>(&self,
+                       query: &str,
+                       options: &::transport::RequestOptions,
+                       iid_of: F)
+                       -> Result<Vec<T>>
+        where T: serde::Deserialize,
+              F: Fn(&T) -> i64
+    {
+        let per_page = ::pagination::MAX_PER_PAGE;
+        let mut all_items = Vec::new();
+        let mut page = 1;
+
+        loop {
+            let (mut items, page_info): (Vec<T>, PageInfo) = self.gl
+                .get_with_headers_and_options(query, page, per_page, options)
+                .chain_err(|| format!("cannot get query {}", query))?;
+            let next_page = ::pagination::next_page(page, per_page, items.len(), &page_info);
+
+            self.retain_iids(&mut items, &iid_of);
+            all_items.append(&mut items);
+
+            match next_page {
+                Some(next_page) => page = next_page,
+                None => return Ok(all_items),
+            }
+        }
+    }
+
+    /// The query of the full merge requests, without `view=simple` which they cannot be
+    /// deserialized from.
+    fn build_full_query(&self) -> String {
+        let mut lister = self.clone();
+        lister.internal.simple = false;
+        lister.build_query()
+    }
+
+    /// Keep only the `items` having one of the `iid`s asked for, if any.
+    fn retain_iids<T, F>(&self, items: &mut Vec<T>, iid_of: F)
+        where F: Fn(&T) -> i64
+    {
+        if let Some(ref iids) = self.internal.iid {
+            items.retain(|item| iids.contains(&iid_of(item)));
+        }
+    }
 }
 
 
@@ -204,14 +519,20 @@ impl<'a> BuildQuery for MergeRequestsLister<'a> {
         let amp_char = "&";
         let none_char = "";
         let mut split_char = &none_char;
+        let api_v4 = self.gl.api_version() >= 4;
 
         // Append a "?" only if at least one of the `Option` is `Some(_)` or if
         // strings contain something.
         query.push_str(match (&self.internal.iid,
                               &self.internal.state,
+                              &self.internal.search,
+                              &self.internal.reviewer_id,
+                              &self.internal.assignee_id,
+                              self.internal.simple,
+                              &self.internal.with_labels_details,
                               &self.internal.order_by,
                               &self.internal.sort) {
-            (&None, &None, &None, &None) => "",
+            (&None, &None, &None, &None, &None, false, &None, &None, &None) => "",
             _ => "?",
         });
 
@@ -219,14 +540,14 @@ impl<'a> BuildQuery for MergeRequestsLister<'a> {
             query.push_str(split_char);
             split_char = &amp_char;
 
-            if iid.len() == 1 {
+            if iid.len() == 1 && !api_v4 {
                 query.push_str("iid=");
                 query.push_str(&iid[0].to_string());
             } else {
                 let mut array_split_char = &none_char;
                 for iid in iid {
                     query.push_str(array_split_char);
-                    query.push_str("iid[]=");
+                    query.push_str(if api_v4 { "iids[]=" } else { "iid[]=" });
                     query.push_str(&iid.to_string());
                     array_split_char = &amp_char;
                 }
@@ -246,6 +567,48 @@ impl<'a> BuildQuery for MergeRequestsLister<'a> {
             });
         });
 
+        self.internal.search.as_ref().map(|search| {
+            query.push_str(split_char);
+            split_char = &amp_char;
+
+            let params = &[("search", search)];
+            query.push_str(&serde_urlencoded::to_string(&params).unwrap());
+        });
+
+        self.internal.reviewer_id.map(|reviewer_id| {
+            query.push_str(split_char);
+            split_char = &amp_char;
+
+            query.push_str("reviewer_id=");
+            query.push_str(&reviewer_id.to_string());
+        });
+
+        self.internal.assignee_id.map(|assignee_id| {
+            query.push_str(split_char);
+            split_char = &amp_char;
+
+            query.push_str("assignee_id=");
+            query.push_str(&assignee_id.query_value());
+        });
+
+        if self.internal.simple {
+            query.push_str(split_char);
+            split_char = &amp_char;
+
+            query.push_str("view=simple");
+        }
+
+        self.internal.with_labels_details.map(|with_labels_details| {
+            query.push_str(split_char);
+            split_char = &amp_char;
+
+            if with_labels_details {
+                query.push_str("with_labels_details=true")
+            } else {
+                query.push_str("with_labels_details=false")
+            }
+        });
+
         self.internal.order_by.map(|order_by| {
             query.push_str(split_char);
             split_char = &amp_char;
@@ -274,12 +637,192 @@ impl<'a> BuildQuery for MergeRequestsLister<'a> {
 
 
 #[cfg(test)]
-mod tests {
+pub mod tests {
+    use serde_json;
+
     use BuildQuery;
+    use Lister;
+    use merge_requests::{ListingOrderBy, MergeRequest, MergeRequestUpdate, SimpleMergeRequests,
+                         State, StateEvent, Status};
+    use tests::mock_gitlab;
+    use transport::Response;
 
 
     const TEST_PROJECT_ID: i64 = 123;
 
+    pub const TEST_MERGE_REQUEST_JSON: &'static str = r##"{
+        "id": 1,
+        "iid": 1,
+        "project_id": 3,
+        "title": "test1",
+        "description": "fixed login page css paddings",
+        "state": "merged",
+        "created_at": "2017-04-29T08:46:00.000Z",
+        "updated_at": "2017-04-29T08:46:00.000Z",
+        "target_branch": "master",
+        "source_branch": "test1",
+        "upvotes": 0,
+        "downvotes": 0,
+        "author": {
+            "id": 1,
+            "name": "Administrator",
+            "username": "admin",
+            "state": "active",
+            "avatar_url": null,
+            "web_url" : "https://gitlab.example.com/admin"
+        },
+        "assignee": null,
+        "source_project_id": 2,
+        "target_project_id": 3,
+        "labels": [
+            "Community contribution",
+            "Manage"
+        ],
+        "work_in_progress": false,
+        "milestone": null,
+        "merge_when_build_succeeds": true,
+        "merge_status": "can_be_merged",
+        "sha": "8888888888888888888888888888888888888888",
+        "merge_commit_sha": null,
+        "subscribed": false,
+        "user_notes_count": 1,
+        "should_remove_source_branch": true,
+        "force_remove_source_branch": false,
+        "web_url": "http://example.com/example/example/merge_requests/1"
+    }"##;
+
+    /// A merge request with all the fields of `MergeRequest` set, and only them.
+    pub const TEST_FULL_MERGE_REQUEST_JSON: &'static str = r##"{
+        "id": 2,
+        "iid": 2,
+        "project_id": 3,
+        "title": "Fix the login page",
+        "description": "Closes #6",
+        "state": "opened",
+        "created_at": "2017-04-29T08:46:00.000Z",
+        "updated_at": "2017-04-30T10:00:00.000Z",
+        "target_branch": "master",
+        "source_branch": "fix-login",
+        "upvotes": 2,
+        "downvotes": 1,
+        "author": {
+            "id": 1,
+            "name": "Administrator",
+            "username": "admin",
+            "state": "active",
+            "avatar_url": "https://gitlab.example.com/uploads/user/avatar/1/admin.png",
+            "web_url": "https://gitlab.example.com/admin"
+        },
+        "assignee": {
+            "id": 5,
+            "name": "John Doe",
+            "username": "jdoe",
+            "state": "blocked",
+            "avatar_url": null,
+            "web_url": "https://gitlab.example.com/jdoe"
+        },
+        "reviewers": [
+            {
+                "id": 6,
+                "name": "Jane Doe",
+                "username": "jane",
+                "state": "active",
+                "avatar_url": null,
+                "web_url": null
+            }
+        ],
+        "source_project_id": 2,
+        "target_project_id": 3,
+        "labels": ["bug", "ui"],
+        "work_in_progress": true,
+        "milestone": {
+            "id": 4,
+            "iid": 1,
+            "project_id": 3,
+            "title": "v1.0",
+            "description": "First release",
+            "state": "closed",
+            "created_at": "2017-04-01T08:00:00.000Z",
+            "updated_at": "2017-04-02T08:00:00.000Z",
+            "due_date": "2017-05-01"
+        },
+        "merge_when_build_succeeds": false,
+        "merge_when_pipeline_succeeds": true,
+        "merge_status": "cannot_be_merged_recheck",
+        "sha": "8888888888888888888888888888888888888888",
+        "merge_commit_sha": "9999999999999999999999999999999999999999",
+        "subscribed": true,
+        "user_notes_count": 3,
+        "should_remove_source_branch": false,
+        "force_remove_source_branch": true,
+        "web_url": "https://gitlab.example.com/group/project/merge_requests/2",
+        "has_conflicts": false,
+        "blocking_discussions_resolved": true,
+        "discussion_locked": false,
+        "pipeline": {
+            "id": 77,
+            "status": "running",
+            "ref": "fix-login",
+            "sha": "8888888888888888888888888888888888888888"
+        },
+        "_links": {
+            "self": "http://example.com/api/v4/projects/3/merge_requests/2",
+            "notes": "http://example.com/api/v4/projects/3/merge_requests/2/notes",
+            "award_emoji": "http://example.com/api/v4/projects/3/merge_requests/2/award_emoji",
+            "project": "http://example.com/api/v4/projects/3"
+        },
+        "has_tasks": true,
+        "task_completion_status": {"count": 3, "completed_count": 1}
+    }"##;
+
+
+    #[test]
+    fn merge_request_round_trip() {
+        let merge_request: MergeRequest = serde_json::from_str(TEST_FULL_MERGE_REQUEST_JSON)
+            .expect("JSON deserialization failed");
+        assert_eq!(merge_request.merge_status, Some(Status::CannotBeMergedRecheck));
+
+        // Serializing gives back the same fields, with the same names and values...
+        let serialized = serde_json::to_string(&merge_request).unwrap();
+        let expected: serde_json::Value = serde_json::from_str(TEST_FULL_MERGE_REQUEST_JSON)
+            .unwrap();
+        let actual: serde_json::Value = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(actual, expected);
+
+        // ... which deserialize again to the same merge request.
+        let merge_request: MergeRequest = serde_json::from_str(&serialized)
+            .expect("JSON deserialization failed");
+        assert_eq!(serde_json::to_string(&merge_request).unwrap(), serialized);
+    }
+
+
+    #[test]
+    fn enums_round_trip() {
+        for &(state, json) in &[(State::Merged, "\"merged\""),
+                                (State::Opened, "\"opened\""),
+                                (State::Closed, "\"closed\""),
+                                (State::All, "\"all\"")] {
+            assert_eq!(serde_json::to_string(&state).unwrap(), json);
+            assert_eq!(serde_json::from_str::<State>(json).unwrap(), state);
+        }
+
+        for &(status, json) in &[(Status::CanBeMerged, "\"can_be_merged\""),
+                                 (Status::CannotBeMerged, "\"cannot_be_merged\""),
+                                 (Status::Unchecked, "\"unchecked\""),
+                                 (Status::Checking, "\"checking\""),
+                                 (Status::CannotBeMergedRecheck,
+                                  "\"cannot_be_merged_recheck\"")] {
+            assert_eq!(serde_json::to_string(&status).unwrap(), json);
+            assert_eq!(serde_json::from_str::<Status>(json).unwrap(), status);
+        }
+
+        for &(order_by, json) in &[(ListingOrderBy::CreatedAt, "\"created_at\""),
+                                   (ListingOrderBy::UpdatedAt, "\"updated_at\"")] {
+            assert_eq!(serde_json::to_string(&order_by).unwrap(), json);
+            assert_eq!(serde_json::from_str::<ListingOrderBy>(json).unwrap(), order_by);
+        }
+    }
+
 
     #[test]
     fn build_query_default() {
@@ -312,6 +855,126 @@ mod tests {
     }
 
 
+    #[test]
+    fn list_iids() {
+        let (gl, mock) = mock_gitlab();
+        // A GitLab ignoring the `iid[]` filter returns all the merge requests.
+        let all = format!("[{}, {}, {}]",
+                          TEST_MERGE_REQUEST_JSON.replace("\"iid\": 1,", "\"iid\": 456,"),
+                          TEST_MERGE_REQUEST_JSON.replace("\"iid\": 1,", "\"iid\": 12,"),
+                          TEST_MERGE_REQUEST_JSON.replace("\"iid\": 1,", "\"iid\": 789,"));
+        mock.push_response(Response::json(&all));
+        mock.push_response(Response::json(&all));
+
+        let merge_requests = gl.merge_requests(TEST_PROJECT_ID).iid(vec![456, 789]).list().unwrap();
+        let iids: Vec<i64> = merge_requests.iter().map(|mr| mr.iid).collect();
+        assert_eq!(iids, vec![456, 789]);
+
+        let merge_requests = gl.merge_requests(TEST_PROJECT_ID).list().unwrap();
+        assert_eq!(merge_requests.len(), 3);
+
+        let expected_url = "https://gitlab.example.com/api/v3/projects/123/merge_requests\
+                            ?iid[]=456&iid[]=789&private_token=";
+        assert!(mock.requests()[0].url.starts_with(expected_url));
+    }
+
+
+    #[test]
+    fn iids_past_first_page() {
+        let with_iids = |iids: &[i64], next_page: &str| {
+            let merge_requests: Vec<String> = iids.iter()
+                .map(|iid| {
+                    TEST_MERGE_REQUEST_JSON.replace("\"iid\": 1,", &format!("\"iid\": {},", iid))
+                })
+                .collect();
+            let mut response = Response::json(&format!("[{}]", merge_requests.join(",")));
+            if !next_page.is_empty() {
+                response.headers.set_raw("X-Next-Page", vec![next_page.as_bytes().to_vec()]);
+            }
+            response
+        };
+        let (gl, mock) = mock_gitlab();
+        let mut lister = gl.merge_requests(TEST_PROJECT_ID);
+        lister.iid(vec![456]);
+
+        // A GitLab ignoring the filter: the merge request is on the second page.
+        mock.push_response(with_iids(&[1, 2], "2"));
+        mock.push_response(with_iids(&[456], ""));
+        let iids: Vec<i64> = lister.list().unwrap().iter().map(|mr| mr.iid).collect();
+        assert_eq!(iids, vec![456]);
+
+        mock.push_response(with_iids(&[1, 2], "2"));
+        mock.push_response(with_iids(&[456], ""));
+        let merge_request: Option<MergeRequest> = lister.first().unwrap();
+        assert_eq!(merge_request.unwrap().iid, 456);
+
+        // Pages emptied by the filter don't end the iteration.
+        mock.push_response(with_iids(&[1], "2"));
+        mock.push_response(with_iids(&[2], "3"));
+        mock.push_response(with_iids(&[456], ""));
+        let iids = lister.iter()
+            .map(|mr: ::errors::Result<MergeRequest>| mr.map(|mr| mr.iid))
+            .collect::<::errors::Result<Vec<i64>>>()
+            .unwrap();
+        assert_eq!(iids, vec![456]);
+
+        let requests = mock.requests();
+        assert_eq!(requests.len(), 7);
+        assert!(requests[1].url.ends_with("&page=2&per_page=100"));
+        assert!(requests[3].url.ends_with("&page=2&per_page=100"));
+        assert!(requests[6].url.ends_with("&page=3&per_page=100"));
+    }
+
+
+    #[test]
+    fn stream_filter() {
+        let with_iid = |iid: i64| {
+            TEST_MERGE_REQUEST_JSON.replace("\"iid\": 1,", &format!("\"iid\": {},", iid))
+        };
+        let (gl, mock) = mock_gitlab();
+        let mut response = Response::json(&format!("[{}, {}]", with_iid(1), with_iid(2)));
+        response.headers.set_raw("X-Next-Page", vec![b"2".to_vec()]);
+        mock.push_response(response);
+        mock.push_response(Response::json(&format!("[{}]", with_iid(3))));
+
+        let lister = gl.merge_requests(TEST_PROJECT_ID);
+        // Keep the iids, except 2.
+        let iids = lister.stream_filter(|mr: MergeRequest| match mr.iid {
+                2 => None,
+                iid => Some(iid),
+            })
+            .collect::<::errors::Result<Vec<i64>>>()
+            .unwrap();
+        assert_eq!(iids, vec![1, 3]);
+
+        let requests = mock.requests();
+        assert_eq!(requests.len(), 2);
+        assert!(requests[1].url.contains("page=2"));
+    }
+
+
+    #[test]
+    fn first() {
+        let (gl, mock) = mock_gitlab();
+        mock.push_response(Response::json(&format!("[{}]", TEST_MERGE_REQUEST_JSON)));
+        mock.push_response(Response::json("[]"));
+
+        let merge_request: Option<MergeRequest> = gl.merge_requests(TEST_PROJECT_ID)
+            .sort(::ListingSort::Desc)
+            .first()
+            .unwrap();
+        assert_eq!(merge_request.unwrap().iid, 1);
+        let merge_request: Option<MergeRequest> =
+            gl.merge_requests(TEST_PROJECT_ID).first().unwrap();
+        assert!(merge_request.is_none());
+
+        let requests = mock.requests();
+        assert_eq!(requests.len(), 2);
+        assert!(requests[0].url.ends_with("&page=1&per_page=1"));
+        assert!(requests[1].url.ends_with("&page=1&per_page=1"));
+    }
+
+
     #[test]
     fn build_query_state() {
         let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
@@ -339,6 +1002,200 @@ mod tests {
     }
 
 
+    #[test]
+    fn build_query_search() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+        // let gl: ::GitLab = Default::default();
+
+        let expected_string = format!("projects/{}/merge_requests?search=fix+login+%26+logout",
+                                      TEST_PROJECT_ID);
+        let query = gl.merge_requests(TEST_PROJECT_ID)
+            .search("fix login & logout".to_string())
+            .build_query();
+        assert_eq!(query, expected_string);
+
+        let expected_string = format!("projects/{}/merge_requests?state=opened&search=login",
+                                      TEST_PROJECT_ID);
+        let query = gl.merge_requests(TEST_PROJECT_ID)
+            .search("login".to_string())
+            .state(::merge_requests::State::Opened)
+            .build_query();
+        assert_eq!(query, expected_string);
+    }
+
+
+    #[test]
+    fn build_query_reviewer_id() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+        // let gl: ::GitLab = Default::default();
+
+        let expected_string = format!("projects/{}/merge_requests?reviewer_id=5",
+                                      TEST_PROJECT_ID);
+        let query = gl.merge_requests(TEST_PROJECT_ID).reviewer_id(5).build_query();
+        assert_eq!(query, expected_string);
+
+        let expected_string = format!("projects/{}/merge_requests?state=opened&reviewer_id=5",
+                                      TEST_PROJECT_ID);
+        let query = gl.merge_requests(TEST_PROJECT_ID)
+            .reviewer_id(5)
+            .state(::merge_requests::State::Opened)
+            .build_query();
+        assert_eq!(query, expected_string);
+    }
+
+
+    #[test]
+    fn build_query_assignee_id() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+        // let gl: ::GitLab = Default::default();
+
+        let expected_string = format!("projects/{}/merge_requests?assignee_id=None",
+                                      TEST_PROJECT_ID);
+        let query = gl.merge_requests(TEST_PROJECT_ID)
+            .assignee_id(::AssigneeFilter::None)
+            .build_query();
+        assert_eq!(query, expected_string);
+
+        let expected_string = format!("projects/{}/merge_requests?assignee_id=Any",
+                                      TEST_PROJECT_ID);
+        let query = gl.merge_requests(TEST_PROJECT_ID)
+            .assignee_id(::AssigneeFilter::Any)
+            .build_query();
+        assert_eq!(query, expected_string);
+
+        let expected_string = format!("projects/{}/merge_requests?state=opened&assignee_id=5",
+                                      TEST_PROJECT_ID);
+        let query = gl.merge_requests(TEST_PROJECT_ID)
+            .assignee_id(::AssigneeFilter::Id(5))
+            .state(::merge_requests::State::Opened)
+            .build_query();
+        assert_eq!(query, expected_string);
+    }
+
+
+    #[test]
+    fn build_query_simple() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+        // let gl: ::GitLab = Default::default();
+
+        let expected_string = format!("projects/{}/merge_requests?view=simple", TEST_PROJECT_ID);
+        let query = gl.merge_requests(TEST_PROJECT_ID).simple(true).build_query();
+        assert_eq!(query, expected_string);
+
+        let expected_string = format!("projects/{}/merge_requests?state=opened&view=simple",
+                                      TEST_PROJECT_ID);
+        let query = gl.merge_requests(TEST_PROJECT_ID)
+            .simple(true)
+            .state(::merge_requests::State::Opened)
+            .build_query();
+        assert_eq!(query, expected_string);
+
+        let expected_string = format!("projects/{}/merge_requests", TEST_PROJECT_ID);
+        let query = gl.merge_requests(TEST_PROJECT_ID).simple(false).build_query();
+        assert_eq!(query, expected_string);
+    }
+
+
+    #[test]
+    fn deserialize_simple_merge_requests() {
+        let json_reply = r##"[
+            {
+                "id": 84,
+                "iid": 14,
+                "project_id": 4,
+                "title": "Impedit et ut et dolores vero provident ullam est",
+                "description": null,
+                "state": "opened",
+                "created_at": "2017-04-29T08:46:00.000Z",
+                "updated_at": "2017-04-29T08:46:00.000Z",
+                "web_url": "http://example.com/example/example/merge_requests/14"
+            }
+        ]"##;
+
+        let merge_requests: SimpleMergeRequests = serde_json::from_str(json_reply)
+            .expect("JSON deserialization failed");
+        assert_eq!(merge_requests[0].iid, 14);
+        assert_eq!(merge_requests[0].state, ::merge_requests::State::Opened);
+        assert_eq!(merge_requests[0].description, None);
+        assert_eq!(merge_requests[0].web_url,
+                   "http://example.com/example/example/merge_requests/14");
+    }
+
+
+    #[test]
+    fn list_ignores_simple() {
+        let (gl, mock) = mock_gitlab();
+        mock.push_response(Response::json(&format!("[{}]", TEST_MERGE_REQUEST_JSON)));
+        mock.push_response(Response::json(&format!("[{}]", TEST_MERGE_REQUEST_JSON)));
+
+        let mut lister = gl.merge_requests(TEST_PROJECT_ID);
+        lister.simple(true);
+        let merge_requests = lister.list().unwrap();
+        assert_eq!(merge_requests[0].iid, 1);
+        let merge_requests = lister.list_simple().unwrap();
+        assert_eq!(merge_requests[0].iid, 1);
+
+        let requests = mock.requests();
+        assert!(!requests[0].url.contains("view=simple"));
+        assert!(requests[1].url.contains("?view=simple&"));
+    }
+
+
+    #[test]
+    fn build_query_with_labels_details() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+        // let gl: ::GitLab = Default::default();
+
+        let expected_string = format!("projects/{}/merge_requests?with_labels_details=true",
+                                      TEST_PROJECT_ID);
+        let query = gl.merge_requests(TEST_PROJECT_ID).with_labels_details(true).build_query();
+        assert_eq!(query, expected_string);
+    }
+
+
+    #[test]
+    fn deserialize_merge_request_label_names() {
+        let merge_request: MergeRequest = serde_json::from_str(TEST_MERGE_REQUEST_JSON)
+            .expect("JSON deserialization failed");
+        assert_eq!(merge_request.labels,
+                   ::Labels::Names(vec![String::from("Community contribution"),
+                                        String::from("Manage")]));
+        assert_eq!(merge_request.labels.names(), vec!["Community contribution", "Manage"]);
+    }
+
+
+    #[test]
+    fn deserialize_merge_request_label_details() {
+        let label_names = r#""Community contribution",
+            "Manage""#;
+        let json_reply = TEST_MERGE_REQUEST_JSON.replace(label_names, r##"{
+                "id": 3,
+                "name": "Community contribution",
+                "color": "#a8d695",
+                "text_color": "#333333",
+                "description": null
+            },
+            {
+                "name": "Manage",
+                "color": "#d9534f",
+                "description": "Manage stage"
+            }"##);
+        let merge_request: MergeRequest = serde_json::from_str(&json_reply)
+            .expect("JSON deserialization failed");
+
+        match merge_request.labels {
+            ::Labels::Details(ref labels) => {
+                assert_eq!(labels.len(), 2);
+                assert_eq!(labels[0].color, "#a8d695");
+                assert_eq!(labels[0].description, None);
+                assert_eq!(labels[1].description, Some(String::from("Manage stage")));
+            }
+            ref labels => panic!("expected the labels' details, got {:?}", labels),
+        }
+        assert_eq!(merge_request.labels.names(), vec!["Community contribution", "Manage"]);
+    }
+
+
     #[test]
     fn build_query_order_by() {
         let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
@@ -390,4 +1247,134 @@ mod tests {
             .build_query();
         assert_eq!(query, expected_string);
     }
+
+
+    #[test]
+    fn serialize_merge_request_update() {
+        let update: MergeRequestUpdate = Default::default();
+        assert_eq!(serde_json::to_string(&update).unwrap(), "{}");
+
+        let update = MergeRequestUpdate {
+            labels: Some(vec![String::from("bug"), String::from("needs review")]),
+            state_event: Some(StateEvent::Close),
+            milestone_id: Some(3),
+            ..Default::default()
+        };
+        assert_eq!(serde_json::to_string(&update).unwrap(),
+                   r#"{"labels":"bug,needs review","state_event":"close","milestone_id":3}"#);
+    }
+
+
+    #[test]
+    fn deserialize_merge_request() {
+        let merge_request: MergeRequest = serde_json::from_str(TEST_MERGE_REQUEST_JSON)
+            .expect("JSON deserialization failed");
+        assert_eq!(merge_request.iid, 1);
+        assert_eq!(merge_request.has_conflicts, None);
+        assert_eq!(merge_request.blocking_discussions_resolved, None);
+        assert_eq!(merge_request.discussion_locked, None);
+        assert!(merge_request.pipeline.is_none());
+        assert!(merge_request.reviewers.is_empty());
+        assert!(merge_request.links.is_none());
+    }
+
+
+    #[test]
+    fn deserialize_merge_request_conflicts() {
+        let json_reply = TEST_MERGE_REQUEST_JSON.replace("\"merge_commit_sha\": null,",
+            "\"merge_commit_sha\": null, \"has_conflicts\": true, \
+             \"blocking_discussions_resolved\": false,");
+        let merge_request: MergeRequest = serde_json::from_str(&json_reply)
+            .expect("JSON deserialization failed");
+        assert_eq!(merge_request.has_conflicts, Some(true));
+        assert_eq!(merge_request.blocking_discussions_resolved, Some(false));
+    }
+
+
+    #[test]
+    fn deserialize_merge_request_discussion_locked() {
+        let json_reply = TEST_MERGE_REQUEST_JSON.replace("\"merge_commit_sha\": null,",
+            "\"merge_commit_sha\": null, \"discussion_locked\": false,");
+        let merge_request: MergeRequest = serde_json::from_str(&json_reply)
+            .expect("JSON deserialization failed");
+        assert_eq!(merge_request.discussion_locked, Some(false));
+    }
+
+
+    #[test]
+    fn deserialize_merge_request_reviewers() {
+        let json_reply = TEST_MERGE_REQUEST_JSON.replace("\"assignee\": null,",
+            r#""assignee": null,
+               "reviewers": [{
+                   "id": 2,
+                   "name": "Sam Bauch",
+                   "username": "kenyatta_oconnell",
+                   "state": "active",
+                   "avatar_url": null,
+                   "web_url": "http://gitlab.example.com//kenyatta_oconnell"
+               }],"#);
+        let merge_request: MergeRequest = serde_json::from_str(&json_reply)
+            .expect("JSON deserialization failed");
+        assert_eq!(merge_request.reviewers.len(), 1);
+        assert_eq!(merge_request.reviewers[0].id, 2);
+    }
+
+
+    #[test]
+    fn deserialize_merge_request_links() {
+        let json_reply = TEST_MERGE_REQUEST_JSON.replace("\"merge_commit_sha\": null,",
+            r#""merge_commit_sha": null,
+               "_links": {
+                   "self": "http://example.com/api/v4/projects/3/merge_requests/1",
+                   "notes": "http://example.com/api/v4/projects/3/merge_requests/1/notes",
+                   "award_emoji": "http://example.com/api/v4/projects/3/merge_requests/1/award_emoji",
+                   "project": "http://example.com/api/v4/projects/3"
+               },"#);
+        let merge_request: MergeRequest = serde_json::from_str(&json_reply)
+            .expect("JSON deserialization failed");
+
+        let links = merge_request.links.expect("_links not deserialized");
+        assert_eq!(links.self_url, "http://example.com/api/v4/projects/3/merge_requests/1");
+        assert_eq!(links.award_emoji,
+                   "http://example.com/api/v4/projects/3/merge_requests/1/award_emoji");
+    }
+
+
+    #[test]
+    fn deserialize_merge_request_task_completion_status() {
+        let json_reply = TEST_MERGE_REQUEST_JSON.replace("\"merge_commit_sha\": null,",
+            r#""merge_commit_sha": null,
+               "task_completion_status": {
+                   "count": 2,
+                   "completed_count": 0
+               },"#);
+        let merge_request: MergeRequest = serde_json::from_str(&json_reply)
+            .expect("JSON deserialization failed");
+
+        let status = merge_request.task_completion_status.expect("task status not deserialized");
+        assert_eq!(status.count, 2);
+        assert_eq!(status.completed_count, 0);
+        assert_eq!(merge_request.has_tasks, None);
+    }
+
+
+    #[test]
+    fn deserialize_merge_request_pipeline() {
+        let json_reply = TEST_MERGE_REQUEST_JSON.replace("\"merge_commit_sha\": null,",
+            r#""merge_commit_sha": null,
+               "pipeline": {
+                   "id": 29626725,
+                   "sha": "2be7ddb704c7b6b83732fdd5b9f09d5a397b5f8f",
+                   "ref": "patch-28",
+                   "status": "success"
+               },"#);
+        let merge_request: MergeRequest = serde_json::from_str(&json_reply)
+            .expect("JSON deserialization failed");
+
+        let pipeline = merge_request.pipeline.expect("pipeline not deserialized");
+        assert_eq!(pipeline.id, 29626725);
+        assert_eq!(pipeline.status, "success");
+        assert_eq!(pipeline.git_ref, "patch-28");
+        assert_eq!(pipeline.sha, "2be7ddb704c7b6b83732fdd5b9f09d5a397b5f8f");
+    }
 }
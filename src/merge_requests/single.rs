@@ -15,14 +15,74 @@
 //! - `id` (required) - The ID of a project
 //! - `merge_request_id` (required) - The ID of MR
 //!
+//! # Accept MR
+//!
+//! Merge changes submitted with MR.
+//!
+//! ```text
+//! PUT /projects/ID/merge_requests/MERGE_REQUEST_ID/merge
+//! ```
+//!
+//! Parameters:
+//!
+//! - `id` (required) - The ID of a project
+//! - `merge_request_id` (required) - The ID of MR
+//! - `merge_commit_message` (optional) - Custom merge commit message
+//! - `should_remove_source_branch` (optional) - If `true` removes the source branch
+//! - `merge_when_build_succeeds` (optional) - If `true` the MR is merged when the build succeeds
+//!   (`merge_when_pipeline_succeeds` with the API v4)
+//!
+//! If the MR cannot be merged (it has conflicts, is already merged or closed, ...), GitLab
+//! answers `405 Method Not Allowed` (or `406 Not Acceptable` with the API v4): this is reported
+//! as `ErrorKind::NotMergeable`.
+//!
+//! # List issues that will close on merge
+//!
+//! Get all the issues that would be closed by merging the provided merge request.
+//!
+//! ```text
+//! GET /projects/ID/merge_requests/MERGE_REQUEST_ID/closes_issues
+//! ```
+//!
+//! Parameters:
+//!
+//! - `id` (required) - The ID of a project
+//! - `merge_request_id` (required) - The ID of MR
+//!
+//! # Update MR
+//!
+//! Updates an existing merge request. You can change the target branch, title, or even close
+//! the MR.
+//!
+//! ```text
+//! PUT /projects/ID/merge_requests/MERGE_REQUEST_ID
+//! ```
+//!
+//! Parameters:
+//!
+//! - `id` (required) - The ID of a project
+//! - `merge_request_id` (required) - The ID of MR
+//! - `title` (optional) - Title of MR
+//! - `description` (optional) - Description of MR
+//! - `target_branch` (optional) - The target branch
+//! - `assignee_id` (optional) - The ID of the user to assign the MR to
+//! - `labels` (optional) - Comma-separated label names for the MR
+//! - `state_event` (optional) - New state (`close` or `reopen`)
+//! - `milestone_id` (optional) - The ID of the milestone to assign the MR to
+//! - `discussion_locked` (optional) - Flag indicating if the MR's discussion is locked
+//! - `reviewer_ids` (optional) - The IDs of the users to review the MR (API v4)
+//!
 //!
 
 
 // use serde_urlencoded;
 
+use std::thread;
+use std::time::Duration;
+
 use BuildQuery;
 
-use merge_requests::MergeRequest;
+use merge_requests::{MergeRequest, MergeRequestUpdate, State};
 
 use ::errors::*;
 
@@ -52,6 +112,209 @@ impl<'a> MergeRequestLister<'a> {
 
         self.gl.get(&query, None, None).chain_err(|| format!("cannot get query {}", query))
     }
+
+    /// Accept the merge request, either right away or, if `opts.merge_when_build_succeeds` is
+    /// `Some(true)`, as soon as its pipeline succeeds.
+    ///
+    /// Returns the updated merge request. A merge request that cannot be merged is reported as
+    /// `ErrorKind::NotMergeable`.
+    pub fn accept(&self, opts: &AcceptOpts) -> Result<MergeRequest> {
+        let query = self.build_accept_query();
+        debug!("query: {:?}", query);
+
+        let result = if self.gl.api_version() >= 4 {
+            self.gl.put(&query, AcceptOptsV4::new(opts))
+        } else {
+            self.gl.put(&query, opts)
+        };
+        match result {
+            Err(Error(ErrorKind::UnexpectedStatus(405), _)) |
+            Err(Error(ErrorKind::UnexpectedStatus(406), _)) => {
+                bail!(ErrorKind::NotMergeable(self.mr_id))
+            }
+            result => result.chain_err(|| format!("cannot put query {}", query)),
+        }
+    }
+
+    fn build_accept_query(&self) -> String {
+        format!("{}/merge", self.build_query())
+    }
+
+    /// The approvals of the merge request (GitLab EE only).
+    #[cfg(feature = "ee")]
+    pub fn approvals(self) -> ::merge_requests::single_approvals::ApprovalsLister<'a> {
+        ::merge_requests::single_approvals::ApprovalsLister::new(self.gl, self.id, self.mr_id)
+    }
+
+    /// Query GitLab and return the issues closed by merging the merge request (for example
+    /// because its description contains `Closes #42`).
+    pub fn closes_issues(&self) -> Result<::issues::Issues> {
+        let query = self.build_closes_issues_query();
+        debug!("query: {:?}", query);
+
+        self.gl.get_listing(&query).chain_err(|| format!("cannot get query {}", query))
+    }
+
+    fn build_closes_issues_query(&self) -> String {
+        format!("{}/closes_issues", self.build_query())
+    }
+
+    /// Update the merge request: only the fields set in `update` are changed.
+    ///
+    /// Returns the updated merge request.
+    pub fn update(&self, update: &MergeRequestUpdate) -> Result<MergeRequest> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl.put(&query, update).chain_err(|| format!("cannot put query {}", query))
+    }
+
+    /// Lock the merge request's discussion: only project members can then comment.
+    ///
+    /// Returns the updated merge request.
+    pub fn lock_discussion(&self) -> Result<MergeRequest> {
+        self.set_discussion_locked(true)
+    }
+
+    /// Unlock the merge request's discussion.
+    ///
+    /// Returns the updated merge request.
+    pub fn unlock_discussion(&self) -> Result<MergeRequest> {
+        self.set_discussion_locked(false)
+    }
+
+    fn set_discussion_locked(&self, discussion_locked: bool) -> Result<MergeRequest> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        let body = DiscussionLockInternal { discussion_locked: discussion_locked };
+
+        self.gl.put(&query, &body).chain_err(|| format!("cannot put query {}", query))
+    }
+
+    /// Replace the merge request's reviewers by the users `reviewer_ids`.
+    ///
+    /// An empty list removes all reviewers. Returns the updated merge request.
+    pub fn set_reviewers(&self, reviewer_ids: Vec<i64>) -> Result<MergeRequest> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        let body = ReviewersInternal { reviewer_ids: reviewer_ids };
+
+        self.gl.put(&query, &body).chain_err(|| format!("cannot put query {}", query))
+    }
+
+    /// Poll the merge request until it is settled or until `timeout` has passed.
+    ///
+    /// The merge request is settled when it got merged or closed, or when GitLab cancelled
+    /// its automatic merge (for example because its pipeline failed). The polling interval
+    /// starts at one second and doubles after each poll, up to 30 seconds.
+    ///
+    /// Returns the merge request in its final state.
+    pub fn wait_until_settled(&self, timeout: Duration) -> Result<MergeRequest> {
+        poll_until_settled(|| self.list(), |delay| thread::sleep(delay), timeout)
+    }
+}
+
+
+#[derive(Debug, Serialize)]
+struct DiscussionLockInternal {
+    discussion_locked: bool,
+}
+
+
+#[derive(Debug, Serialize)]
+struct ReviewersInternal {
+    reviewer_ids: Vec<i64>,
+}
+
+
+/// The options of `MergeRequestLister::accept()`. The fields left to `None` are not sent.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct AcceptOpts {
+    /// The message of the merge commit, instead of GitLab's default one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub merge_commit_message: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub should_remove_source_branch: Option<bool>,
+    /// Merge once the pipeline succeeds instead of right away
+    ///
+    /// Sent as `merge_when_pipeline_succeeds` with the API v4.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub merge_when_build_succeeds: Option<bool>,
+}
+
+
+/// `AcceptOpts` with the names of the API v4.
+#[derive(Debug, Serialize)]
+struct AcceptOptsV4 {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    merge_commit_message: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    should_remove_source_branch: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    merge_when_pipeline_succeeds: Option<bool>,
+}
+
+impl AcceptOptsV4 {
+    fn new(opts: &AcceptOpts) -> AcceptOptsV4 {
+        AcceptOptsV4 {
+            merge_commit_message: opts.merge_commit_message.clone(),
+            should_remove_source_branch: opts.should_remove_source_branch,
+            merge_when_pipeline_succeeds: opts.merge_when_build_succeeds,
+        }
+    }
+}
+
+
+const POLL_INITIAL_DELAY_SECS: u64 = 1;
+const POLL_MAX_DELAY_SECS: u64 = 30;
+
+
+/// Is there nothing left to wait for on the merge request?
+fn is_settled(merge_request: &MergeRequest) -> bool {
+    match merge_request.state {
+        State::Merged | State::Closed => true,
+        // GitLab cancels the automatic merge when the pipeline fails.
+        State::Opened | State::All => !merge_request.merges_when_pipeline_succeeds(),
+    }
+}
+
+
+fn poll_until_settled<F, S>(mut fetch: F, mut sleep: S, timeout: Duration) -> Result<MergeRequest>
+    where F: FnMut() -> Result<MergeRequest>,
+          S: FnMut(Duration)
+{
+    let mut waited = Duration::from_secs(0);
+    let mut delay = Duration::from_secs(POLL_INITIAL_DELAY_SECS);
+
+    loop {
+        let merge_request = fetch().chain_err(|| "cannot poll merge request")?;
+        if is_settled(&merge_request) {
+            return Ok(merge_request);
+        }
+
+        if waited >= timeout {
+            bail!(format!("merge request {} not merged after {} seconds (state: {:?})",
+                          merge_request.iid,
+                          waited.as_secs(),
+                          merge_request.state));
+        }
+
+        // Don't sleep past the timeout.
+        let remaining = timeout - waited;
+        let this_delay = if delay < remaining { delay } else { remaining };
+        debug!("merge request {} not settled, polling again in {:?}",
+               merge_request.iid,
+               this_delay);
+        sleep(this_delay);
+        waited += this_delay;
+
+        delay = delay * 2;
+        if delay > Duration::from_secs(POLL_MAX_DELAY_SECS) {
+            delay = Duration::from_secs(POLL_MAX_DELAY_SECS);
+        }
+    }
 }
 
 
@@ -64,7 +327,18 @@ impl<'a> BuildQuery for MergeRequestLister<'a> {
 
 #[cfg(test)]
 mod tests {
+    use std::cell::RefCell;
+    use std::time::Duration;
+
+    use hyper;
+    use serde_json;
+
     use BuildQuery;
+    use merge_requests::{MergeRequest, MergeRequestUpdate};
+    use merge_requests::single::{AcceptOpts, poll_until_settled};
+    use merge_requests::tests::TEST_MERGE_REQUEST_JSON;
+    use tests::mock_gitlab;
+    use transport::Response;
 
     const TEST_PROJECT_ID: i64 = 123;
     const TEST_MR_ID: i64 = 456;
@@ -88,4 +362,289 @@ mod tests {
         let query = gl.merge_requests(TEST_PROJECT_ID).single(TEST_MR_ID).build_query();
         assert_eq!(query, expected_string);
     }
+
+
+    #[test]
+    fn build_closes_issues_query() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+
+        let expected_string = format!("projects/{}/merge_requests/{}/closes_issues",
+                                      TEST_PROJECT_ID,
+                                      TEST_MR_ID);
+        let query = gl.merge_requests(TEST_PROJECT_ID)
+            .single(TEST_MR_ID)
+            .build_closes_issues_query();
+        assert_eq!(query, expected_string);
+    }
+
+
+    #[test]
+    fn build_accept_query() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+
+        let query = gl.merge_requests(9).single(42).build_accept_query();
+        assert_eq!(query, "projects/9/merge_requests/42/merge");
+    }
+
+
+    #[test]
+    fn serialize_accept_opts() {
+        let opts: AcceptOpts = Default::default();
+        assert_eq!(serde_json::to_string(&opts).unwrap(), "{}");
+
+        let opts = AcceptOpts {
+            merge_commit_message: Some(String::from("Merge the login page")),
+            should_remove_source_branch: Some(true),
+            merge_when_build_succeeds: Some(false),
+        };
+        assert_eq!(serde_json::to_string(&opts).unwrap(),
+                   concat!(r#"{"merge_commit_message":"Merge the login page","#,
+                           r#""should_remove_source_branch":true,"#,
+                           r#""merge_when_build_succeeds":false}"#));
+    }
+
+
+    #[test]
+    fn accept() {
+        let (gl, mock) = mock_gitlab();
+        mock.push_response(Response::json(TEST_MERGE_REQUEST_JSON));
+        let mut response = Response::json(r#"{"message": "405 Method Not Allowed"}"#);
+        response.status = hyper::status::StatusCode::MethodNotAllowed;
+        mock.push_response(response);
+
+        let opts = AcceptOpts { should_remove_source_branch: Some(true), ..Default::default() };
+        let merge_request = gl.merge_requests(9).single(42).accept(&opts).unwrap();
+        assert_eq!(merge_request.state, ::merge_requests::State::Merged);
+
+        match gl.merge_requests(9).single(42).accept(&opts) {
+            Err(::errors::Error(::errors::ErrorKind::NotMergeable(42), _)) => {}
+            other => panic!("expected a not mergeable error, got {:?}", other),
+        }
+
+        let requests = mock.requests();
+        assert_eq!(requests[0].method, hyper::method::Method::Put);
+        assert!(requests[0]
+            .url
+            .starts_with("https://gitlab.example.com/api/v3/projects/9/merge_requests/42/merge?"));
+        assert_eq!(requests[0].body,
+                   Some(String::from(r#"{"should_remove_source_branch":true}"#)));
+    }
+
+
+    #[test]
+    fn accept_api_v4() {
+        let (gl, mock) = mock_gitlab();
+        mock.push_response(Response::json(r#"{"version": "10.8.0", "revision": "a"}"#));
+        mock.push_response(Response::json(TEST_MERGE_REQUEST_JSON));
+        let mut response = Response::json(r#"{"message": "406 Not Acceptable"}"#);
+        response.status = hyper::status::StatusCode::NotAcceptable;
+        mock.push_response(response);
+        assert_eq!(gl.server_version().unwrap().major, 10);
+
+        let opts = AcceptOpts { merge_when_build_succeeds: Some(true), ..Default::default() };
+        gl.merge_requests(9).single(7).accept(&opts).unwrap();
+        match gl.merge_requests(9).single(7).accept(&opts) {
+            Err(::errors::Error(::errors::ErrorKind::NotMergeable(7), _)) => {}
+            other => panic!("expected a not mergeable error, got {:?}", other),
+        }
+
+        let requests = mock.requests();
+        assert!(requests[1]
+            .url
+            .starts_with("https://gitlab.example.com/api/v4/projects/9/merge_requests/7/merge?"));
+        assert_eq!(requests[1].body,
+                   Some(String::from(r#"{"merge_when_pipeline_succeeds":true}"#)));
+    }
+
+
+    #[test]
+    fn closes_issues() {
+        let (gl, mock) = mock_gitlab();
+        mock.push_response(Response::json("[]"));
+
+        let issues = gl.merge_requests(TEST_PROJECT_ID).single(TEST_MR_ID).closes_issues().unwrap();
+        assert!(issues.is_empty());
+
+        let requests = mock.requests();
+        assert_eq!(requests[0].method, hyper::method::Method::Get);
+        let expected_url = format!("https://gitlab.example.com/api/v3/projects/{}/merge_requests/{}\
+                                    /closes_issues?",
+                                   TEST_PROJECT_ID,
+                                   TEST_MR_ID);
+        assert!(requests[0].url.starts_with(&expected_url));
+    }
+
+
+    fn merge_request(state: &str, merge_when_build_succeeds: bool) -> MergeRequest {
+        let json = TEST_MERGE_REQUEST_JSON
+            .replace("\"state\": \"merged\"", &format!("\"state\": \"{}\"", state))
+            .replace("\"merge_when_build_succeeds\": true",
+                     &format!("\"merge_when_build_succeeds\": {}", merge_when_build_succeeds));
+        serde_json::from_str(&json).unwrap()
+    }
+
+
+    #[test]
+    fn poll_pending_then_merged() {
+        let mut polls = vec![merge_request("opened", true),
+                             merge_request("opened", true),
+                             merge_request("merged", true)];
+        polls.reverse();
+        let slept = RefCell::new(Vec::new());
+
+        let merge_request = poll_until_settled(|| Ok(polls.pop().unwrap()),
+                                               |delay| slept.borrow_mut().push(delay),
+                                               Duration::from_secs(60))
+            .unwrap();
+
+        assert_eq!(merge_request.state, ::merge_requests::State::Merged);
+        assert_eq!(*slept.borrow(),
+                   vec![Duration::from_secs(1), Duration::from_secs(2)]);
+    }
+
+
+    #[test]
+    fn poll_pipeline_failed() {
+        // GitLab cancels the automatic merge when the pipeline fails.
+        let mut polls = vec![merge_request("opened", true), merge_request("opened", false)];
+        polls.reverse();
+
+        let merge_request = poll_until_settled(|| Ok(polls.pop().unwrap()),
+                                               |_| {},
+                                               Duration::from_secs(60))
+            .unwrap();
+
+        assert_eq!(merge_request.state, ::merge_requests::State::Opened);
+        assert!(!merge_request.merge_when_build_succeeds);
+    }
+
+
+    #[test]
+    fn poll_timeout() {
+        let slept = RefCell::new(Vec::new());
+
+        let result = poll_until_settled(|| Ok(merge_request("opened", true)),
+                                        |delay| slept.borrow_mut().push(delay),
+                                        Duration::from_secs(5));
+
+        assert!(result.is_err());
+        assert_eq!(*slept.borrow(),
+                   vec![Duration::from_secs(1), Duration::from_secs(2), Duration::from_secs(2)]);
+    }
+
+
+    #[test]
+    fn lock_discussion() {
+        let (gl, mock) = mock_gitlab();
+        let json_reply = TEST_MERGE_REQUEST_JSON.replace("\"merge_commit_sha\": null,",
+            "\"merge_commit_sha\": null, \"discussion_locked\": true,");
+        mock.push_response(Response::json(&json_reply));
+        mock.push_response(Response::json(TEST_MERGE_REQUEST_JSON));
+
+        let merge_request =
+            gl.merge_requests(TEST_PROJECT_ID).single(TEST_MR_ID).lock_discussion().unwrap();
+        assert_eq!(merge_request.discussion_locked, Some(true));
+        gl.merge_requests(TEST_PROJECT_ID).single(TEST_MR_ID).unlock_discussion().unwrap();
+
+        let requests = mock.requests();
+        assert_eq!(requests[0].method, hyper::method::Method::Put);
+        let expected_url = format!("https://gitlab.example.com/api/v3/projects/{}\
+                                    /merge_requests/{}?",
+                                   TEST_PROJECT_ID,
+                                   TEST_MR_ID);
+        assert!(requests[0].url.starts_with(&expected_url));
+        assert_eq!(requests[0].body, Some(String::from(r#"{"discussion_locked":true}"#)));
+        assert_eq!(requests[1].body, Some(String::from(r#"{"discussion_locked":false}"#)));
+    }
+
+
+    #[test]
+    fn set_reviewers() {
+        let (gl, mock) = mock_gitlab();
+        mock.push_response(Response::json(TEST_MERGE_REQUEST_JSON));
+        mock.push_response(Response::json(TEST_MERGE_REQUEST_JSON));
+
+        gl.merge_requests(TEST_PROJECT_ID).single(TEST_MR_ID).set_reviewers(vec![2, 3]).unwrap();
+        gl.merge_requests(TEST_PROJECT_ID).single(TEST_MR_ID).set_reviewers(vec![]).unwrap();
+
+        let requests = mock.requests();
+        assert_eq!(requests[0].method, hyper::method::Method::Put);
+        assert_eq!(requests[0].body, Some(String::from(r#"{"reviewer_ids":[2,3]}"#)));
+        assert_eq!(requests[1].body, Some(String::from(r#"{"reviewer_ids":[]}"#)));
+    }
+
+
+    #[test]
+    fn update_title_only() {
+        let (gl, mock) = mock_gitlab();
+        mock.push_response(Response::json(&format!("[{}]", TEST_MERGE_REQUEST_JSON)));
+        mock.push_response(Response::json(TEST_MERGE_REQUEST_JSON));
+
+        let update = MergeRequestUpdate {
+            title: Some(String::from("WIP: test1")),
+            ..Default::default()
+        };
+        gl.update_merge_request(TEST_PROJECT_ID, 1, update).unwrap();
+
+        let requests = mock.requests();
+        assert_eq!(requests.len(), 2);
+        // The merge request's `id` is looked up from its `iid`.
+        assert!(requests[0]
+            .url
+            .starts_with(&format!("https://gitlab.example.com/api/v3/projects/{}\
+                                   /merge_requests?iid=1&",
+                                  TEST_PROJECT_ID)));
+        assert_eq!(requests[1].method, hyper::method::Method::Put);
+        assert!(requests[1]
+            .url
+            .starts_with(&format!("https://gitlab.example.com/api/v3/projects/{}/merge_requests/1?",
+                                  TEST_PROJECT_ID)));
+        assert_eq!(requests[1].body, Some(String::from(r#"{"title":"WIP: test1"}"#)));
+    }
+
+
+    fn merge_request_json(state: &str) -> String {
+        TEST_MERGE_REQUEST_JSON.replace("\"state\": \"merged\"",
+                                        &format!("\"state\": \"{}\"", state))
+    }
+
+
+    #[test]
+    fn close_and_reopen() {
+        let (gl, mock) = mock_gitlab();
+        let opened = merge_request_json("opened");
+        let closed = merge_request_json("closed");
+        mock.push_response(Response::json(&format!("[{}]", opened)));
+        mock.push_response(Response::json(&closed));
+        mock.push_response(Response::json(&format!("[{}]", closed)));
+        mock.push_response(Response::json(&opened));
+
+        let merge_request = gl.close_merge_request(TEST_PROJECT_ID, 1).unwrap();
+        assert_eq!(merge_request.state, ::merge_requests::State::Closed);
+        let merge_request = gl.reopen_merge_request(TEST_PROJECT_ID, 1).unwrap();
+        assert_eq!(merge_request.state, ::merge_requests::State::Opened);
+
+        let requests = mock.requests();
+        assert_eq!(requests.len(), 4);
+        assert_eq!(requests[1].method, hyper::method::Method::Put);
+        assert_eq!(requests[1].body, Some(String::from(r#"{"state_event":"close"}"#)));
+        assert_eq!(requests[3].method, hyper::method::Method::Put);
+        assert_eq!(requests[3].body, Some(String::from(r#"{"state_event":"reopen"}"#)));
+    }
+
+
+    #[test]
+    fn close_already_closed() {
+        let (gl, mock) = mock_gitlab();
+        let closed = merge_request_json("closed");
+        mock.push_response(Response::json(&format!("[{}]", closed)));
+
+        let merge_request = gl.close_merge_request(TEST_PROJECT_ID, 1).unwrap();
+        assert_eq!(merge_request.state, ::merge_requests::State::Closed);
+
+        // Nothing to do: no update sent.
+        let requests = mock.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].method, hyper::method::Method::Get);
+    }
 }
@@ -0,0 +1,221 @@
+//! Get, give and withdraw the approvals of a merge request
+//!
+//! https://docs.gitlab.com/ee/api/merge_request_approvals.html#merge-request-level-mr-approvals
+//!
+//! **Note**: Merge request approvals are a GitLab _Enterprise Edition_ feature. This module is
+//! only available with the `ee` cargo feature.
+//!
+//! # Merge request approvals
+//!
+//! Get the approval state of a merge request.
+//!
+//! ```text
+//! GET /projects/ID/merge_requests/MERGE_REQUEST_ID/approvals
+//! ```
+//!
+//! # Approve merge request
+//!
+//! Approve the merge request as the authenticated user.
+//!
+//! ```text
+//! POST /projects/ID/merge_requests/MERGE_REQUEST_ID/approve
+//! ```
+//!
+//! # Unapprove merge request
+//!
+//! Withdraw the approval of the authenticated user.
+//!
+//! ```text
+//! POST /projects/ID/merge_requests/MERGE_REQUEST_ID/unapprove
+//! ```
+//!
+//! Parameters (all three endpoints):
+//!
+//! | Attribute | Type | Required | Description |
+//! | --------- | ---- | -------- | ----------- |
+//! | `id` | integer | yes | The ID of a project |
+//! | `merge_request_id` | integer | yes | The ID of MR |
+
+
+use serde;
+
+use BuildQuery;
+
+use ::errors::*;
+
+
+/// The approval state of a merge request.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Approvals {
+    /// The number of approvals needed to merge
+    pub approvals_required: i64,
+    /// The number of approvals still missing
+    pub approvals_left: i64,
+    /// The users who approved the merge request
+    #[serde(default, deserialize_with = "deserialize_approved_by")]
+    pub approved_by: Vec<::User>,
+}
+
+
+/// An entry of `approved_by`, as sent by GitLab.
+#[derive(Deserialize)]
+struct ApprovedBy {
+    user: ::User,
+}
+
+
+/// GitLab wraps each approver in an object (`[{"user": {...}}]`): unwrap the users.
+fn deserialize_approved_by<D>(deserializer: D) -> ::std::result::Result<Vec<::User>, D::Error>
+    where D: serde::Deserializer
+{
+    let approved_by: Vec<ApprovedBy> = serde::Deserialize::deserialize(deserializer)?;
+
+    Ok(approved_by.into_iter().map(|approved_by| approved_by.user).collect())
+}
+
+
+#[derive(Debug, Clone)]
+pub struct ApprovalsLister<'a> {
+    gl: &'a ::GitLab,
+    id: i64,
+    mr_id: i64,
+}
+
+
+impl<'a> ApprovalsLister<'a> {
+    pub fn new(gl: &'a ::GitLab, id: i64, mr_id: i64) -> ApprovalsLister {
+        ApprovalsLister {
+            gl: gl,
+            id: id,
+            mr_id: mr_id,
+        }
+    }
+
+
+    /// Commit the lister: Query GitLab and return the approval state of the merge request.
+    pub fn list(&self) -> Result<Approvals> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl.get(&query, None, None).chain_err(|| format!("cannot get query {}", query))
+    }
+
+    /// Approve the merge request. Returns the new approval state.
+    pub fn approve(&self) -> Result<Approvals> {
+        let query = self.build_approve_query();
+        debug!("query: {:?}", query);
+
+        self.gl.post(&query, ()).chain_err(|| format!("cannot post query {}", query))
+    }
+
+    /// Withdraw the approval of the merge request. Returns the new approval state.
+    pub fn unapprove(&self) -> Result<Approvals> {
+        let query = self.build_unapprove_query();
+        debug!("query: {:?}", query);
+
+        self.gl.post(&query, ()).chain_err(|| format!("cannot post query {}", query))
+    }
+
+    fn build_approve_query(&self) -> String {
+        format!("projects/{}/merge_requests/{}/approve", self.id, self.mr_id)
+    }
+
+    fn build_unapprove_query(&self) -> String {
+        format!("projects/{}/merge_requests/{}/unapprove", self.id, self.mr_id)
+    }
+}
+
+
+impl<'a> BuildQuery for ApprovalsLister<'a> {
+    fn build_query(&self) -> String {
+        format!("projects/{}/merge_requests/{}/approvals", self.id, self.mr_id)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use serde_json;
+
+    use BuildQuery;
+    use merge_requests::single_approvals::Approvals;
+
+    const TEST_PROJECT_ID: i64 = 123;
+    const TEST_MR_ID: i64 = 456;
+
+
+    #[test]
+    fn build_query_default() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+
+        let expected_string = format!("projects/{}/merge_requests/{}/approvals",
+                                      TEST_PROJECT_ID,
+                                      TEST_MR_ID);
+        let query = gl.merge_requests(TEST_PROJECT_ID).single(TEST_MR_ID).approvals().build_query();
+        assert_eq!(query, expected_string);
+    }
+
+
+    #[test]
+    fn build_approve_query() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+
+        let expected_string = format!("projects/{}/merge_requests/{}/approve",
+                                      TEST_PROJECT_ID,
+                                      TEST_MR_ID);
+        let query = gl.merge_requests(TEST_PROJECT_ID)
+            .single(TEST_MR_ID)
+            .approvals()
+            .build_approve_query();
+        assert_eq!(query, expected_string);
+    }
+
+
+    #[test]
+    fn build_unapprove_query() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+
+        let expected_string = format!("projects/{}/merge_requests/{}/unapprove",
+                                      TEST_PROJECT_ID,
+                                      TEST_MR_ID);
+        let query = gl.merge_requests(TEST_PROJECT_ID)
+            .single(TEST_MR_ID)
+            .approvals()
+            .build_unapprove_query();
+        assert_eq!(query, expected_string);
+    }
+
+
+    #[test]
+    fn deserialize_approvals() {
+        let json_reply = r#"{
+            "id": 5,
+            "iid": 5,
+            "project_id": 1,
+            "title": "Approvals API",
+            "state": "opened",
+            "merge_status": "can_be_merged",
+            "approvals_required": 2,
+            "approvals_left": 1,
+            "approved_by": [
+                {
+                    "user": {
+                        "name": "Administrator",
+                        "username": "root",
+                        "id": 1,
+                        "state": "active",
+                        "avatar_url": "http://www.gravatar.com/avatar/e64c7d89f2?s=80&d=identicon",
+                        "web_url": "http://localhost:3000/root"
+                    }
+                }
+            ]
+        }"#;
+
+        let approvals: Approvals = serde_json::from_str(json_reply)
+            .expect("JSON deserialization failed");
+        assert_eq!(approvals.approvals_required, 2);
+        assert_eq!(approvals.approvals_left, 1);
+        assert_eq!(approvals.approved_by.len(), 1);
+        assert_eq!(approvals.approved_by[0].username, "root");
+    }
+}
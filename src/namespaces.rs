@@ -0,0 +1,162 @@
+//! List namespaces
+//!
+//! https://docs.gitlab.com/ce/api/namespaces.html
+//!
+//! # List namespaces
+//!
+//! Get a list of the namespaces of the authenticated user. If the user is an administrator, a
+//! list of all namespaces in the GitLab instance is shown.
+//!
+//! ```text
+//! GET /namespaces
+//! GET /namespaces?search=foobar
+//! ```
+//!
+//! Parameters:
+//!
+//! | Attribute | Type | Required | Description |
+//! | --------- | ---- | -------- | ----------- |
+//! | `search` | string | no | Returns a list of namespaces the user is authorized to see based on the search criteria |
+//!
+//! The search matches the namespaces' names and paths, not their full paths (`group/subgroup`).
+
+
+use serde_urlencoded;
+
+use BuildQuery;
+use Lister;
+use PageInfo;
+
+use ::errors::*;
+
+
+/// A user's or group's namespace, in which projects are created.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Namespace {
+    pub id: i64,
+    pub name: String,
+    pub path: String,
+    /// `user` or `group`
+    pub kind: String,
+    /// The path including the parent groups, for example `group/subgroup` (API v4)
+    pub full_path: Option<String>,
+}
+
+pub type Namespaces = Vec<Namespace>;
+
+
+impl Namespace {
+    /// Whether the namespace's full path (or its path if GitLab doesn't return the full path)
+    /// is `path`.
+    pub fn has_path(&self, path: &str) -> bool {
+        self.full_path.as_ref().unwrap_or(&self.path) == path
+    }
+}
+
+
+#[derive(Debug, Clone, Serialize)]
+struct NamespacesListerInternal {
+    search: Option<String>,
+}
+
+
+#[derive(Debug, Clone)]
+pub struct NamespacesLister<'a> {
+    gl: &'a ::GitLab,
+    internal: NamespacesListerInternal,
+}
+
+
+impl<'a> NamespacesLister<'a> {
+    pub fn new(gl: &'a ::GitLab) -> NamespacesLister {
+        NamespacesLister {
+            gl: gl,
+            internal: NamespacesListerInternal { search: None },
+        }
+    }
+
+    pub fn search(&'a mut self, search: String) -> &'a mut NamespacesLister {
+        self.internal.search = Some(search);
+        self
+    }
+}
+
+
+impl<'a> Lister<Namespaces> for NamespacesLister<'a> {
+    /// Commit the lister: Query GitLab and return a list of namespaces.
+    fn list(&self) -> Result<Namespaces> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl.get_listing(&query).chain_err(|| format!("cannot get query {}", query))
+    }
+
+    fn list_paginated(&self, page: u16, per_page: u16) -> Result<Namespaces> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl.get(&query, page, per_page).chain_err(|| format!("cannot get query {}", query))
+    }
+
+    /// Commit the lister: Query GitLab and return a page of namespaces with its pagination
+    /// information.
+    fn list_page(&self, page: u16, per_page: u16) -> Result<(Namespaces, PageInfo)> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl
+            .get_with_headers(&query, page, per_page)
+            .chain_err(|| format!("cannot get query {}", query))
+    }
+}
+
+
+impl<'a> BuildQuery for NamespacesLister<'a> {
+    fn build_query(&self) -> String {
+        let encoded = serde_urlencoded::to_string(&self.internal).unwrap();
+        let mut query = String::from("namespaces");
+        if !encoded.is_empty() {
+            query.push_str("?");
+            query.push_str(&encoded);
+        }
+
+        query
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use serde_json;
+
+    use BuildQuery;
+    use namespaces::Namespaces;
+
+
+    #[test]
+    fn build_query_search() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+        // let gl: ::GitLab = Default::default();
+
+        assert_eq!(gl.namespaces().build_query(), "namespaces");
+
+        let query = gl.namespaces().search(String::from("sub group")).build_query();
+        assert_eq!(query, "namespaces?search=sub+group");
+    }
+
+
+    #[test]
+    fn deserialize_namespaces() {
+        let json_reply = r#"[
+            {"id": 1, "name": "user1", "path": "user1", "kind": "user"},
+            {"id": 4, "name": "subgroup", "path": "subgroup", "kind": "group",
+             "full_path": "group/subgroup"}
+        ]"#;
+
+        let namespaces: Namespaces = serde_json::from_str(json_reply)
+            .expect("JSON deserialization failed");
+        assert!(namespaces[0].has_path("user1"));
+        assert!(namespaces[1].has_path("group/subgroup"));
+        assert!(!namespaces[1].has_path("subgroup"));
+    }
+}
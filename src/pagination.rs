@@ -0,0 +1,742 @@
+//! Pagination
+//!
+//! https://docs.gitlab.com/ce/api/#pagination
+//!
+//! # Pagination
+//!
+//! Sometimes the returned result will span across many pages. When listing resources, the
+//! `page` and `per_page` parameters can be passed.
+//!
+//! | Parameter | Description |
+//! | --------- | ----------- |
+//! | `page`    | Page number (default: `1`) |
+//! | `per_page`| Number of items to list per page (default: `20`, max: `100`) |
+//!
+//! # Pagination headers
+//!
+//! Additional pagination headers are also sent back.
+//!
+//! | Header | Description |
+//! | ------ | ----------- |
+//! | `X-Total`       | The total number of items |
+//! | `X-Total-Pages` | The total number of pages |
+//! | `X-Per-Page`    | The number of items per page |
+//! | `X-Page`        | The index of the current page (starting at 1) |
+//! | `X-Next-Page`   | The index of the next page |
+//! | `X-Prev-Page`   | The index of the previous page |
+//!
+//! # Link header
+//!
+//! GitLab also sends a `Link` header with the URLs of the `prev`, `next`, `first` and `last`
+//! pages:
+//!
+//! ```text
+//! Link: <https://gitlab.example.com/api/v3/projects?page=2&per_page=20>; rel="next",
+//!       <https://gitlab.example.com/api/v3/projects?page=1&per_page=20>; rel="first",
+//!       <https://gitlab.example.com/api/v3/projects?page=3&per_page=20>; rel="last"
+//! ```
+//!
+//! It is used when the `X-*` headers are missing, for example for large listings whose total
+//! GitLab does not compute.
+//!
+
+
+use std::collections::HashSet;
+use std::str::FromStr;
+
+use hyper;
+use url;
+
+use Lister;
+
+use ::errors::*;
+
+
+/// Number of items per page GitLab uses when `per_page` is not given.
+pub const DEFAULT_PER_PAGE: u16 = 20;
+
+/// Maximum number of items per page returned by GitLab.
+pub const MAX_PER_PAGE: u16 = 100;
+
+
+/// Pagination information, as returned by GitLab in the response's headers.
+///
+/// All fields are optional since GitLab might not send all (or any) of the headers.
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+pub struct PageInfo {
+    /// The index of the current page (starting at 1)
+    pub page: Option<u16>,
+    /// The number of items per page
+    pub per_page: Option<u16>,
+    /// The index of the next page, `None` for the last page
+    pub next_page: Option<u16>,
+    /// The index of the previous page, `None` for the first page
+    pub prev_page: Option<u16>,
+    /// The total number of items
+    pub total: Option<u64>,
+    /// The total number of pages
+    pub total_pages: Option<u16>,
+}
+
+
+impl PageInfo {
+    /// Extract the pagination information from GitLab's response headers.
+    ///
+    /// The `Link` header is used for the headers GitLab did not send. Missing or malformed
+    /// headers are ignored.
+    pub fn from_headers(headers: &hyper::header::Headers) -> PageInfo {
+        PageInfo {
+            page: parse_header(headers, "X-Page"),
+            per_page: parse_header(headers, "X-Per-Page"),
+            next_page: parse_header(headers, "X-Next-Page").or_else(|| link_page(headers, "next")),
+            prev_page: parse_header(headers, "X-Prev-Page").or_else(|| link_page(headers, "prev")),
+            total: parse_header(headers, "X-Total"),
+            total_pages: parse_header(headers, "X-Total-Pages")
+                .or_else(|| link_page(headers, "last")),
+        }
+    }
+}
+
+
+/// Parse the (first) value of a header, returning `None` if absent, empty or malformed.
+pub fn parse_header<T>(headers: &hyper::header::Headers, name: &str) -> Option<T>
+    where T: FromStr
+{
+    headers.get_raw(name)
+        .and_then(|values| values.first())
+        .and_then(|value| ::std::str::from_utf8(value).ok())
+        .and_then(|value| value.trim().parse().ok())
+}
+
+
+/// The `page` parameter of the link of relation `rel` (`next`, `last`...) in the `Link` header.
+fn link_page(headers: &hyper::header::Headers, rel: &str) -> Option<u16> {
+    let relation = format!("rel=\"{}\"", rel);
+
+    headers.get_raw("Link")
+        .into_iter()
+        .flat_map(|values| values.iter())
+        .filter_map(|value| ::std::str::from_utf8(value).ok())
+        .flat_map(|value| value.split(','))
+        .find(|link| link.split(';').skip(1).any(|param| param.trim() == relation))
+        .and_then(|link| link.split(';').next())
+        .map(|target| target.trim().trim_left_matches('<').trim_right_matches('>'))
+        .and_then(|target| url::Url::parse(target).ok())
+        .and_then(|target| {
+            target.query_pairs()
+                .find(|&(ref name, _)| name == "page")
+                .and_then(|(_, page)| page.parse().ok())
+        })
+}
+
+
+/// An opaque position in a paginated listing.
+///
+/// A `PageCursor` can be serialized (for example to checkpoint a long running export) and later
+/// passed to `Lister::list_from()` to resume the listing where it stopped, without fetching the
+/// earlier pages again.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PageCursor {
+    page: u16,
+    per_page: u16,
+}
+
+
+impl PageCursor {
+    /// A cursor pointing to the first page, listing `per_page` items per page.
+    pub fn new(per_page: u16) -> PageCursor {
+        PageCursor {
+            page: 1,
+            per_page: per_page,
+        }
+    }
+
+    /// The index of the page pointed to (starting at 1)
+    pub fn page(&self) -> u16 {
+        self.page
+    }
+
+    /// The number of items per page
+    pub fn per_page(&self) -> u16 {
+        self.per_page
+    }
+
+    /// The cursor pointing to the page following the one described by `page_info`, if any.
+    pub fn next(&self, page_info: &PageInfo) -> Option<PageCursor> {
+        page_info.next_page.map(|next_page| {
+            PageCursor {
+                page: next_page,
+                per_page: self.per_page,
+            }
+        })
+    }
+}
+
+
+/// GitLab items identified by a unique `id`.
+pub trait Identifiable {
+    fn id(&self) -> i64;
+}
+
+
+/// An iterator over the items of all the pages of a lister, see `Lister::paginated_iter()`.
+///
+/// A page is only fetched once all the items of the previous one were returned. The iteration
+/// stops after the last page, as told by GitLab's pagination headers: when the last page is
+/// exactly full, no extra (empty) page is requested. Only if GitLab sends no pagination headers
+/// is a page with fewer than `per_page` items considered the last one.
+///
+/// An error fetching a page is returned as an item, after which the iteration stops.
+///
+/// ```no_run
+/// use gitlab_api::GitLab;
+/// use gitlab_api::Lister;
+///
+/// let gl = GitLab::new("gitlab.example.com", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+///
+/// for issue in gl.issues().project(9).iter() {
+///     println!("{}", issue.unwrap().title);
+/// }
+/// ```
+pub struct PaginatedIter<'a, T, L: 'a> {
+    lister: &'a L,
+    per_page: u16,
+    /// The page to fetch once `items` is exhausted, `None` after the last page
+    next_page: Option<u16>,
+    items: ::std::vec::IntoIter<T>,
+}
+
+
+impl<'a, T, L> PaginatedIter<'a, T, L>
+    where L: Lister<Vec<T>>
+{
+    pub fn new(lister: &'a L, per_page: u16) -> PaginatedIter<'a, T, L> {
+        PaginatedIter {
+            lister: lister,
+            per_page: per_page,
+            next_page: Some(1),
+            items: Vec::new().into_iter(),
+        }
+    }
+}
+
+
+impl<'a, T, L> Iterator for PaginatedIter<'a, T, L>
+    where L: Lister<Vec<T>>
+{
+    type Item = Result<T>;
+
+    fn next(&mut self) -> Option<Result<T>> {
+        loop {
+            if let Some(item) = self.items.next() {
+                return Some(Ok(item));
+            }
+
+            let page = match self.next_page {
+                Some(page) => page,
+                None => return None,
+            };
+
+            match self.lister.list_page(page, self.per_page) {
+                Ok((mut items, page_info)) => {
+                    self.next_page = if items.is_empty() {
+                        None
+                    } else {
+                        next_page(page, self.per_page, items.len(), &page_info)
+                    };
+                    self.lister.retain(&mut items);
+                    self.items = items.into_iter();
+                }
+                Err(e) => {
+                    self.next_page = None;
+                    return Some(Err(e).chain_err(|| format!("cannot get page {}", page)));
+                }
+            }
+        }
+    }
+}
+
+
+/// An iterator transforming and filtering the items of all the pages of a lister, see
+/// `Lister::stream_filter()`.
+///
+/// Items for which the closure returns `None` are skipped. Pages are fetched lazily, like with
+/// `PaginatedIter`; errors are returned as is, without calling the closure.
+///
+/// ```no_run
+/// use gitlab_api::GitLab;
+/// use gitlab_api::Lister;
+///
+/// let gl = GitLab::new("gitlab.example.com", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+///
+/// let titles = gl.merge_requests(9).stream_filter(|mr| Some(mr.title));
+/// for title in titles {
+///     println!("{}", title.unwrap());
+/// }
+/// ```
+pub struct StreamFilter<'a, T, L: 'a, F> {
+    pages: PaginatedIter<'a, T, L>,
+    f: F,
+}
+
+
+impl<'a, T, L, F> StreamFilter<'a, T, L, F>
+    where L: Lister<Vec<T>>
+{
+    pub fn new(pages: PaginatedIter<'a, T, L>, f: F) -> StreamFilter<'a, T, L, F> {
+        StreamFilter { pages: pages, f: f }
+    }
+}
+
+
+impl<'a, T, L, F, R> Iterator for StreamFilter<'a, T, L, F>
+    where L: Lister<Vec<T>>,
+          F: FnMut(T) -> Option<R>
+{
+    type Item = Result<R>;
+
+    fn next(&mut self) -> Option<Result<R>> {
+        loop {
+            match self.pages.next() {
+                Some(Ok(item)) => {
+                    if let Some(mapped) = (self.f)(item) {
+                        return Some(Ok(mapped));
+                    }
+                }
+                Some(Err(e)) => return Some(Err(e)),
+                None => return None,
+            }
+        }
+    }
+}
+
+
+/// The items of a listing which might have been stopped before its end.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Partial<T> {
+    pub items: Vec<T>,
+    /// `false` if the deadline passed before all the pages were fetched
+    pub complete: bool,
+}
+
+
+/// Fetch all the pages of `lister`, `per_page` items at a time, and return all their items.
+pub fn list_all<T, L>(lister: &L, per_page: u16) -> Result<Vec<T>>
+    where L: Lister<Vec<T>>
+{
+    fetch_all(lister, per_page, false, |_| true).map(|partial| partial.items)
+}
+
+
+/// Same as `list_all()` but stop fetching pages once the deadline of the requests (see
+/// `RequestOptions::deadline()`) has passed, returning the items collected so far.
+///
+/// The returned `Partial` tells whether all the pages were fetched. Other errors are still
+/// returned as errors.
+pub fn list_all_partial<T, L>(lister: &L, per_page: u16) -> Result<Partial<T>>
+    where L: Lister<Vec<T>>
+{
+    fetch_all(lister, per_page, true, |_| true)
+}
+
+
+/// Same as `list_all()` but drop the items already returned by a previous page.
+///
+/// With offset pagination, items created or deleted while the pages are fetched shift the
+/// following pages: an item can then be returned twice. The ids of all the returned items are
+/// kept in memory to drop those duplicates.
+pub fn list_all_dedup<T, L>(lister: &L, per_page: u16) -> Result<Vec<T>>
+    where L: Lister<Vec<T>>,
+          T: Identifiable
+{
+    let mut seen = HashSet::new();
+
+    fetch_all(lister, per_page, false, |item: &T| seen.insert(item.id()))
+        .map(|partial| partial.items)
+}
+
+
+/// Fetch all the pages of `lister`, keeping the items for which `keep` returns `true`.
+///
+/// If `partial` is `true`, an exceeded deadline stops the listing instead of failing it.
+fn fetch_all<T, L, F>(lister: &L, per_page: u16, partial: bool, mut keep: F) -> Result<Partial<T>>
+    where L: Lister<Vec<T>>,
+          F: FnMut(&T) -> bool
+{
+    let mut all_items = Vec::new();
+    let mut page = 1;
+
+    loop {
+        let (mut items, page_info) = match lister.list_page(page, per_page) {
+            Ok(page) => page,
+            Err(ref e) if partial && is_deadline_exceeded(e) => {
+                info!("deadline exceeded, stopping the listing before page {}", page);
+                return Ok(Partial {
+                    items: all_items,
+                    complete: false,
+                });
+            }
+            Err(e) => return Err(e).chain_err(|| format!("cannot get page {}", page)),
+        };
+        let nb_items = items.len();
+        lister.retain(&mut items);
+
+        all_items.extend(items.into_iter().filter(|item| keep(item)));
+
+        match next_page(page, per_page, nb_items, &page_info) {
+            Some(next_page) => page = next_page,
+            None => break,
+        }
+    }
+
+    Ok(Partial {
+        items: all_items,
+        complete: true,
+    })
+}
+
+
+/// The page following `page`, which contained `nb_items` items, if any.
+///
+/// The pagination headers (`page_info`) are used if GitLab sent them. Otherwise, a page with
+/// fewer than `per_page` items is the last one.
+pub fn next_page(page: u16, per_page: u16, nb_items: usize, page_info: &PageInfo) -> Option<u16> {
+    if *page_info == PageInfo::default() {
+        // Without pagination headers, a partial page is the last one.
+        if nb_items < per_page as usize { None } else { Some(page + 1) }
+    } else if page_info.next_page.is_none() && page_info.total_pages.is_some() {
+        // Without `X-Next-Page`, the number of pages tells whether this was the last one.
+        page_info.total_pages.and_then(|total_pages| {
+            if page < total_pages { Some(page + 1) } else { None }
+        })
+    } else {
+        page_info.next_page
+    }
+}
+
+
+/// Whether `error`, or one of its causes, is an exceeded deadline.
+fn is_deadline_exceeded(error: &Error) -> bool {
+    match *error.typed_kind() {
+        ErrorKind::DeadlineExceeded => true,
+        _ => false,
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use hyper;
+    use serde_json;
+
+    use errors::*;
+    use Lister;
+    use pagination::*;
+
+
+    fn headers(values: &[(&'static str, &str)]) -> hyper::header::Headers {
+        let mut headers = hyper::header::Headers::new();
+        for &(name, value) in values {
+            headers.set_raw(name, vec![value.as_bytes().to_vec()]);
+        }
+        headers
+    }
+
+
+    /// A fake lister, returning pre-defined pages and recording which pages were requested.
+    struct PagesLister {
+        pages: Vec<Vec<i64>>,
+        /// Whether to return the pagination information, like GitLab's headers
+        headers: bool,
+        requested: RefCell<Vec<u16>>,
+    }
+
+    impl PagesLister {
+        fn new() -> PagesLister {
+            PagesLister::with_pages(vec![vec![1, 2], vec![3, 4], vec![5]])
+        }
+
+        fn with_pages(pages: Vec<Vec<i64>>) -> PagesLister {
+            PagesLister {
+                pages: pages,
+                headers: true,
+                requested: RefCell::new(Vec::new()),
+            }
+        }
+
+        fn without_headers(pages: Vec<Vec<i64>>) -> PagesLister {
+            PagesLister { headers: false, ..PagesLister::with_pages(pages) }
+        }
+    }
+
+    impl Identifiable for i64 {
+        fn id(&self) -> i64 {
+            *self
+        }
+    }
+
+    impl Lister<Vec<i64>> for PagesLister {
+        fn list(&self) -> Result<Vec<i64>> {
+            self.list_paginated(1, 2)
+        }
+
+        fn list_paginated(&self, page: u16, per_page: u16) -> Result<Vec<i64>> {
+            self.list_page(page, per_page).map(|(items, _)| items)
+        }
+
+        fn list_page(&self, page: u16, per_page: u16) -> Result<(Vec<i64>, PageInfo)> {
+            self.requested.borrow_mut().push(page);
+
+            let nb_pages = self.pages.len() as u16;
+            if page > nb_pages {
+                return Ok((Vec::new(), Default::default()));
+            }
+            if !self.headers {
+                return Ok((self.pages[(page - 1) as usize].clone(), Default::default()));
+            }
+
+            let page_info = PageInfo {
+                page: Some(page),
+                per_page: Some(per_page),
+                next_page: if page < nb_pages { Some(page + 1) } else { None },
+                prev_page: if page > 1 { Some(page - 1) } else { None },
+                total: Some(5),
+                total_pages: Some(nb_pages),
+            };
+
+            Ok((self.pages[(page - 1) as usize].clone(), page_info))
+        }
+    }
+
+
+    #[test]
+    fn page_info_from_headers() {
+        let headers = headers(&[("X-Page", "2"),
+                                ("X-Per-Page", "20"),
+                                ("X-Next-Page", "3"),
+                                ("X-Prev-Page", "1"),
+                                ("X-Total", "45"),
+                                ("X-Total-Pages", "3")]);
+        let expected = PageInfo {
+            page: Some(2),
+            per_page: Some(20),
+            next_page: Some(3),
+            prev_page: Some(1),
+            total: Some(45),
+            total_pages: Some(3),
+        };
+        assert_eq!(PageInfo::from_headers(&headers), expected);
+    }
+
+
+    #[test]
+    fn page_info_from_headers_last_page() {
+        // GitLab sends empty `X-Next-Page` on the last page.
+        let headers = headers(&[("X-Page", "3"), ("X-Next-Page", ""), ("X-Total", "garbage")]);
+        let page_info = PageInfo::from_headers(&headers);
+        assert_eq!(page_info.page, Some(3));
+        assert_eq!(page_info.next_page, None);
+        assert_eq!(page_info.total, None);
+    }
+
+
+    #[test]
+    fn page_info_from_link_header() {
+        let link = "<https://localhost/api/v3/projects?page=3&per_page=20>; rel=\"next\", \
+                    <https://localhost/api/v3/projects?page=1&per_page=20>; rel=\"prev\", \
+                    <https://localhost/api/v3/projects?per_page=20&page=5>; rel=\"last\"";
+        let page_info = PageInfo::from_headers(&headers(&[("X-Page", "2"), ("Link", link)]));
+        assert_eq!(page_info.page, Some(2));
+        assert_eq!(page_info.next_page, Some(3));
+        assert_eq!(page_info.prev_page, Some(1));
+        assert_eq!(page_info.total_pages, Some(5));
+
+        // The `X-*` headers take precedence.
+        let page_info = PageInfo::from_headers(&headers(&[("X-Next-Page", "4"), ("Link", link)]));
+        assert_eq!(page_info.next_page, Some(4));
+
+        // On the last page, there is no `next` link.
+        let link = "<https://localhost/api/v3/projects?page=1&per_page=20>; rel=\"first\", \
+                    <https://localhost/api/v3/projects?page=2&per_page=20>; rel=\"last\"";
+        let page_info = PageInfo::from_headers(&headers(&[("Link", link)]));
+        assert_eq!(page_info.next_page, None);
+        assert!(page_info != PageInfo::default());
+    }
+
+
+    #[test]
+    fn page_info_from_headers_missing() {
+        let headers = headers(&[]);
+        assert_eq!(PageInfo::from_headers(&headers), PageInfo::default());
+    }
+
+
+    #[test]
+    fn resume_from_cursor() {
+        // First run: fetch the first page only, then checkpoint the cursor.
+        let lister = PagesLister::new();
+        let (items, cursor) = lister.list_from(PageCursor::new(2)).unwrap();
+        assert_eq!(items, vec![1, 2]);
+        let checkpoint = serde_json::to_string(&cursor.unwrap()).unwrap();
+
+        // Second run: resume from the checkpoint.
+        let lister = PagesLister::new();
+        let mut cursor: Option<PageCursor> = Some(serde_json::from_str(&checkpoint).unwrap());
+        let mut items = Vec::new();
+        while let Some(current) = cursor {
+            let (page_items, next) = lister.list_from(current).unwrap();
+            items.extend(page_items);
+            cursor = next;
+        }
+
+        assert_eq!(items, vec![3, 4, 5]);
+        // Earlier pages were not fetched again.
+        assert_eq!(*lister.requested.borrow(), vec![2, 3]);
+    }
+
+
+    #[test]
+    fn jump_to_page() {
+        let lister = PagesLister::new();
+        let (items, page_info) = lister.page(3).unwrap();
+
+        assert_eq!(items, vec![5]);
+        assert_eq!(page_info.page, Some(3));
+        assert_eq!(page_info.per_page, Some(DEFAULT_PER_PAGE));
+        assert_eq!(page_info.total_pages, Some(3));
+        assert_eq!(page_info.next_page, None);
+        assert_eq!(*lister.requested.borrow(), vec![3]);
+    }
+
+
+    /// A lister whose pages tell the number of pages, but not which one is next.
+    struct TotalPagesLister(PagesLister);
+
+    impl Lister<Vec<i64>> for TotalPagesLister {
+        fn list(&self) -> Result<Vec<i64>> {
+            self.0.list()
+        }
+
+        fn list_paginated(&self, page: u16, per_page: u16) -> Result<Vec<i64>> {
+            self.0.list_paginated(page, per_page)
+        }
+
+        fn list_page(&self, page: u16, per_page: u16) -> Result<(Vec<i64>, PageInfo)> {
+            self.0
+                .list_page(page, per_page)
+                .map(|(items, page_info)| (items, PageInfo { next_page: None, ..page_info }))
+        }
+    }
+
+
+    #[test]
+    fn next_page_from_total_pages() {
+        let page_info = PageInfo::from_headers(&headers(&[("X-Next-Page", "3"),
+                                                          ("X-Total-Pages", "5")]));
+        assert_eq!(next_page(2, 2, 2, &page_info), Some(3));
+
+        // GitLab sends an empty `X-Next-Page` on the last page.
+        let page_info = PageInfo::from_headers(&headers(&[("X-Next-Page", ""),
+                                                          ("X-Total-Pages", "3")]));
+        assert_eq!(next_page(3, 2, 2, &page_info), None);
+
+        // Without `X-Next-Page`, `X-Total-Pages` tells whether there is a next page.
+        let page_info = PageInfo::from_headers(&headers(&[("X-Total-Pages", "3")]));
+        assert_eq!(next_page(2, 2, 2, &page_info), Some(3));
+        assert_eq!(next_page(3, 2, 2, &page_info), None);
+
+        // The iteration stops after the last page, although it is full.
+        let lister = TotalPagesLister(PagesLister::with_pages(vec![vec![1, 2], vec![3, 4]]));
+        let items: Result<Vec<i64>> = lister.paginated_iter(2).collect();
+        assert_eq!(items.unwrap(), vec![1, 2, 3, 4]);
+        assert_eq!(*lister.0.requested.borrow(), vec![1, 2]);
+
+        let lister = TotalPagesLister(PagesLister::new());
+        assert_eq!(list_all(&lister, 2).unwrap(), vec![1, 2, 3, 4, 5]);
+        assert_eq!(*lister.0.requested.borrow(), vec![1, 2, 3]);
+    }
+
+
+    #[test]
+    fn list_all_pages() {
+        let lister = PagesLister::new();
+        assert_eq!(list_all(&lister, 2).unwrap(), vec![1, 2, 3, 4, 5]);
+        assert_eq!(*lister.requested.borrow(), vec![1, 2, 3]);
+    }
+
+
+    #[test]
+    fn paginated_iter() {
+        let lister = PagesLister::new();
+        let mut iter = lister.paginated_iter(2);
+        assert_eq!(iter.next().unwrap().unwrap(), 1);
+        // Pages are fetched lazily.
+        assert_eq!(*lister.requested.borrow(), vec![1]);
+
+        let items: Vec<i64> = iter.map(|item| item.unwrap()).collect();
+        assert_eq!(items, vec![2, 3, 4, 5]);
+        assert_eq!(*lister.requested.borrow(), vec![1, 2, 3]);
+    }
+
+
+    #[test]
+    fn paginated_iter_full_last_page() {
+        // The pagination headers tell page 2 is the last one: page 3 is not requested.
+        let lister = PagesLister::with_pages(vec![vec![1, 2], vec![3, 4]]);
+        let items: Result<Vec<i64>> = lister.paginated_iter(2).collect();
+        assert_eq!(items.unwrap(), vec![1, 2, 3, 4]);
+        assert_eq!(*lister.requested.borrow(), vec![1, 2]);
+
+        // Without headers, only an empty page tells the end was reached.
+        let lister = PagesLister::without_headers(vec![vec![1, 2], vec![3, 4]]);
+        let items: Result<Vec<i64>> = lister.paginated_iter(2).collect();
+        assert_eq!(items.unwrap(), vec![1, 2, 3, 4]);
+        assert_eq!(*lister.requested.borrow(), vec![1, 2, 3]);
+
+        let lister = PagesLister::without_headers(vec![vec![1, 2], vec![3]]);
+        let items: Result<Vec<i64>> = lister.paginated_iter(2).collect();
+        assert_eq!(items.unwrap(), vec![1, 2, 3]);
+        assert_eq!(*lister.requested.borrow(), vec![1, 2]);
+    }
+
+
+    /// A lister relying on the default `list_page()`.
+    struct PaginatedLister(PagesLister);
+
+    impl Lister<Vec<i64>> for PaginatedLister {
+        fn list(&self) -> Result<Vec<i64>> {
+            self.0.list()
+        }
+
+        fn list_paginated(&self, page: u16, per_page: u16) -> Result<Vec<i64>> {
+            self.0.list_paginated(page, per_page)
+        }
+    }
+
+
+    #[test]
+    fn default_list_page() {
+        let lister = PaginatedLister(PagesLister::new());
+        let (items, page_info) = lister.page(2).unwrap();
+        assert_eq!(items, vec![3, 4]);
+        assert_eq!(page_info, PageInfo::default());
+
+        // The partial page is the last one.
+        let items: Result<Vec<i64>> = lister.paginated_iter(2).collect();
+        assert_eq!(items.unwrap(), vec![1, 2, 3, 4, 5]);
+        assert_eq!(*lister.0.requested.borrow(), vec![2, 1, 2, 3]);
+    }
+
+
+    #[test]
+    fn list_all_dedup_overlapping_pages() {
+        // An item got created while listing: the last item of page 1 shifted to page 2.
+        let lister = PagesLister::with_pages(vec![vec![1, 2], vec![2, 3], vec![4]]);
+        assert_eq!(list_all(&lister, 2).unwrap(), vec![1, 2, 2, 3, 4]);
+
+        let lister = PagesLister::with_pages(vec![vec![1, 2], vec![2, 3], vec![4]]);
+        assert_eq!(list_all_dedup(&lister, 2).unwrap(), vec![1, 2, 3, 4]);
+    }
+}
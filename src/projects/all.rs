@@ -10,6 +10,9 @@
 //! GET /projects/all
 //! ```
 //!
+//! The API v4 removed this endpoint: `GET /projects` is queried instead (see
+//! `GitLab::api_version()`), which lists all the projects for an administrator.
+//!
 //! Parameters:
 //!
 //! | Attribute | Type | Required | Description |
@@ -68,6 +71,15 @@ impl<'a> ProjectsLister<'a> {
         self
     }
 
+    /// Return the projects with the most recent activity first.
+    ///
+    /// Shortcut for `order_by(ListingOrderBy::LastActivityAt)` and `sort(ListingSort::Desc)`.
+    pub fn most_recently_active(&'a mut self) -> &'a mut ProjectsLister {
+        self.internal.order_by = Some(::projects::ListingOrderBy::LastActivityAt);
+        self.internal.sort = Some(::ListingSort::Desc);
+        self
+    }
+
     pub fn search(&'a mut self, search: String) -> &'a mut ProjectsLister {
         self.internal.search = Some(search);
         self
@@ -79,7 +91,7 @@ impl<'a> ProjectsLister<'a> {
         let query = self.build_query();
         debug!("query: {:?}", query);
 
-        self.gl.get(&query, None, None).chain_err(|| format!("cannot get query {}", query))
+        self.gl.get_listing(&query).chain_err(|| format!("cannot get query {}", query))
     }
 }
 
@@ -88,7 +100,11 @@ impl<'a> BuildQuery for ProjectsLister<'a> {
     fn build_query(&self) -> String {
 
         let encoded = serde_urlencoded::to_string(&self.internal).unwrap();
-        let mut query = String::from("projects/all");
+        let mut query = String::from(if self.gl.api_version() >= 4 {
+            "projects"
+        } else {
+            "projects/all"
+        });
         if !encoded.is_empty() {
             query.push_str("?");
             query.push_str(&encoded);
@@ -204,6 +220,17 @@ mod tests {
     }
 
 
+    #[test]
+    fn build_query_most_recently_active() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+        // let gl: ::GitLab = Default::default();
+
+        let expected_string = "projects/all?order_by=last_activity_at&sort=desc";
+        let query = gl.projects().all().most_recently_active().build_query();
+        assert_eq!(query, expected_string);
+    }
+
+
     #[test]
     fn build_query_search() {
         let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
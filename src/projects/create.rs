@@ -0,0 +1,207 @@
+//! Create a project
+//!
+//! https://docs.gitlab.com/ce/api/projects.html#create-project
+//!
+//! # Create project
+//!
+//! Creates a new project owned by the authenticated user.
+//!
+//! ```text
+//! POST /projects
+//! ```
+//!
+//! Parameters:
+//!
+//! | Attribute | Type | Required | Description |
+//! | --------- | ---- | -------- | ----------- |
+//! | `name` | string | yes | The name of the new project |
+//! | `path` | string | no | Custom repository name for new project. By default generated based on name |
+//! | `namespace_id` | integer | no | Namespace for the new project (defaults to the current user's namespace) |
+//! | `description` | string | no | Short project description |
+//! | `visibility` | string | no | `public`, `internal`, or `private` |
+//! | `issues_enabled` | boolean | no | Enable issues for this project |
+//! | `merge_requests_enabled` | boolean | no | Enable merge requests for this project |
+//!
+//! # Create project for user
+//!
+//! Creates a new project owned by the specified user. Available only for admins.
+//!
+//! ```text
+//! POST /projects/user/USER_ID
+//! ```
+//!
+//! Parameters:
+//!
+//! | Attribute | Type | Required | Description |
+//! | --------- | ---- | -------- | ----------- |
+//! | `user_id` | integer | yes | The user ID of the project owner |
+//! | `name` | string | yes | The name of the new project |
+//! | `path` | string | no | Custom repository name for new project. By default generated based on name |
+//! | `namespace_id` | integer | no | Namespace for the new project (defaults to the user's namespace) |
+//! | `description` | string | no | Short project description |
+//! | `visibility` | string | no | `public`, `internal`, or `private` |
+//! | `issues_enabled` | boolean | no | Enable issues for this project |
+//! | `merge_requests_enabled` | boolean | no | Enable merge requests for this project |
+
+
+use BuildQuery;
+
+use ::errors::*;
+
+
+/// The settings of a project to create.
+///
+/// Only the settings given are sent, GitLab uses its defaults for the others.
+#[derive(Debug, Clone, Serialize)]
+pub struct ProjectCreator<'a> {
+    #[serde(skip_serializing)]
+    gl: &'a ::GitLab,
+    name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    namespace_id: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    visibility: Option<::ListingVisibility>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    issues_enabled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    merge_requests_enabled: Option<bool>,
+}
+
+
+impl<'a> ProjectCreator<'a> {
+    pub fn new(gl: &'a ::GitLab, name: String) -> ProjectCreator {
+        ProjectCreator {
+            gl: gl,
+            name: name,
+            path: None,
+            namespace_id: None,
+            description: None,
+            visibility: None,
+            issues_enabled: None,
+            merge_requests_enabled: None,
+        }
+    }
+
+    pub fn path(&'a mut self, path: String) -> &'a mut ProjectCreator {
+        self.path = Some(path);
+        self
+    }
+
+    pub fn namespace_id(&'a mut self, namespace_id: i64) -> &'a mut ProjectCreator {
+        self.namespace_id = Some(namespace_id);
+        self
+    }
+
+    pub fn description(&'a mut self, description: String) -> &'a mut ProjectCreator {
+        self.description = Some(description);
+        self
+    }
+
+    pub fn visibility(&'a mut self, visibility: ::ListingVisibility) -> &'a mut ProjectCreator {
+        self.visibility = Some(visibility);
+        self
+    }
+
+    pub fn issues_enabled(&'a mut self, issues_enabled: bool) -> &'a mut ProjectCreator {
+        self.issues_enabled = Some(issues_enabled);
+        self
+    }
+
+    pub fn merge_requests_enabled(&'a mut self,
+                                  merge_requests_enabled: bool)
+                                  -> &'a mut ProjectCreator {
+        self.merge_requests_enabled = Some(merge_requests_enabled);
+        self
+    }
+
+    /// Commit the creator: Create the project in GitLab and return it.
+    ///
+    /// Fails without contacting GitLab if the project's name is empty.
+    pub fn create(&self) -> Result<::projects::Project> {
+        if self.name.trim().is_empty() {
+            bail!("cannot create a project with an empty name");
+        }
+
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl
+            .post(&query, self)
+            .chain_err(|| format!("cannot create project '{}'", self.name))
+    }
+
+    /// The query creating the project for the user `user_id` (admin only).
+    pub fn build_user_query(&self, user_id: i64) -> String {
+        format!("{}/user/{}", self.build_query(), user_id)
+    }
+}
+
+
+impl<'a> BuildQuery for ProjectCreator<'a> {
+    fn build_query(&self) -> String {
+        String::from("projects")
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use serde_json;
+
+    use projects::create::ProjectCreator;
+    use tests::mock_gitlab;
+    use transport::Response;
+
+
+    #[test]
+    fn build_user_query() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+
+        let creator = ProjectCreator::new(&gl, String::from("onboarding"));
+        assert_eq!(creator.build_user_query(42), "projects/user/42");
+    }
+
+
+    #[test]
+    fn create() {
+        let (gl, mock) = mock_gitlab();
+        let mut response = Response::json(::tests::TEST_PROJECTS_JSON
+            .trim()
+            .trim_left_matches('[')
+            .trim_right_matches(']'));
+        response.status = ::hyper::status::StatusCode::Created;
+        mock.push_response(response);
+
+        let mut creator = gl.projects().create("onboarding");
+        let project = creator.description(String::from("Docs")).create().unwrap();
+        assert_eq!(project.id, 517564);
+
+        let error = gl.projects().create(" ").create().unwrap_err();
+        assert!(error.to_string().contains("empty name"));
+
+        let requests = mock.requests();
+        assert_eq!(requests.len(), 1);
+        assert!(requests[0].url.starts_with("https://gitlab.example.com/api/v3/projects?"));
+        assert_eq!(requests[0].body,
+                   Some(String::from(r#"{"name":"onboarding","description":"Docs"}"#)));
+    }
+
+
+    #[test]
+    fn serialize_project_creator() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+
+        let creator = ProjectCreator::new(&gl, String::from("onboarding"));
+        assert_eq!(serde_json::to_string(&creator).unwrap(),
+                   r#"{"name":"onboarding"}"#);
+
+        let mut creator = ProjectCreator::new(&gl, String::from("onboarding"));
+        let creator = creator.visibility(::ListingVisibility::Private).issues_enabled(false);
+        assert_eq!(serde_json::to_string(&*creator).unwrap(),
+                   r#"{"name":"onboarding","visibility":"private","issues_enabled":false}"#);
+    }
+}
@@ -18,9 +18,72 @@
 //! | Attribute | Type | Required | Description |
 //! | --------- | ---- | -------- | ----------- |
 //! | `id` | integer/string | yes | The ID or `NAMESPACE/PROJECT_NAME` of the project |
+//! | `simple` | boolean | no | Return only the ID, URL, name, and path of the project |
+//!
+//! # Edit project
+//!
+//! Updates an existing project.
+//!
+//! ```text
+//! PUT /projects/ID
+//! ```
+//!
+//! | Attribute | Type | Required | Description |
+//! | --------- | ---- | -------- | ----------- |
+//! | `id` | integer/string | yes | The ID or `NAMESPACE/PROJECT_NAME` of the project |
+//! | `name` | string | no | The name of the project |
+//! | `description` | string | no | Short project description |
+//! | `default_branch` | string | no | The default branch |
+//! | `lfs_enabled` | boolean | no | Enable LFS |
+//! | `request_access_enabled` | boolean | no | Allow users to request member access |
+//! | `shared_runners_enabled` | boolean | no | Enable shared runners for this project |
+//!
+//! # Languages
+//!
+//! Get the languages used in the project's repository, with their percentage.
+//!
+//! ```text
+//! GET /projects/ID/languages
+//! ```
+//!
+//! # Star / unstar a project
+//!
+//! ```text
+//! POST /projects/ID/star
+//! DELETE /projects/ID/star
+//! ```
+//!
+//! # Archive / unarchive a project
+//!
+//! Only available to project owners and admins.
+//!
+//! ```text
+//! POST /projects/ID/archive
+//! POST /projects/ID/unarchive
+//! ```
+//!
+//! # Start the housekeeping task for a project
+//!
+//! Runs `git gc` and other maintenance tasks on the project's repository. Only available to
+//! admins: GitLab answers `403 Forbidden` to other users.
+//!
+//! ```text
+//! POST /projects/ID/housekeeping
+//! ```
+//!
+//! # Remove project
+//!
+//! Removes a project including all associated resources (issues, merge requests etc.)
+//!
+//! ```text
+//! DELETE /projects/ID
+//! ```
 
 
+use serde_json;
+
 use BuildQuery;
+use projects::id_notification_settings::NotificationSettingsLister;
 
 use ::errors::*;
 
@@ -48,6 +111,112 @@ impl<'a> ProjectsLister<'a> {
     }
 
 
+    /// Query GitLab and return only the project's ID, URL, name and path.
+    ///
+    /// Lighter than `list()` when rendering a link to the project.
+    pub fn simple(&self) -> Result<::projects::SimpleProject> {
+        let query = self.build_simple_query();
+        debug!("query: {:?}", query);
+
+        self.gl.get(&query, None, None).chain_err(|| format!("cannot get query {}", query))
+    }
+
+    fn build_simple_query(&self) -> String {
+        format!("{}?simple=true", self.build_query())
+    }
+
+
+    /// Query GitLab and return the languages of the project's repository (percentage of the code
+    /// per language).
+    ///
+    /// An empty repository has no languages: GitLab might then answer `[]`, see `::tolerant`.
+    pub fn languages(&self) -> Result<::projects::Languages> {
+        let query = format!("{}/languages", self.build_query());
+        debug!("query: {:?}", query);
+
+        self.gl.get_or_default(&query).chain_err(|| format!("cannot get query {}", query))
+    }
+
+
+    /// Update the project: only the fields set in `update` are changed.
+    ///
+    /// Returns the updated project.
+    pub fn update(&self, update: &::projects::ProjectUpdate) -> Result<::projects::Project> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl.put(&query, update).chain_err(|| format!("cannot put query {}", query))
+    }
+
+    /// Star the project.
+    ///
+    /// Returns the starred project.
+    pub fn star(&self) -> Result<::projects::Project> {
+        self.post_action("star")
+    }
+
+    /// Unstar the project.
+    pub fn unstar(&self) -> Result<()> {
+        let query = format!("{}/star", self.build_query());
+        debug!("query: {:?}", query);
+
+        self.gl.delete(&query).chain_err(|| format!("cannot delete query {}", query))
+    }
+
+    /// Archive the project, making it read-only.
+    ///
+    /// Returns the archived project.
+    pub fn archive(&self) -> Result<::projects::Project> {
+        self.post_action("archive")
+    }
+
+    /// Unarchive the project.
+    ///
+    /// Returns the unarchived project.
+    pub fn unarchive(&self) -> Result<::projects::Project> {
+        self.post_action("unarchive")
+    }
+
+    /// Start the housekeeping (`git gc`...) of the project's repository (admin only).
+    ///
+    /// The housekeeping runs in the background: this returns once it was scheduled.
+    pub fn housekeeping(&self) -> Result<()> {
+        let query = self.build_housekeeping_query();
+        debug!("query: {:?}", query);
+
+        match self.gl.post::<serde_json::Value, _>(&query, &EmptyBody {}) {
+            Ok(_) => Ok(()),
+            Err(e @ Error(ErrorKind::Forbidden(_), _)) => {
+                Err(e).chain_err(|| {
+                    format!("cannot post query {}: only administrators can start the \
+                             housekeeping",
+                            query)
+                })
+            }
+            Err(e) => Err(e).chain_err(|| format!("cannot post query {}", query)),
+        }
+    }
+
+    fn build_housekeeping_query(&self) -> String {
+        format!("{}/housekeeping", self.build_query())
+    }
+
+    /// Remove the project, including all its issues, merge requests etc.
+    pub fn delete(&self) -> Result<()> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl.delete(&query).chain_err(|| format!("cannot delete query {}", query))
+    }
+
+    fn post_action(&self, action: &str) -> Result<::projects::Project> {
+        let query = format!("{}/{}", self.build_query(), action);
+        debug!("query: {:?}", query);
+
+        self.gl.post(&query, &EmptyBody {}).chain_err(|| format!("cannot post query {}", query))
+    }
+
+
     /// Return a lister for the project's issues
     pub fn issues(self) -> Result<::issues::project::IssuesLister<'a>> {
         let project = self.list().chain_err(|| "failure to find project")?;
@@ -55,22 +224,87 @@ impl<'a> ProjectsLister<'a> {
         Ok(::issues::project::IssuesLister::new(self.gl, project.id))
     }
 
+    /// Return a lister for the project's issue `iid`
+    pub fn issue(self, iid: i64) -> ::issues::links::ProjectIssueLister<'a> {
+        ::issues::links::ProjectIssueLister::new(self.gl, self.id, iid)
+    }
+
     /// Return a lister for the project's merge requests
     pub fn merge_requests(self) -> Result<::merge_requests::MergeRequestsLister<'a>> {
         let project = self.list().chain_err(|| "failure to find project")?;
 
         Ok(::merge_requests::MergeRequestsLister::new(self.gl, project.id))
     }
+
+    /// Return a lister for the project's merge request approval rules
+    pub fn approval_rules(self) -> ::projects::id_approval_rules::ApprovalRulesLister<'a> {
+        ::projects::id_approval_rules::ApprovalRulesLister::new(self.gl, self.id)
+    }
+
+    /// Return a lister for the project's labels
+    pub fn labels(self) -> ::projects::id_labels::LabelsLister<'a> {
+        ::projects::id_labels::LabelsLister::new(self.gl, self.id)
+    }
+
+    /// Return a lister for the project's members
+    pub fn members(self) -> ::projects::id_members::MembersLister<'a> {
+        ::projects::id_members::MembersLister::new(self.gl, self.id)
+    }
+
+    /// Return a lister for the project's pipelines
+    pub fn pipelines(self) -> ::projects::id_pipelines::PipelinesLister<'a> {
+        ::projects::id_pipelines::PipelinesLister::new(self.gl, self.id)
+    }
+
+    /// Return a lister for the authenticated user's notification settings for the project
+    pub fn notification_settings(self) -> NotificationSettingsLister<'a> {
+        NotificationSettingsLister::new(self.gl, self.id)
+    }
+
+    /// Return a lister for the project's forks
+    pub fn forks(self) -> ::projects::id_forks::ProjectsLister<'a> {
+        ::projects::id_forks::ProjectsLister::new(self.gl, self.id)
+    }
+
+    /// Return a lister for the repositories of the project's container registry
+    pub fn registry_repositories(self) -> ::projects::id_registry::RegistryRepositoriesLister<'a> {
+        ::projects::id_registry::RegistryRepositoriesLister::new(self.gl, self.id)
+    }
+
+    /// Return a lister for the project's wiki pages
+    pub fn wikis(self) -> ::projects::wikis::WikisLister<'a> {
+        ::projects::wikis::WikisLister::new(self.gl, self.id)
+    }
+
+    /// Search the project's `scope` (files content, commits...) for `search`
+    pub fn search(self,
+                  scope: ::projects::id_search::SearchScope,
+                  search: String)
+                  -> ::projects::id_search::SearchLister<'a> {
+        ::projects::id_search::SearchLister::new(self.gl, self.id, scope, search)
+    }
+
+    /// Return a lister for the project's repository commits
+    pub fn commits(self) -> ::projects::id_commits::CommitsLister<'a> {
+        ::projects::id_commits::CommitsLister::new(self.gl, self.id)
+    }
+
+    /// Return a lister for the commit `sha` (or branch or tag name) of the project's repository
+    pub fn commit(self, sha: String) -> ::projects::id_commit::CommitLister<'a> {
+        ::projects::id_commit::CommitLister::new(self.gl, self.id, sha)
+    }
 }
 
+/// Body of the actions not taking any parameter.
+#[derive(Debug, Serialize)]
+struct EmptyBody {}
+
+
 impl<'a> BuildQuery for ProjectsLister<'a> {
     fn build_query(&self) -> String {
         let mut query = String::from("projects/");
 
-        query.push_str(&match self.id {
-            ::projects::ListingId::Id(id) => id.to_string(),
-            ::projects::ListingId::NamespaceProject(ref s) => s.replace("/", "%2F"),
-        });
+        query.push_str(&self.id.encode());
 
         query
     }
@@ -79,7 +313,13 @@ impl<'a> BuildQuery for ProjectsLister<'a> {
 
 #[cfg(test)]
 mod tests {
+    use hyper;
+    use serde_json;
+
     use BuildQuery;
+    use errors::ErrorKind;
+    use tests::mock_gitlab;
+    use transport::Response;
 
     const TEST_PROJECT_ID: i64 = 123;
     const TEST_PROJECT_NAME: &'static str = "group/project";
@@ -103,4 +343,202 @@ mod tests {
             .build_query();
         assert_eq!(query, expected_string);
     }
+
+
+    #[test]
+    fn build_housekeeping_query() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+
+        let expected_string = format!("projects/{}/housekeeping", TEST_PROJECT_ID);
+        let query = gl.projects()
+            .id(::projects::ListingId::Id(TEST_PROJECT_ID))
+            .build_housekeeping_query();
+        assert_eq!(query, expected_string);
+
+        let expected_string = "projects/group%2Fproject/housekeeping";
+        let query = gl.projects()
+            .id(::projects::ListingId::NamespaceProject(TEST_PROJECT_NAME.to_string()))
+            .build_housekeeping_query();
+        assert_eq!(query, expected_string);
+    }
+
+
+    #[test]
+    fn housekeeping() {
+        let id = || ::projects::ListingId::Id(TEST_PROJECT_ID);
+
+        let requests = requests_of(Response::new(hyper::status::StatusCode::Created), |gl| {
+            gl.projects().id(id()).housekeeping().unwrap();
+        });
+        assert_eq!(requests[0].method, hyper::method::Method::Post);
+        assert!(requests[0]
+            .url
+            .starts_with("https://gitlab.example.com/api/v3/projects/123/housekeeping?"));
+
+        let mut forbidden = Response::json(r#"{"message": "403 Forbidden"}"#);
+        forbidden.status = hyper::status::StatusCode::Forbidden;
+        requests_of(forbidden, |gl| {
+            let error = gl.projects().id(id()).housekeeping().unwrap_err();
+            assert!(error.to_string().contains("only administrators"));
+            // The status is still found behind the message.
+            match *error.typed_kind() {
+                ErrorKind::Forbidden(ref reason) => assert_eq!(reason, "403 Forbidden"),
+                ref other => panic!("expected a forbidden error, got {:?}", other),
+            }
+        });
+    }
+
+
+    #[test]
+    fn deserialize_project_flags() {
+        let project: ::projects::Project = serde_json::from_str(TEST_PROJECT_JSON)
+            .expect("JSON deserialization failed");
+        assert_eq!(project.shared_runners_enabled, Some(true));
+        assert_eq!(project.lfs_enabled, None);
+        assert_eq!(project.request_access_enabled, None);
+
+        let json_reply = TEST_PROJECT_JSON.replace("\"shared_runners_enabled\": true,",
+                                                   "\"lfs_enabled\": true, \
+                                                    \"request_access_enabled\": false,");
+        let project: ::projects::Project = serde_json::from_str(&json_reply)
+            .expect("JSON deserialization failed");
+        assert_eq!(project.shared_runners_enabled, None);
+        assert_eq!(project.lfs_enabled, Some(true));
+        assert_eq!(project.request_access_enabled, Some(false));
+    }
+
+
+    #[test]
+    fn build_simple_query() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+
+        let expected_string = format!("projects/{}?simple=true", TEST_PROJECT_ID);
+        let query = gl.projects()
+            .id(::projects::ListingId::Id(TEST_PROJECT_ID))
+            .build_simple_query();
+        assert_eq!(query, expected_string);
+
+        let expected_string = "projects/group%2Fproject?simple=true";
+        let query = gl.projects()
+            .id(::projects::ListingId::NamespaceProject(TEST_PROJECT_NAME.to_string()))
+            .build_simple_query();
+        assert_eq!(query, expected_string);
+    }
+
+
+    #[test]
+    fn deserialize_simple_project() {
+        let json_reply = r##"{
+            "id": 123,
+            "name": "project",
+            "path": "project",
+            "web_url": "http://example.com/group/project",
+            "path_with_namespace": "group/project"
+        }"##;
+
+        let project: ::projects::SimpleProject = serde_json::from_str(json_reply)
+            .expect("JSON deserialization failed");
+        assert_eq!(project.id, 123);
+        assert_eq!(project.web_url, "http://example.com/group/project");
+        assert_eq!(project.path_with_namespace, Some(String::from("group/project")));
+        assert_eq!(project.name_with_namespace, None);
+
+        // The full project can be read as a simple one.
+        let project: ::projects::SimpleProject = serde_json::from_str(TEST_PROJECT_JSON)
+            .expect("JSON deserialization failed");
+        assert_eq!(project.star_count, Some(1));
+    }
+
+
+    const TEST_PROJECT_JSON: &'static str = r##"{
+        "id": 123,
+        "description": "",
+        "default_branch": "master",
+        "tag_list": [],
+        "public": false,
+        "archived": true,
+        "visibility_level": 0,
+        "ssh_url_to_repo": "git@example.com:group/project.git",
+        "http_url_to_repo": "http://example.com/group/project.git",
+        "web_url": "http://example.com/group/project",
+        "name": "project",
+        "name_with_namespace": "group / project",
+        "path": "project",
+        "path_with_namespace": "group/project",
+        "created_at": "2017-01-31T14:46:26.638Z",
+        "last_activity_at": "2017-01-31T14:46:26.638Z",
+        "shared_runners_enabled": true,
+        "creator_id": 1,
+        "namespace": {
+            "id": 2,
+            "name": "group",
+            "path": "group",
+            "kind": "group"
+        },
+        "star_count": 1,
+        "forks_count": 0
+    }"##;
+
+
+    fn requests_of<F>(response: Response, action: F) -> Vec<::transport::Request>
+        where F: Fn(&::GitLab)
+    {
+        let (gl, mock) = mock_gitlab();
+        mock.push_response(response);
+
+        action(&gl);
+
+        mock.requests()
+    }
+
+
+    #[test]
+    fn mutations() {
+        let id = || ::projects::ListingId::NamespaceProject(TEST_PROJECT_NAME.to_string());
+        let project_url = "https://gitlab.example.com/api/v3/projects/group%2Fproject";
+        let no_content = || Response::new(hyper::status::StatusCode::Ok);
+
+        let requests = requests_of(Response::json(TEST_PROJECT_JSON), |gl| {
+            gl.projects().id(id()).star().unwrap();
+        });
+        assert_eq!(requests[0].method, hyper::method::Method::Post);
+        assert!(requests[0].url.starts_with(&format!("{}/star?", project_url)));
+
+        let requests = requests_of(no_content(), |gl| {
+            gl.projects().id(id()).unstar().unwrap();
+        });
+        assert_eq!(requests[0].method, hyper::method::Method::Delete);
+        assert!(requests[0].url.starts_with(&format!("{}/star?", project_url)));
+
+        let requests = requests_of(Response::json(TEST_PROJECT_JSON), |gl| {
+            assert!(gl.projects().id(id()).archive().unwrap().archived);
+        });
+        assert_eq!(requests[0].method, hyper::method::Method::Post);
+        assert!(requests[0].url.starts_with(&format!("{}/archive?", project_url)));
+
+        let requests = requests_of(Response::json(TEST_PROJECT_JSON), |gl| {
+            gl.projects().id(id()).unarchive().unwrap();
+        });
+        assert_eq!(requests[0].method, hyper::method::Method::Post);
+        assert!(requests[0].url.starts_with(&format!("{}/unarchive?", project_url)));
+
+        let requests = requests_of(Response::json(TEST_PROJECT_JSON), |gl| {
+            let update = ::projects::ProjectUpdate {
+                lfs_enabled: Some(false),
+                shared_runners_enabled: Some(true),
+                ..Default::default()
+            };
+            gl.projects().id(id()).update(&update).unwrap();
+        });
+        assert_eq!(requests[0].method, hyper::method::Method::Put);
+        assert!(requests[0].url.starts_with(&format!("{}?", project_url)));
+        assert_eq!(requests[0].body,
+                   Some(String::from(r#"{"lfs_enabled":false,"shared_runners_enabled":true}"#)));
+
+        let requests = requests_of(no_content(), |gl| {
+            gl.projects().id(id()).delete().unwrap();
+        });
+        assert_eq!(requests[0].method, hyper::method::Method::Delete);
+        assert!(requests[0].url.starts_with(&format!("{}?", project_url)));
+    }
 }
@@ -0,0 +1,249 @@
+//! List, create, update and delete project-level merge request approval rules
+//!
+//! https://docs.gitlab.com/ee/api/merge_request_approvals.html#get-project-level-rules
+//!
+//! **Note**: Approval rules are a GitLab _Enterprise Edition_ feature, only available through
+//! the API v4.
+//!
+//! # Get project-level rules
+//!
+//! ```text
+//! GET /projects/ID/approval_rules
+//! ```
+//!
+//! | Attribute | Type | Required | Description |
+//! | --------- | ---- | -------- | ----------- |
+//! | `id` | integer/string | yes | The ID or `NAMESPACE/PROJECT_NAME` of the project |
+//!
+//! # Create project-level rule
+//!
+//! ```text
+//! POST /projects/ID/approval_rules
+//! ```
+//!
+//! | Attribute | Type | Required | Description |
+//! | --------- | ---- | -------- | ----------- |
+//! | `id` | integer/string | yes | The ID or `NAMESPACE/PROJECT_NAME` of the project |
+//! | `name` | string | yes | The name of the approval rule |
+//! | `approvals_required` | integer | yes | The number of required approvals for this rule |
+//! | `user_ids` | array | no | The ids of users as approvers |
+//! | `group_ids` | array | no | The ids of groups as approvers |
+//!
+//! # Update project-level rule
+//!
+//! ```text
+//! PUT /projects/ID/approval_rules/APPROVAL_RULE_ID
+//! ```
+//!
+//! Same attributes as when creating a rule, plus `approval_rule_id`, the ID of the rule.
+//!
+//! # Delete project-level rule
+//!
+//! ```text
+//! DELETE /projects/ID/approval_rules/APPROVAL_RULE_ID
+//! ```
+
+
+use BuildQuery;
+
+use ::errors::*;
+
+
+/// A group allowed to approve merge requests.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ApprovalRuleGroup {
+    pub id: i64,
+    pub name: String,
+    pub path: String,
+    pub full_path: Option<String>,
+}
+
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ApprovalRule {
+    pub id: i64,
+    pub name: String,
+    pub approvals_required: i64,
+    /// Users allowed to approve, including the members of `groups`.
+    pub eligible_approvers: Vec<::User>,
+    pub groups: Vec<ApprovalRuleGroup>,
+}
+
+pub type ApprovalRules = Vec<ApprovalRule>;
+
+
+/// The attributes of an approval rule to create or update.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApprovalRuleCreate {
+    pub name: String,
+    pub approvals_required: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_ids: Option<Vec<i64>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub group_ids: Option<Vec<i64>>,
+}
+
+
+#[derive(Debug, Clone)]
+pub struct ApprovalRulesLister<'a> {
+    gl: &'a ::GitLab,
+    id: ::projects::ListingId,
+}
+
+
+impl<'a> ApprovalRulesLister<'a> {
+    pub fn new(gl: &'a ::GitLab, id: ::projects::ListingId) -> ApprovalRulesLister {
+        ApprovalRulesLister { gl: gl, id: id }
+    }
+
+
+    /// Commit the lister: Query GitLab and return a list of approval rules.
+    pub fn list(&self) -> Result<ApprovalRules> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl.get_listing(&query).chain_err(|| format!("cannot get query {}", query))
+    }
+
+    /// Create an approval rule.
+    pub fn create(&self, rule: &ApprovalRuleCreate) -> Result<ApprovalRule> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl.post(&query, rule).chain_err(|| format!("cannot post query {}", query))
+    }
+
+    /// Update the approval rule `rule_id`.
+    pub fn update(&self, rule_id: i64, rule: &ApprovalRuleCreate) -> Result<ApprovalRule> {
+        let query = self.build_rule_query(rule_id);
+        debug!("query: {:?}", query);
+
+        self.gl.put(&query, rule).chain_err(|| format!("cannot put query {}", query))
+    }
+
+    /// Delete the approval rule `rule_id`.
+    pub fn delete(&self, rule_id: i64) -> Result<()> {
+        let query = self.build_rule_query(rule_id);
+        debug!("query: {:?}", query);
+
+        self.gl.delete(&query).chain_err(|| format!("cannot delete query {}", query))
+    }
+
+    fn build_rule_query(&self, rule_id: i64) -> String {
+        format!("{}/{}", self.build_query(), rule_id)
+    }
+}
+
+
+impl<'a> BuildQuery for ApprovalRulesLister<'a> {
+    fn build_query(&self) -> String {
+        let mut query = String::from("projects/");
+
+        query.push_str(&self.id.encode());
+        query.push_str("/approval_rules");
+
+        query
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use serde_json;
+
+    use BuildQuery;
+    use projects::id_approval_rules::{ApprovalRule, ApprovalRuleCreate};
+
+    const TEST_PROJECT_ID: i64 = 123;
+    const TEST_PROJECT_NAME: &'static str = "group/project";
+
+
+    #[test]
+    fn build_query_default() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+        // let gl: ::GitLab = Default::default();
+
+        let expected_string = format!("projects/{}/approval_rules", TEST_PROJECT_ID);
+        let query = gl.projects()
+            .id(::projects::ListingId::Id(TEST_PROJECT_ID))
+            .approval_rules()
+            .build_query();
+        assert_eq!(query, expected_string);
+
+        let expected_string = "projects/group%2Fproject/approval_rules";
+        let query = gl.projects()
+            .id(::projects::ListingId::NamespaceProject(TEST_PROJECT_NAME.to_string()))
+            .approval_rules()
+            .build_query();
+        assert_eq!(query, expected_string);
+    }
+
+
+    #[test]
+    fn build_rule_query() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+
+        let expected_string = format!("projects/{}/approval_rules/7", TEST_PROJECT_ID);
+        let query = gl.projects()
+            .id(::projects::ListingId::Id(TEST_PROJECT_ID))
+            .approval_rules()
+            .build_rule_query(7);
+        assert_eq!(query, expected_string);
+    }
+
+
+    #[test]
+    fn serialize_approval_rule_create() {
+        let rule = ApprovalRuleCreate {
+            name: String::from("security"),
+            approvals_required: 2,
+            user_ids: None,
+            group_ids: Some(vec![5, 6]),
+        };
+
+        let body = serde_json::to_string(&rule).unwrap();
+        assert_eq!(body,
+                   r#"{"name":"security","approvals_required":2,"group_ids":[5,6]}"#);
+    }
+
+
+    #[test]
+    fn deserialize_approval_rules() {
+        let json_reply = r##"[
+            {
+                "id": 1,
+                "name": "security",
+                "rule_type": "regular",
+                "eligible_approvers": [
+                    {
+                        "id": 5,
+                        "name": "John Doe",
+                        "username": "jdoe",
+                        "state": "active",
+                        "avatar_url": "https://www.gravatar.com/avatar/0?s=80&d=identicon",
+                        "web_url": "http://localhost/jdoe"
+                    }
+                ],
+                "approvals_required": 3,
+                "users": [],
+                "groups": [
+                    {
+                        "id": 5,
+                        "name": "group1",
+                        "path": "group1",
+                        "full_path": "group1",
+                        "visibility": "public"
+                    }
+                ],
+                "contains_hidden_groups": false
+            }
+        ]"##;
+
+        let rules: Vec<ApprovalRule> = serde_json::from_str(json_reply)
+            .expect("JSON deserialization failed");
+        assert_eq!(rules[0].name, "security");
+        assert_eq!(rules[0].approvals_required, 3);
+        assert_eq!(rules[0].eligible_approvers[0].username, "jdoe");
+        assert_eq!(rules[0].groups[0].path, "group1");
+    }
+}
@@ -0,0 +1,302 @@
+//! Single commit
+//!
+//! https://docs.gitlab.com/ce/api/commits.html
+//!
+//! # Get a single commit
+//!
+//! Get a specific commit identified by the commit hash or name of a branch or tag.
+//!
+//! ```text
+//! GET /projects/ID/repository/commits/SHA
+//! ```
+//!
+//! Parameters:
+//!
+//! | Attribute | Type | Required | Description |
+//! | --------- | ---- | -------- | ----------- |
+//! | `id` | integer/string | yes | The ID of a project or `NAMESPACE/PROJECT_NAME` owned by the authenticated user |
+//! | `sha` | string | yes | The commit hash or name of a repository branch or tag |
+//!
+//! The `sha` is URL-encoded, so branch names like `feature/login` can be used. The commit is
+//! returned with its parents and its stats (lines added and deleted), see `CommitDetail`.
+//!
+//! # Get references a commit is pushed to
+//!
+//! Get all references (from branches or tags) a commit is pushed to.
+//!
+//! ```text
+//! GET /projects/ID/repository/commits/SHA/refs
+//! ```
+//!
+//! # Cherry pick a commit
+//!
+//! Cherry picks a commit to a given branch.
+//!
+//! ```text
+//! POST /projects/ID/repository/commits/SHA/cherry_pick
+//! ```
+//!
+//! Parameters:
+//!
+//! | Attribute | Type | Required | Description |
+//! | --------- | ---- | -------- | ----------- |
+//! | `id` | integer/string | yes | The ID of a project or `NAMESPACE/PROJECT_NAME` owned by the authenticated user |
+//! | `sha` | string | yes | The commit hash |
+//! | `branch` | string | yes | The name of the branch |
+//!
+//! GitLab answers `400 Bad Request` when the commit cannot be cherry-picked, for example because
+//! of a conflict; this is reported as `ErrorKind::CherryPickConflict`.
+
+
+use BuildQuery;
+
+use projects::RepoCommit;
+
+use ::errors::*;
+
+
+/// The number of lines changed by a commit.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CommitStats {
+    pub additions: u64,
+    pub deletions: u64,
+    pub total: u64,
+}
+
+
+/// A single commit, as returned by `CommitLister::list()`: a `RepoCommit` with its stats.
+pub type CommitDetail = RepoCommit;
+
+
+/// The kind of a reference, see `CommitRef`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum CommitRefType {
+    #[serde(rename = "branch")]
+    Branch,
+    #[serde(rename = "tag")]
+    Tag,
+}
+
+
+/// A branch or tag containing a commit.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CommitRef {
+    #[serde(rename = "type")]
+    pub ref_type: CommitRefType,
+    pub name: String,
+}
+
+pub type CommitRefs = Vec<CommitRef>;
+
+
+#[derive(Debug, Serialize)]
+struct CherryPickInternal {
+    branch: String,
+}
+
+
+#[derive(Debug, Clone)]
+pub struct CommitLister<'a> {
+    gl: &'a ::GitLab,
+    id: ::projects::ListingId,
+    /// The commit hash or name of a branch or tag
+    sha: String,
+}
+
+
+impl<'a> CommitLister<'a> {
+    pub fn new(gl: &'a ::GitLab, id: ::projects::ListingId, sha: String) -> CommitLister {
+        CommitLister {
+            gl: gl,
+            id: id,
+            sha: sha,
+        }
+    }
+
+
+    /// Commit the lister: Query GitLab and return the commit.
+    pub fn list(&self) -> Result<CommitDetail> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl.get(&query, None, None).chain_err(|| format!("cannot get query {}", query))
+    }
+
+    /// Query GitLab and return the branches and tags the commit is pushed to.
+    pub fn refs(&self) -> Result<CommitRefs> {
+        let query = format!("{}/refs", self.build_query());
+        debug!("query: {:?}", query);
+
+        self.gl.get_listing(&query).chain_err(|| format!("cannot get query {}", query))
+    }
+
+    /// Cherry-pick the commit onto `branch`.
+    ///
+    /// Returns the new commit created on `branch`. A commit that cannot be applied cleanly is
+    /// reported as `ErrorKind::CherryPickConflict`.
+    pub fn cherry_pick(&self, branch: String) -> Result<RepoCommit> {
+        let query = self.build_cherry_pick_query();
+        debug!("query: {:?}", query);
+
+        match self.gl.post(&query, CherryPickInternal { branch: branch.clone() }) {
+            Err(Error(ErrorKind::BadRequest(message), _)) => {
+                bail!(ErrorKind::CherryPickConflict(branch, message))
+            }
+            result => result.chain_err(|| format!("cannot post query {}", query)),
+        }
+    }
+
+    fn build_cherry_pick_query(&self) -> String {
+        format!("{}/cherry_pick", self.build_query())
+    }
+}
+
+
+impl<'a> BuildQuery for CommitLister<'a> {
+    fn build_query(&self) -> String {
+        let mut query = String::from("projects/");
+
+        query.push_str(&self.id.encode());
+        query.push_str("/repository/commits/");
+        query.push_str(&::id::encode_path_segment(&self.sha));
+
+        query
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use serde_json;
+
+    use BuildQuery;
+    use errors::{Error, ErrorKind};
+    use projects::id_commit::{CommitDetail, CommitRefType, CommitRefs};
+    use tests::mock_gitlab;
+    use transport::Response;
+
+    const TEST_PROJECT_ID: i64 = 123;
+    const TEST_PROJECT_NAME: &'static str = "group/project";
+    const TEST_SHA: &'static str = "8b090c1b79a14f2bd9e8a738f717824ff53aebad";
+
+
+    #[test]
+    fn build_query_default() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+        // let gl: ::GitLab = Default::default();
+
+        let query = gl.projects()
+            .id(::projects::ListingId::Id(9))
+            .commit(String::from("abc123"))
+            .build_query();
+        assert_eq!(query, "projects/9/repository/commits/abc123");
+
+        let query = gl.projects()
+            .id(::projects::ListingId::NamespaceProject(TEST_PROJECT_NAME.to_string()))
+            .commit(String::from("feature/login"))
+            .build_query();
+        assert_eq!(query, "projects/group%2Fproject/repository/commits/feature%2Flogin");
+    }
+
+
+    #[test]
+    fn build_query_cherry_pick() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+        // let gl: ::GitLab = Default::default();
+
+        let expected_string = format!("projects/{}/repository/commits/{}",
+                                      TEST_PROJECT_ID,
+                                      TEST_SHA);
+        let lister = gl.projects()
+            .id(::projects::ListingId::Id(TEST_PROJECT_ID))
+            .commit(TEST_SHA.to_string());
+        assert_eq!(lister.build_query(), expected_string);
+
+        let expected_string = format!("projects/group%2Fproject/repository/commits/{}/cherry_pick",
+                                      TEST_SHA);
+        let lister = gl.projects()
+            .id(::projects::ListingId::NamespaceProject(TEST_PROJECT_NAME.to_string()))
+            .commit(TEST_SHA.to_string());
+        assert_eq!(lister.build_cherry_pick_query(), expected_string);
+    }
+
+
+    #[test]
+    fn cherry_pick_conflict() {
+        let (gl, mock) = mock_gitlab();
+        let mut response = Response::json(r#"{"message": "Sorry, we cannot cherry-pick this \
+                                              commit automatically."}"#);
+        response.status = ::hyper::status::StatusCode::BadRequest;
+        mock.push_response(response);
+
+        let result = gl.projects()
+            .id(::projects::ListingId::Id(TEST_PROJECT_ID))
+            .commit(TEST_SHA.to_string())
+            .cherry_pick(String::from("stable"));
+        match result {
+            Err(Error(ErrorKind::CherryPickConflict(branch, _), _)) => assert_eq!(branch, "stable"),
+            other => panic!("expected a cherry-pick conflict, got {:?}", other),
+        }
+
+        let requests = mock.requests();
+        assert_eq!(requests[0].body, Some(String::from(r#"{"branch":"stable"}"#)));
+    }
+
+
+    #[test]
+    fn deserialize_commit_detail() {
+        let json_reply = r#"{
+            "id": "6104942438c14ec7bd21c6cd5bd995272b3faff6",
+            "short_id": "6104942438c",
+            "title": "Sanitize for network graph",
+            "author_name": "randx",
+            "author_email": "dmitriy.zaporozhets@gmail.com",
+            "created_at": "2012-09-20T09:06:12+03:00",
+            "message": "Sanitize for network graph",
+            "committed_date": "2012-09-20T09:06:12+03:00",
+            "authored_date": "2012-09-20T09:06:12+03:00",
+            "parent_ids": ["ae1d9fb46aa2b07ee9836d49862ec4e2c46fbbba"],
+            "stats": {"additions": 15, "deletions": 10, "total": 25},
+            "status": "running"
+        }"#;
+
+        let commit: CommitDetail = serde_json::from_str(json_reply)
+            .expect("JSON deserialization failed");
+        assert_eq!(commit.short_id, "6104942438c");
+        assert_eq!(commit.parent_ids.unwrap(), vec!["ae1d9fb46aa2b07ee9836d49862ec4e2c46fbbba"]);
+        let stats = commit.stats.unwrap();
+        assert_eq!((stats.additions, stats.deletions, stats.total), (15, 10, 25));
+
+        // Older GitLab versions send neither the stats nor (for the root commit) the parents.
+        let json_reply = r#"{
+            "id": "6104942438c14ec7bd21c6cd5bd995272b3faff6",
+            "short_id": "6104942438c",
+            "title": "Initial commit",
+            "author_name": "randx",
+            "author_email": "dmitriy.zaporozhets@gmail.com",
+            "created_at": "2012-09-20T09:06:12+03:00",
+            "message": "Initial commit"
+        }"#;
+
+        let commit: CommitDetail = serde_json::from_str(json_reply)
+            .expect("JSON deserialization failed");
+        assert!(commit.parent_ids.is_none());
+        assert!(commit.committed_date.is_none());
+        assert!(commit.stats.is_none());
+    }
+
+
+    #[test]
+    fn deserialize_refs() {
+        let json_reply = r#"[
+            {"type": "branch", "name": "master"},
+            {"type": "tag", "name": "v1.1.0"}
+        ]"#;
+
+        let refs: CommitRefs = serde_json::from_str(json_reply)
+            .expect("JSON deserialization failed");
+        assert_eq!(refs[0].ref_type, CommitRefType::Branch);
+        assert_eq!(refs[1].ref_type, CommitRefType::Tag);
+        assert_eq!(refs[1].name, "v1.1.0");
+    }
+}
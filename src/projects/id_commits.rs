@@ -0,0 +1,163 @@
+//! List repository commits and create a commit
+//!
+//! https://docs.gitlab.com/ce/api/commits.html
+//!
+//! # List repository commits
+//!
+//! Get a list of repository commits in a project.
+//!
+//! ```text
+//! GET /projects/ID/repository/commits
+//! ```
+//!
+//! Parameters:
+//!
+//! | Attribute | Type | Required | Description |
+//! | --------- | ---- | -------- | ----------- |
+//! | `id` | integer/string | yes | The ID of a project or `NAMESPACE/PROJECT_NAME` owned by the authenticated user |
+//!
+//! # Create a commit with multiple files and actions
+//!
+//! Create a commit by posting a JSON payload.
+//!
+//! ```text
+//! POST /projects/ID/repository/commits
+//! ```
+//!
+//! Parameters:
+//!
+//! | Attribute | Type | Required | Description |
+//! | --------- | ---- | -------- | ----------- |
+//! | `id` | integer/string | yes | The ID of a project or `NAMESPACE/PROJECT_NAME` |
+//! | `branch_name` | string | yes | The name of a branch |
+//! | `commit_message` | string | yes | Commit message |
+//! | `actions[]` | array | yes | An array of action hashes to commit as a batch. See the next table for what attributes it can take. |
+//!
+//! | `actions[]` Attribute | Type | Required | Description |
+//! | --------------------- | ---- | -------- | ----------- |
+//! | `action` | string | yes | The action to perform, `create`, `delete`, `move`, `update` |
+//! | `file_path` | string | yes | Full path to the file. Ex. `lib/class.rb` |
+//! | `previous_path` | string | no | Original full path to the file being moved. Ex. `lib/class1.rb` |
+//! | `content` | string | no | File content, required for all except `delete`. Optional for `move` |
+
+
+use BuildQuery;
+
+use projects::{CommitCreate, RepoCommit, RepoCommits};
+
+use ::errors::*;
+
+
+#[derive(Debug, Clone)]
+pub struct CommitsLister<'a> {
+    gl: &'a ::GitLab,
+    id: ::projects::ListingId,
+}
+
+
+impl<'a> CommitsLister<'a> {
+    pub fn new(gl: &'a ::GitLab, id: ::projects::ListingId) -> CommitsLister {
+        CommitsLister { gl: gl, id: id }
+    }
+
+
+    /// Commit the lister: Query GitLab and return a list of commits.
+    pub fn list(&self) -> Result<RepoCommits> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl.get_listing(&query).chain_err(|| format!("cannot get query {}", query))
+    }
+
+    /// Create a commit, applying all the `commit.actions` as a batch.
+    pub fn create(&self, commit: &CommitCreate) -> Result<RepoCommit> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl.post(&query, commit).chain_err(|| format!("cannot post query {}", query))
+    }
+}
+
+
+impl<'a> BuildQuery for CommitsLister<'a> {
+    fn build_query(&self) -> String {
+        let mut query = String::from("projects/");
+
+        query.push_str(&self.id.encode());
+        query.push_str("/repository/commits");
+
+        query
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use serde_json;
+
+    use BuildQuery;
+    use projects::{CommitAction, CommitActionType, CommitCreate};
+
+    const TEST_PROJECT_ID: i64 = 123;
+    const TEST_PROJECT_NAME: &'static str = "group/project";
+
+
+    #[test]
+    fn build_query_default() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+        // let gl: ::GitLab = Default::default();
+
+        let expected_string = format!("projects/{}/repository/commits", TEST_PROJECT_ID);
+        let query = gl.projects()
+            .id(::projects::ListingId::Id(TEST_PROJECT_ID))
+            .commits()
+            .build_query();
+        assert_eq!(query, expected_string);
+
+        let expected_string = "projects/group%2Fproject/repository/commits";
+        let query = gl.projects()
+            .id(::projects::ListingId::NamespaceProject(TEST_PROJECT_NAME.to_string()))
+            .commits()
+            .build_query();
+        assert_eq!(query, expected_string);
+    }
+
+
+    #[test]
+    fn serialize_commit_create() {
+        let commit = CommitCreate {
+            branch: String::from("master"),
+            commit_message: String::from("Generated files"),
+            actions: vec![CommitAction {
+                              action: CommitActionType::Create,
+                              file_path: String::from("doc/new.md"),
+                              previous_path: None,
+                              content: Some(String::from("# New")),
+                          },
+                          CommitAction {
+                              action: CommitActionType::Move,
+                              file_path: String::from("doc/moved.md"),
+                              previous_path: Some(String::from("doc/old.md")),
+                              content: None,
+                          },
+                          CommitAction {
+                              action: CommitActionType::Delete,
+                              file_path: String::from("doc/obsolete.md"),
+                              previous_path: None,
+                              content: None,
+                          }],
+        };
+
+        let expected_string = "{\"branch_name\":\"master\",\
+                               \"commit_message\":\"Generated files\",\
+                               \"actions\":[\
+                               {\"action\":\"create\",\"file_path\":\"doc/new.md\",\
+                               \"content\":\"# New\"},\
+                               {\"action\":\"move\",\"file_path\":\"doc/moved.md\",\
+                               \"previous_path\":\"doc/old.md\"},\
+                               {\"action\":\"delete\",\"file_path\":\"doc/obsolete.md\"}]}";
+
+        let body = serde_json::to_string(&commit).unwrap();
+        assert_eq!(body, expected_string);
+    }
+}
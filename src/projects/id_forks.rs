@@ -0,0 +1,166 @@
+//! List the forks of a project
+//!
+//! https://docs.gitlab.com/ce/api/projects.html#list-forks-of-a-project
+//!
+//! # List forks of a project
+//!
+//! List the projects accessible to the calling user that have an established, forked relationship
+//! with the specified project.
+//!
+//! ```text
+//! GET /projects/ID/forks
+//! ```
+//!
+//! Parameters:
+//!
+//! | Attribute | Type | Required | Description |
+//! | --------- | ---- | -------- | ----------- |
+//! | `id` | integer/string | yes | The ID or `NAMESPACE/PROJECT_NAME` of the project |
+//! | `archived` | boolean | no | Limit by archived status |
+//! | `visibility` | string | no | Limit by visibility `public`, `internal`, or `private` |
+//! | `order_by` | string | no | Return projects ordered by `id`, `name`, `path`, `created_at`, `updated_at`, or `last_activity_at` fields. Default is `created_at` |
+//! | `sort` | string | no | Return projects sorted in `asc` or `desc` order. Default is `desc` |
+//! | `search` | string | no | Return list of authorized projects matching the search criteria |
+
+
+use serde_urlencoded;
+
+use BuildQuery;
+
+use ::errors::*;
+
+
+#[derive(Debug, Clone)]
+pub struct ProjectsLister<'a> {
+    gl: &'a ::GitLab,
+    id: ::projects::ListingId,
+    internal: ::projects::ForksProjectListerInternal,
+}
+
+impl<'a> ProjectsLister<'a> {
+    pub fn new(gl: &'a ::GitLab, id: ::projects::ListingId) -> ProjectsLister {
+        ProjectsLister {
+            gl: gl,
+            id: id,
+            internal: ::projects::ForksProjectListerInternal {
+                archived: None,
+                visibility: None,
+                order_by: None,
+                sort: None,
+                search: None,
+            },
+        }
+    }
+
+    pub fn archived(&'a mut self, archived: bool) -> &'a mut ProjectsLister {
+        self.internal.archived = Some(archived);
+        self
+    }
+
+    pub fn visibility(&'a mut self, visibility: ::ListingVisibility) -> &'a mut ProjectsLister {
+        self.internal.visibility = Some(visibility);
+        self
+    }
+
+    pub fn order_by(&'a mut self, order_by: ::projects::ListingOrderBy) -> &'a mut ProjectsLister {
+        self.internal.order_by = Some(order_by);
+        self
+    }
+
+    pub fn sort(&'a mut self, sort: ::ListingSort) -> &'a mut ProjectsLister {
+        self.internal.sort = Some(sort);
+        self
+    }
+
+    pub fn search(&'a mut self, search: String) -> &'a mut ProjectsLister {
+        self.internal.search = Some(search);
+        self
+    }
+
+    /// Commit the lister: Query GitLab and return a list of forks.
+    pub fn list(&self) -> Result<::projects::Projects> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl.get_listing(&query).chain_err(|| format!("cannot get query {}", query))
+    }
+}
+
+
+impl<'a> BuildQuery for ProjectsLister<'a> {
+    fn build_query(&self) -> String {
+        let mut query = String::from("projects/");
+
+        query.push_str(&self.id.encode());
+        query.push_str("/forks");
+
+        let encoded = serde_urlencoded::to_string(&self.internal).unwrap();
+        if !encoded.is_empty() {
+            query.push_str("?");
+            query.push_str(&encoded);
+        }
+
+        query
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use BuildQuery;
+
+    const TEST_PROJECT_ID: i64 = 10;
+    const TEST_PROJECT_NAME: &'static str = "group/project";
+
+
+    #[test]
+    fn build_query_default() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+        // let gl: ::GitLab = Default::default();
+
+        let expected_string = "projects/10/forks";
+        let query = gl.projects()
+            .id(::projects::ListingId::Id(TEST_PROJECT_ID))
+            .forks()
+            .build_query();
+        assert_eq!(query, expected_string);
+
+        let expected_string = "projects/group%2Fproject/forks";
+        let query = gl.projects()
+            .id(::projects::ListingId::NamespaceProject(TEST_PROJECT_NAME.to_string()))
+            .forks()
+            .build_query();
+        assert_eq!(query, expected_string);
+    }
+
+
+    #[test]
+    fn build_query_visibility() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+        // let gl: ::GitLab = Default::default();
+
+        let expected_string = "projects/10/forks?visibility=public";
+        let query = gl.projects()
+            .id(::projects::ListingId::Id(TEST_PROJECT_ID))
+            .forks()
+            .visibility(::ListingVisibility::Public)
+            .build_query();
+        assert_eq!(query, expected_string);
+    }
+
+
+    #[test]
+    fn build_query_search() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+        // let gl: ::GitLab = Default::default();
+
+        let expected_string = "projects/10/forks?archived=false&search=my+fork";
+        let query = gl.projects()
+            .id(::projects::ListingId::Id(TEST_PROJECT_ID))
+            .forks()
+            .archived(false)
+            .search(String::from("my fork"))
+            .build_query();
+        assert_eq!(query, expected_string);
+    }
+}
@@ -0,0 +1,309 @@
+//! List, create and delete project labels
+//!
+//! https://docs.gitlab.com/ce/api/labels.html
+//!
+//! # List labels
+//!
+//! Get all labels for a given project.
+//!
+//! ```text
+//! GET /projects/ID/labels
+//! ```
+//!
+//! | Attribute | Type | Required | Description |
+//! | --------- | ---- | -------- | ----------- |
+//! | `id` | integer/string | yes | The ID or `NAMESPACE/PROJECT_NAME` of the project |
+//!
+//! The labels are paginated: `list()` only returns the first page, use the `Lister`'s
+//! `paginated_iter()` or `pagination::list_all()` to get all of them.
+//!
+//! # Create a new label
+//!
+//! Creates a new label for the given repository with the given name and color.
+//!
+//! ```text
+//! POST /projects/ID/labels
+//! ```
+//!
+//! | Attribute | Type | Required | Description |
+//! | --------- | ---- | -------- | ----------- |
+//! | `id` | integer/string | yes | The ID or `NAMESPACE/PROJECT_NAME` of the project |
+//! | `name` | string | yes | The name of the label |
+//! | `color` | string | yes | The color of the label in 6-digit hex notation with leading `#` sign |
+//!
+//! # Delete a label
+//!
+//! Deletes a label with a given name.
+//!
+//! ```text
+//! DELETE /projects/ID/labels?name=NAME
+//! ```
+//!
+//! | Attribute | Type | Required | Description |
+//! | --------- | ---- | -------- | ----------- |
+//! | `id` | integer/string | yes | The ID or `NAMESPACE/PROJECT_NAME` of the project |
+//! | `name` | string | yes | The name of the label |
+
+
+use serde_urlencoded;
+
+use BuildQuery;
+use Label;
+use Lister;
+use PageInfo;
+
+use ::errors::*;
+
+
+pub type ProjectLabels = Vec<Label>;
+
+
+#[derive(Debug, Serialize)]
+struct LabelCreate {
+    name: String,
+    color: String,
+}
+
+
+/// Whether `color` is written `#RRGGBB`, the only notation GitLab accepts.
+fn is_valid_color(color: &str) -> bool {
+    color.len() == 7 && color.starts_with('#') &&
+    color[1..].chars().all(|c| c.is_digit(16))
+}
+
+
+#[derive(Debug, Clone)]
+pub struct LabelsLister<'a> {
+    gl: &'a ::GitLab,
+    id: ::projects::ListingId,
+}
+
+
+impl<'a> LabelsLister<'a> {
+    pub fn new(gl: &'a ::GitLab, id: ::projects::ListingId) -> LabelsLister {
+        LabelsLister { gl: gl, id: id }
+    }
+
+    /// Create the label `name`, of color `color` (for example `#d9534f`).
+    ///
+    /// Returns the new label. Fails without contacting GitLab if `color` is not written
+    /// `#RRGGBB`.
+    pub fn create_label(&self, name: &str, color: &str) -> Result<Label> {
+        if !is_valid_color(color) {
+            bail!(format!("invalid color '{}' for label '{}': expected #RRGGBB", color, name));
+        }
+
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        let label = LabelCreate {
+            name: name.to_string(),
+            color: color.to_string(),
+        };
+        self.gl.post(&query, label).chain_err(|| format!("cannot post query {}", query))
+    }
+
+    /// Delete the label `name`.
+    pub fn delete_label(&self, name: &str) -> Result<()> {
+        let query = self.build_label_query(name);
+        debug!("query: {:?}", query);
+
+        self.gl.delete(&query).chain_err(|| format!("cannot delete query {}", query))
+    }
+
+    fn build_label_query(&self, name: &str) -> String {
+        let encoded = serde_urlencoded::to_string(&[("name", name)]).unwrap();
+
+        format!("{}?{}", self.build_query(), encoded)
+    }
+}
+
+
+impl<'a> Lister<ProjectLabels> for LabelsLister<'a> {
+    /// Commit the lister: Query GitLab and return the first page of labels.
+    fn list(&self) -> Result<ProjectLabels> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl.get_listing(&query).chain_err(|| format!("cannot get query {}", query))
+    }
+
+    fn list_paginated(&self, page: u16, per_page: u16) -> Result<ProjectLabels> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl.get(&query, page, per_page).chain_err(|| format!("cannot get query {}", query))
+    }
+
+    /// Commit the lister: Query GitLab and return a page of labels with its pagination
+    /// information.
+    fn list_page(&self, page: u16, per_page: u16) -> Result<(ProjectLabels, PageInfo)> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl
+            .get_with_headers(&query, page, per_page)
+            .chain_err(|| format!("cannot get query {}", query))
+    }
+}
+
+
+impl<'a> BuildQuery for LabelsLister<'a> {
+    fn build_query(&self) -> String {
+        let mut query = String::from("projects/");
+
+        query.push_str(&self.id.encode());
+        query.push_str("/labels");
+
+        query
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use hyper;
+    use serde_json;
+
+    use BuildQuery;
+    use Lister;
+    use projects::id_labels::{ProjectLabels, is_valid_color};
+    use tests::mock_gitlab;
+    use transport::Response;
+
+    const TEST_PROJECT_ID: i64 = 123;
+    const TEST_PROJECT_NAME: &'static str = "group/project";
+
+
+    #[test]
+    fn build_query_default() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+        // let gl: ::GitLab = Default::default();
+
+        let expected_string = format!("projects/{}/labels", TEST_PROJECT_ID);
+        let query = gl.projects()
+            .id(::projects::ListingId::Id(TEST_PROJECT_ID))
+            .labels()
+            .build_query();
+        assert_eq!(query, expected_string);
+
+        let expected_string = "projects/group%2Fproject/labels";
+        let query = gl.projects()
+            .id(::projects::ListingId::NamespaceProject(TEST_PROJECT_NAME.to_string()))
+            .labels()
+            .build_query();
+        assert_eq!(query, expected_string);
+    }
+
+
+    #[test]
+    fn build_label_query() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+
+        let expected_string = format!("projects/{}/labels?name=needs+review%2Fui", TEST_PROJECT_ID);
+        let query = gl.projects()
+            .id(::projects::ListingId::Id(TEST_PROJECT_ID))
+            .labels()
+            .build_label_query("needs review/ui");
+        assert_eq!(query, expected_string);
+    }
+
+
+    #[test]
+    fn valid_colors() {
+        assert!(is_valid_color("#d9534f"));
+        assert!(is_valid_color("#FFAABB"));
+        assert!(!is_valid_color("d9534f"));
+        assert!(!is_valid_color("#d9534"));
+        assert!(!is_valid_color("#d9534fa"));
+        assert!(!is_valid_color("#g9534f"));
+        assert!(!is_valid_color("red"));
+        assert!(!is_valid_color("#d9534é"));
+    }
+
+
+    #[test]
+    fn all_pages() {
+        let (gl, mock) = mock_gitlab();
+        let label = r##"{"id": 1, "name": "bug", "color": "#d9534f", "description": null}"##;
+        let mut response = Response::json(&format!("[{}]", label));
+        response.headers.set_raw("X-Next-Page", vec![b"2".to_vec()]);
+        mock.push_response(response);
+        mock.push_response(Response::json(&format!("[{}]", label)));
+        let lister = gl.projects().id(::projects::ListingId::Id(TEST_PROJECT_ID)).labels();
+
+        let labels: ProjectLabels = lister.paginated_iter(1).map(|label| label.unwrap()).collect();
+        assert_eq!(labels.len(), 2);
+
+        let requests = mock.requests();
+        assert_eq!(requests.len(), 2);
+        assert!(requests[1].url.ends_with("&page=2&per_page=1"));
+    }
+
+
+    #[test]
+    fn create_label() {
+        let (gl, mock) = mock_gitlab();
+        let mut response = Response::json(r##"{"id": 10, "name": "bug", "color": "#d9534f",
+                                               "description": null}"##);
+        response.status = hyper::status::StatusCode::Created;
+        mock.push_response(response);
+        let lister = gl.projects().id(::projects::ListingId::Id(TEST_PROJECT_ID)).labels();
+
+        let label = lister.create_label("bug", "#d9534f").unwrap();
+        assert_eq!(label.id, Some(10));
+
+        let requests = mock.requests();
+        assert_eq!(requests[0].method, hyper::method::Method::Post);
+        assert_eq!(requests[0].body,
+                   Some(String::from(r##"{"name":"bug","color":"#d9534f"}"##)));
+    }
+
+
+    #[test]
+    fn create_label_malformed_color() {
+        let (gl, mock) = mock_gitlab();
+        let lister = gl.projects().id(::projects::ListingId::Id(TEST_PROJECT_ID)).labels();
+
+        let error = lister.create_label("bug", "red").unwrap_err();
+        assert!(error.to_string().contains("invalid color 'red'"));
+        let error = lister.create_label("bug", "#d9534").unwrap_err();
+        assert!(error.to_string().contains("#RRGGBB"));
+
+        // Nothing was sent.
+        assert!(mock.requests().is_empty());
+    }
+
+
+    #[test]
+    fn deserialize_labels() {
+        let json_reply = r##"[
+            {
+                "id": 1,
+                "name": "bug",
+                "color": "#d9534f",
+                "text_color": "#FFFFFF",
+                "description": "Bug reported by user",
+                "open_issues_count": 1,
+                "closed_issues_count": 0,
+                "open_merge_requests_count": 1,
+                "subscribed": false,
+                "priority": 10
+            },
+            {
+                "id": 4,
+                "name": "enhancement",
+                "color": "#5cb85c",
+                "description": null
+            }
+        ]"##;
+
+        let labels: ProjectLabels = serde_json::from_str(json_reply)
+            .expect("JSON deserialization failed");
+        assert_eq!(labels.len(), 2);
+        assert_eq!(labels[0].name, "bug");
+        assert_eq!(labels[0].description, Some(String::from("Bug reported by user")));
+        assert_eq!(labels[1].color, "#5cb85c");
+        assert_eq!(labels[1].text_color, None);
+    }
+}
@@ -0,0 +1,255 @@
+//! List, add and remove project members
+//!
+//! https://docs.gitlab.com/ce/api/members.html
+//!
+//! # List all members of a project
+//!
+//! Gets a list of project members viewable by the authenticated user.
+//!
+//! ```text
+//! GET /projects/ID/members
+//! ```
+//!
+//! | Attribute | Type | Required | Description |
+//! | --------- | ---- | -------- | ----------- |
+//! | `id` | integer/string | yes | The ID or `NAMESPACE/PROJECT_NAME` of the project |
+//!
+//! # Add a member to a project
+//!
+//! Adds a member to a project.
+//!
+//! ```text
+//! POST /projects/ID/members
+//! ```
+//!
+//! | Attribute | Type | Required | Description |
+//! | --------- | ---- | -------- | ----------- |
+//! | `id` | integer/string | yes | The ID or `NAMESPACE/PROJECT_NAME` of the project |
+//! | `user_id` | integer | yes | The user ID of the new member |
+//! | `access_level` | integer | yes | A valid access level (see `AccessLevel`) |
+//!
+//! # Remove a member from a project
+//!
+//! Removes a user from a project.
+//!
+//! ```text
+//! DELETE /projects/ID/members/USER_ID
+//! ```
+//!
+//! | Attribute | Type | Required | Description |
+//! | --------- | ---- | -------- | ----------- |
+//! | `id` | integer/string | yes | The ID or `NAMESPACE/PROJECT_NAME` of the project |
+//! | `user_id` | integer | yes | The user ID of the member |
+
+
+use BuildQuery;
+
+use ::errors::*;
+
+
+/// A member of a project: a user with an access level.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Member {
+    /// The user's ID
+    pub id: i64,
+    pub username: String,
+    pub name: String,
+    pub access_level: ::AccessLevel,
+    /// `YYYY-MM-DD`, `None` if the membership never expires
+    pub expires_at: Option<String>,
+}
+
+pub type Members = Vec<Member>;
+
+
+#[derive(Debug, Serialize)]
+struct MemberCreate {
+    user_id: i64,
+    access_level: ::AccessLevel,
+}
+
+
+#[derive(Debug, Clone)]
+pub struct MembersLister<'a> {
+    gl: &'a ::GitLab,
+    id: ::projects::ListingId,
+}
+
+
+impl<'a> MembersLister<'a> {
+    pub fn new(gl: &'a ::GitLab, id: ::projects::ListingId) -> MembersLister {
+        MembersLister { gl: gl, id: id }
+    }
+
+
+    /// Commit the lister: Query GitLab and return a list of members.
+    pub fn list(&self) -> Result<Members> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl.get_listing(&query).chain_err(|| format!("cannot get query {}", query))
+    }
+
+    /// Add the user `user_id` to the project, with the permissions of `access_level`.
+    ///
+    /// Returns the new member.
+    pub fn add_member(&self, user_id: i64, access_level: ::AccessLevel) -> Result<Member> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        let member = MemberCreate {
+            user_id: user_id,
+            access_level: access_level,
+        };
+        self.gl.post(&query, member).chain_err(|| format!("cannot post query {}", query))
+    }
+
+    /// Remove the user `user_id` from the project.
+    pub fn remove_member(&self, user_id: i64) -> Result<()> {
+        let query = self.build_member_query(user_id);
+        debug!("query: {:?}", query);
+
+        self.gl.delete(&query).chain_err(|| format!("cannot delete query {}", query))
+    }
+
+    fn build_member_query(&self, user_id: i64) -> String {
+        format!("{}/{}", self.build_query(), user_id)
+    }
+}
+
+
+impl<'a> BuildQuery for MembersLister<'a> {
+    fn build_query(&self) -> String {
+        let mut query = String::from("projects/");
+
+        query.push_str(&self.id.encode());
+        query.push_str("/members");
+
+        query
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use serde_json;
+
+    use BuildQuery;
+    use projects::id_members::{MemberCreate, Members};
+    use tests::mock_gitlab;
+    use transport::Response;
+
+    const TEST_PROJECT_ID: i64 = 123;
+    const TEST_PROJECT_NAME: &'static str = "group/project";
+
+
+    #[test]
+    fn build_query_default() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+        // let gl: ::GitLab = Default::default();
+
+        let expected_string = format!("projects/{}/members", TEST_PROJECT_ID);
+        let query = gl.projects()
+            .id(::projects::ListingId::Id(TEST_PROJECT_ID))
+            .members()
+            .build_query();
+        assert_eq!(query, expected_string);
+
+        let expected_string = "projects/group%2Fproject/members";
+        let query = gl.projects()
+            .id(::projects::ListingId::NamespaceProject(TEST_PROJECT_NAME.to_string()))
+            .members()
+            .build_query();
+        assert_eq!(query, expected_string);
+    }
+
+
+    #[test]
+    fn build_member_query() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+
+        let expected_string = format!("projects/{}/members/42", TEST_PROJECT_ID);
+        let query = gl.projects()
+            .id(::projects::ListingId::Id(TEST_PROJECT_ID))
+            .members()
+            .build_member_query(42);
+        assert_eq!(query, expected_string);
+    }
+
+
+    #[test]
+    fn serialize_member_create() {
+        let levels = [(::AccessLevel::Guest, 10),
+                      (::AccessLevel::Reporter, 20),
+                      (::AccessLevel::Developer, 30),
+                      (::AccessLevel::Master, 40),
+                      (::AccessLevel::Owner, 50)];
+        for &(access_level, value) in &levels {
+            let member = MemberCreate {
+                user_id: 42,
+                access_level: access_level,
+            };
+            assert_eq!(serde_json::to_string(&member).unwrap(),
+                       format!(r#"{{"user_id":42,"access_level":{}}}"#, value));
+        }
+    }
+
+
+    #[test]
+    fn add_member() {
+        let (gl, mock) = mock_gitlab();
+        mock.push_response(Response::json(r#"{"id": 42, "username": "jdoe", "name": "John Doe",
+                                              "state": "active", "access_level": 30,
+                                              "expires_at": null}"#));
+
+        let member = gl.projects()
+            .id(::projects::ListingId::Id(TEST_PROJECT_ID))
+            .members()
+            .add_member(42, ::AccessLevel::Developer)
+            .unwrap();
+        assert_eq!(member.access_level, ::AccessLevel::Developer);
+
+        let requests = mock.requests();
+        assert_eq!(requests[0].method, ::hyper::method::Method::Post);
+        assert_eq!(requests[0].body,
+                   Some(String::from(r#"{"user_id":42,"access_level":30}"#)));
+    }
+
+
+    #[test]
+    fn deserialize_members() {
+        let json_reply = r#"[
+            {
+                "id": 1,
+                "username": "raymond_smith",
+                "name": "Raymond Smith",
+                "state": "active",
+                "avatar_url": "https://www.gravatar.com/avatar/c2525a7f58ae?s=80&d=identicon",
+                "web_url": "http://192.168.1.8:3000/root",
+                "expires_at": "2012-10-22",
+                "access_level": 30
+            },
+            {
+                "id": 2,
+                "username": "john_doe",
+                "name": "John Doe",
+                "state": "active",
+                "avatar_url": "https://www.gravatar.com/avatar/c2525a7f58ae?s=80&d=identicon",
+                "web_url": "http://192.168.1.8:3000/root",
+                "expires_at": null,
+                "access_level": 50
+            }
+        ]"#;
+
+        let members: Members = serde_json::from_str(json_reply)
+            .expect("JSON deserialization failed");
+        assert_eq!(members[0].username, "raymond_smith");
+        assert_eq!(members[0].access_level, ::AccessLevel::Developer);
+        assert_eq!(members[0].expires_at, Some(String::from("2012-10-22")));
+        assert_eq!(members[1].access_level, ::AccessLevel::Owner);
+        assert_eq!(members[1].expires_at, None);
+
+        let json_reply = json_reply.replace("\"access_level\": 50", "\"access_level\": 35");
+        assert!(serde_json::from_str::<Members>(&json_reply).is_err());
+    }
+}
@@ -0,0 +1,232 @@
+//! Get and update a project's notification settings
+//!
+//! https://docs.gitlab.com/ce/api/notification_settings.html
+//!
+//! # Project notification settings
+//!
+//! Get the authenticated user's notification settings for a project.
+//!
+//! ```text
+//! GET /projects/ID/notification_settings
+//! ```
+//!
+//! | Attribute | Type | Required | Description |
+//! | --------- | ---- | -------- | ----------- |
+//! | `id` | integer/string | yes | The ID or `NAMESPACE/PROJECT_NAME` of the project |
+//!
+//! # Update project notification settings
+//!
+//! Update the authenticated user's notification settings for a project.
+//!
+//! ```text
+//! PUT /projects/ID/notification_settings
+//! ```
+//!
+//! | Attribute | Type | Required | Description |
+//! | --------- | ---- | -------- | ----------- |
+//! | `id` | integer/string | yes | The ID or `NAMESPACE/PROJECT_NAME` of the project |
+//! | `level` | string | no | The notification level (see `NotificationLevel`) |
+//! | `new_note`, `new_issue`, ... | boolean | no | Enable or disable the notification of the event, for the `custom` level |
+//!
+//! The events are only returned (and only taken into account) for the `custom` level.
+
+
+use std::collections::BTreeMap;
+
+use serde_json;
+
+use BuildQuery;
+
+use ::errors::*;
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum NotificationLevel {
+    #[serde(rename = "disabled")]
+    Disabled,
+    #[serde(rename = "participating")]
+    Participating,
+    #[serde(rename = "watch")]
+    Watch,
+    #[serde(rename = "global")]
+    Global,
+    #[serde(rename = "mention")]
+    Mention,
+    #[serde(rename = "custom")]
+    Custom,
+}
+
+
+/// A user's notification settings, for a project.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NotificationSettings {
+    pub level: NotificationLevel,
+    /// Whether each event (`new_note`, `new_issue`, `merge_merge_request`, ...) is notified,
+    /// only for the `Custom` level
+    pub events: Option<BTreeMap<String, bool>>,
+}
+
+
+impl NotificationSettings {
+    /// The parameters of the update to `self`: the level and the events, if any.
+    fn update_body(&self) -> BTreeMap<String, serde_json::Value> {
+        let level = match self.level {
+            NotificationLevel::Disabled => "disabled",
+            NotificationLevel::Participating => "participating",
+            NotificationLevel::Watch => "watch",
+            NotificationLevel::Global => "global",
+            NotificationLevel::Mention => "mention",
+            NotificationLevel::Custom => "custom",
+        };
+
+        let mut body = BTreeMap::new();
+        body.insert(String::from("level"), serde_json::Value::String(String::from(level)));
+        if let Some(ref events) = self.events {
+            for (event, &enabled) in events {
+                body.insert(event.clone(), serde_json::Value::Bool(enabled));
+            }
+        }
+
+        body
+    }
+}
+
+
+#[derive(Debug, Clone)]
+pub struct NotificationSettingsLister<'a> {
+    gl: &'a ::GitLab,
+    id: ::projects::ListingId,
+}
+
+
+impl<'a> NotificationSettingsLister<'a> {
+    pub fn new(gl: &'a ::GitLab, id: ::projects::ListingId) -> NotificationSettingsLister {
+        NotificationSettingsLister { gl: gl, id: id }
+    }
+
+
+    /// Commit the lister: Query GitLab and return the notification settings.
+    pub fn list(&self) -> Result<NotificationSettings> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl.get(&query, None, None).chain_err(|| format!("cannot get query {}", query))
+    }
+
+    /// Replace the notification settings by `settings`.
+    ///
+    /// Returns the updated settings.
+    pub fn update(&self, settings: &NotificationSettings) -> Result<NotificationSettings> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl
+            .put(&query, settings.update_body())
+            .chain_err(|| format!("cannot put query {}", query))
+    }
+}
+
+
+impl<'a> BuildQuery for NotificationSettingsLister<'a> {
+    fn build_query(&self) -> String {
+        let mut query = String::from("projects/");
+
+        query.push_str(&self.id.encode());
+        query.push_str("/notification_settings");
+
+        query
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use serde_json;
+
+    use BuildQuery;
+    use projects::id_notification_settings::{NotificationLevel, NotificationSettings};
+    use tests::mock_gitlab;
+    use transport::Response;
+
+    const TEST_PROJECT_ID: i64 = 123;
+    const TEST_PROJECT_NAME: &'static str = "group/project";
+
+
+    #[test]
+    fn build_query_default() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+        // let gl: ::GitLab = Default::default();
+
+        let expected_string = format!("projects/{}/notification_settings", TEST_PROJECT_ID);
+        let query = gl.projects()
+            .id(::projects::ListingId::Id(TEST_PROJECT_ID))
+            .notification_settings()
+            .build_query();
+        assert_eq!(query, expected_string);
+
+        let expected_string = "projects/group%2Fproject/notification_settings";
+        let query = gl.projects()
+            .id(::projects::ListingId::NamespaceProject(TEST_PROJECT_NAME.to_string()))
+            .notification_settings()
+            .build_query();
+        assert_eq!(query, expected_string);
+    }
+
+
+    #[test]
+    fn deserialize_notification_settings() {
+        let settings: NotificationSettings = serde_json::from_str(r#"{"level": "watch"}"#)
+            .expect("JSON deserialization failed");
+        assert_eq!(settings.level, NotificationLevel::Watch);
+        assert_eq!(settings.events, None);
+
+        let json_reply = r#"{
+            "level": "custom",
+            "events": {"new_note": false, "new_issue": true, "failed_pipeline": true}
+        }"#;
+        let settings: NotificationSettings = serde_json::from_str(json_reply)
+            .expect("JSON deserialization failed");
+        assert_eq!(settings.level, NotificationLevel::Custom);
+        let events = settings.events.unwrap();
+        assert_eq!(events.get("new_note"), Some(&false));
+        assert_eq!(events.get("failed_pipeline"), Some(&true));
+
+        assert!(serde_json::from_str::<NotificationSettings>(r#"{"level": "loud"}"#).is_err());
+    }
+
+
+    #[test]
+    fn update() {
+        let (gl, mock) = mock_gitlab();
+        mock.push_response(Response::json(r#"{"level": "mention"}"#));
+        mock.push_response(Response::json(r#"{"level": "custom",
+                                              "events": {"new_note": false, "new_issue": true}}"#));
+        let lister = gl.projects()
+            .id(::projects::ListingId::Id(TEST_PROJECT_ID))
+            .notification_settings();
+
+        let settings = NotificationSettings {
+            level: NotificationLevel::Mention,
+            events: None,
+        };
+        assert_eq!(lister.update(&settings).unwrap(), settings);
+
+        // The events are sent as top-level parameters.
+        let mut events = BTreeMap::new();
+        events.insert(String::from("new_note"), false);
+        events.insert(String::from("new_issue"), true);
+        let settings = NotificationSettings {
+            level: NotificationLevel::Custom,
+            events: Some(events),
+        };
+        assert_eq!(lister.update(&settings).unwrap(), settings);
+
+        let requests = mock.requests();
+        assert_eq!(requests[0].method, ::hyper::method::Method::Put);
+        assert_eq!(requests[0].body, Some(String::from(r#"{"level":"mention"}"#)));
+        assert_eq!(requests[1].body,
+                   Some(String::from(r#"{"level":"custom","new_issue":true,"new_note":false}"#)));
+    }
+}
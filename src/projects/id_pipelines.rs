@@ -0,0 +1,187 @@
+//! List project pipelines
+//!
+//! https://docs.gitlab.com/ce/api/pipelines.html#list-project-pipelines
+//!
+//! # List project pipelines
+//!
+//! ```text
+//! GET /projects/ID/pipelines
+//! ```
+//!
+//! | Attribute | Type | Required | Description |
+//! | --------- | ---- | -------- | ----------- |
+//! | `id` | integer/string | yes | The ID or `NAMESPACE/PROJECT_NAME` of the project |
+//! | `status` | string | no | The status of pipelines, one of: `running`, `pending`, `success`, `failed`, `canceled`, `skipped` |
+
+
+use serde_urlencoded;
+
+use BuildQuery;
+use Lister;
+use PageInfo;
+
+use ::errors::*;
+
+
+pub type Pipelines = Vec<::merge_requests::PipelineSummary>;
+
+
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub enum PipelineStatus {
+    #[serde(rename = "running")]
+    Running,
+    #[serde(rename = "pending")]
+    Pending,
+    #[serde(rename = "success")]
+    Success,
+    #[serde(rename = "failed")]
+    Failed,
+    #[serde(rename = "canceled")]
+    Canceled,
+    #[serde(rename = "skipped")]
+    Skipped,
+}
+
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct PipelinesListerInternal {
+    /// Limit by status.
+    status: Option<PipelineStatus>,
+}
+
+
+#[derive(Debug, Clone)]
+pub struct PipelinesLister<'a> {
+    gl: &'a ::GitLab,
+    id: ::projects::ListingId,
+    internal: PipelinesListerInternal,
+}
+
+
+impl<'a> Lister<Pipelines> for PipelinesLister<'a> {
+    /// Commit the lister: Query GitLab and return a list of pipelines.
+    fn list(&self) -> Result<Pipelines> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl.get_listing(&query).chain_err(|| format!("cannot get query {}", query))
+    }
+
+    fn list_paginated(&self, page: u16, per_page: u16) -> Result<Pipelines> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl.get(&query, page, per_page).chain_err(|| format!("cannot get query {}", query))
+    }
+
+    /// Commit the lister: Query GitLab and return a page of pipelines with its pagination
+    /// information.
+    fn list_page(&self, page: u16, per_page: u16) -> Result<(Pipelines, PageInfo)> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl
+            .get_with_headers(&query, page, per_page)
+            .chain_err(|| format!("cannot get query {}", query))
+    }
+}
+
+
+impl<'a> PipelinesLister<'a> {
+    pub fn new(gl: &'a ::GitLab, id: ::projects::ListingId) -> PipelinesLister {
+        PipelinesLister {
+            gl: gl,
+            id: id,
+            internal: PipelinesListerInternal { status: None },
+        }
+    }
+
+    pub fn status(&'a mut self, status: PipelineStatus) -> &'a mut PipelinesLister {
+        self.internal.status = Some(status);
+        self
+    }
+}
+
+
+impl<'a> BuildQuery for PipelinesLister<'a> {
+    fn build_query(&self) -> String {
+        let mut query = String::from("projects/");
+
+        query.push_str(&self.id.encode());
+        query.push_str("/pipelines");
+
+        let encoded = serde_urlencoded::to_string(&self.internal).unwrap();
+        if !encoded.is_empty() {
+            query.push_str("?");
+            query.push_str(&encoded);
+        }
+
+        query
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use BuildQuery;
+    use Lister;
+    use projects::id_pipelines::PipelineStatus;
+    use tests::mock_gitlab;
+    use transport::Response;
+
+    const TEST_PROJECT_ID: i64 = 123;
+
+
+    #[test]
+    fn build_query_default() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+
+        let expected_string = format!("projects/{}/pipelines", TEST_PROJECT_ID);
+        let query = gl.projects()
+            .id(::projects::ListingId::Id(TEST_PROJECT_ID))
+            .pipelines()
+            .build_query();
+        assert_eq!(query, expected_string);
+    }
+
+
+    #[test]
+    fn build_query_status() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+
+        let expected_string = format!("projects/{}/pipelines?status=success", TEST_PROJECT_ID);
+        let mut lister = gl.projects().id(::projects::ListingId::Id(TEST_PROJECT_ID)).pipelines();
+        let query = lister.status(PipelineStatus::Success).build_query();
+        assert_eq!(query, expected_string);
+
+        let expected_string = format!("projects/{}/pipelines?status=canceled", TEST_PROJECT_ID);
+        let mut lister = gl.projects().id(::projects::ListingId::Id(TEST_PROJECT_ID)).pipelines();
+        let query = lister.status(PipelineStatus::Canceled).build_query();
+        assert_eq!(query, expected_string);
+    }
+
+
+    #[test]
+    fn iter() {
+        let (gl, mock) = mock_gitlab();
+        let pipeline = |id| {
+            format!(r#"{{"id": {}, "status": "success", "ref": "master", "sha": "a91957a8"}}"#,
+                    id)
+        };
+        let mut response = Response::json(&format!("[{}, {}]", pipeline(47), pipeline(46)));
+        response.headers.set_raw("X-Next-Page", vec![b"2".to_vec()]);
+        mock.push_response(response);
+        mock.push_response(Response::json(&format!("[{}]", pipeline(45))));
+
+        let mut lister = gl.projects().id(::projects::ListingId::Id(TEST_PROJECT_ID)).pipelines();
+        lister.status(PipelineStatus::Success);
+        let ids: Vec<i64> = lister.paginated_iter(2).map(|pipeline| pipeline.unwrap().id).collect();
+        assert_eq!(ids, vec![47, 46, 45]);
+
+        let requests = mock.requests();
+        assert_eq!(requests.len(), 2);
+        assert!(requests[0].url.contains("/projects/123/pipelines?status=success&"));
+        assert!(requests[0].url.ends_with("&page=1&per_page=2"));
+        assert!(requests[1].url.ends_with("&page=2&per_page=2"));
+    }
+}
@@ -0,0 +1,293 @@
+//! List container registry repositories and delete their tags
+//!
+//! https://docs.gitlab.com/ce/api/container_registry.html
+//!
+//! # List registry repositories
+//!
+//! Get a list of registry repositories in a project. The container registry must be enabled for
+//! the project (see `Project::container_registry_enabled`).
+//!
+//! ```text
+//! GET /projects/ID/registry/repositories
+//! ```
+//!
+//! | Attribute | Type | Required | Description |
+//! | --------- | ---- | -------- | ----------- |
+//! | `id` | integer/string | yes | The ID or `NAMESPACE/PROJECT_NAME` of the project |
+//!
+//! # Delete a registry repository tag
+//!
+//! Delete a registry repository tag.
+//!
+//! ```text
+//! DELETE /projects/ID/registry/repositories/REPOSITORY_ID/tags/TAG_NAME
+//! ```
+//!
+//! | Attribute | Type | Required | Description |
+//! | --------- | ---- | -------- | ----------- |
+//! | `id` | integer/string | yes | The ID or `NAMESPACE/PROJECT_NAME` of the project |
+//! | `repository_id` | integer | yes | The ID of registry repository |
+//! | `tag_name` | string | yes | The name of tag |
+//!
+//! # Delete registry repository tags in bulk
+//!
+//! Delete registry repository tags in bulk based on given criteria. The tags are deleted
+//! asynchronously by GitLab, which answers `202 Accepted`.
+//!
+//! ```text
+//! DELETE /projects/ID/registry/repositories/REPOSITORY_ID/tags
+//! ```
+//!
+//! | Attribute | Type | Required | Description |
+//! | --------- | ---- | -------- | ----------- |
+//! | `id` | integer/string | yes | The ID or `NAMESPACE/PROJECT_NAME` of the project |
+//! | `repository_id` | integer | yes | The ID of registry repository |
+//! | `name_regex` | string | yes | The regex of the name to delete. To delete all tags specify `.*` |
+//! | `keep_n` | integer | no | The amount of latest tags of given name to keep |
+//! | `older_than` | string | no | Tags to delete that are older than the given time, written in human readable form `1h`, `1d`, `1month` |
+
+
+use serde_urlencoded;
+
+use BuildQuery;
+
+use ::errors::*;
+
+
+/// A repository of the project's container registry, holding the tags of an image.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RegistryRepository {
+    pub id: i64,
+    /// Empty for the repository named after the project
+    pub name: String,
+    /// For example `group/project/image`
+    pub path: String,
+    /// The image's location, for example `registry.example.com/group/project/image`
+    pub location: String,
+}
+
+pub type RegistryRepositories = Vec<RegistryRepository>;
+
+
+/// The criteria of the tags to delete with `delete_registry_tags_in_bulk()`.
+#[derive(Debug, Clone, Serialize)]
+pub struct TagsBulkDeletion {
+    /// The regex the names of the tags to delete must match, `.*` for all tags
+    pub name_regex: String,
+    /// The number of latest tags (matching `name_regex`) to keep
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub keep_n: Option<u32>,
+    /// Only delete the tags older than this, for example `1d` or `1month`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub older_than: Option<String>,
+}
+
+
+impl TagsBulkDeletion {
+    pub fn new(name_regex: String) -> TagsBulkDeletion {
+        TagsBulkDeletion {
+            name_regex: name_regex,
+            keep_n: None,
+            older_than: None,
+        }
+    }
+}
+
+
+#[derive(Debug, Clone)]
+pub struct RegistryRepositoriesLister<'a> {
+    gl: &'a ::GitLab,
+    id: ::projects::ListingId,
+}
+
+
+impl<'a> RegistryRepositoriesLister<'a> {
+    pub fn new(gl: &'a ::GitLab, id: ::projects::ListingId) -> RegistryRepositoriesLister {
+        RegistryRepositoriesLister { gl: gl, id: id }
+    }
+
+
+    /// Commit the lister: Query GitLab and return a list of registry repositories.
+    pub fn list(&self) -> Result<RegistryRepositories> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl.get_listing(&query).chain_err(|| format!("cannot get query {}", query))
+    }
+
+    /// Delete the tag `tag_name` of the registry repository `repository_id`.
+    pub fn delete_registry_tag(&self, repository_id: i64, tag_name: &str) -> Result<()> {
+        let query = self.build_tag_query(repository_id, tag_name);
+        debug!("query: {:?}", query);
+
+        self.gl.delete(&query).chain_err(|| format!("cannot delete query {}", query))
+    }
+
+    /// Delete the tags of the registry repository `repository_id` matching `deletion`.
+    ///
+    /// GitLab deletes the tags in the background: they might still be listed right after this
+    /// call returns.
+    pub fn delete_registry_tags_in_bulk(&self,
+                                        repository_id: i64,
+                                        deletion: &TagsBulkDeletion)
+                                        -> Result<()> {
+        let query = self.build_tags_bulk_query(repository_id, deletion);
+        debug!("query: {:?}", query);
+
+        self.gl.delete(&query).chain_err(|| format!("cannot delete query {}", query))
+    }
+
+    fn build_tags_query(&self, repository_id: i64) -> String {
+        format!("{}/{}/tags", self.build_query(), repository_id)
+    }
+
+    fn build_tag_query(&self, repository_id: i64, tag_name: &str) -> String {
+        format!("{}/{}",
+                self.build_tags_query(repository_id),
+                ::id::encode_path_segment(tag_name))
+    }
+
+    fn build_tags_bulk_query(&self, repository_id: i64, deletion: &TagsBulkDeletion) -> String {
+        let encoded = serde_urlencoded::to_string(deletion).unwrap();
+
+        format!("{}?{}", self.build_tags_query(repository_id), encoded)
+    }
+}
+
+
+impl<'a> BuildQuery for RegistryRepositoriesLister<'a> {
+    fn build_query(&self) -> String {
+        let mut query = String::from("projects/");
+
+        query.push_str(&self.id.encode());
+        query.push_str("/registry/repositories");
+
+        query
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use hyper;
+    use serde_json;
+
+    use BuildQuery;
+    use projects::id_registry::{RegistryRepositories, TagsBulkDeletion};
+    use tests::mock_gitlab;
+    use transport::Response;
+
+    const TEST_PROJECT_ID: i64 = 123;
+    const TEST_PROJECT_NAME: &'static str = "group/project";
+
+
+    #[test]
+    fn build_query_default() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+        // let gl: ::GitLab = Default::default();
+
+        let expected_string = format!("projects/{}/registry/repositories", TEST_PROJECT_ID);
+        let query = gl.projects()
+            .id(::projects::ListingId::Id(TEST_PROJECT_ID))
+            .registry_repositories()
+            .build_query();
+        assert_eq!(query, expected_string);
+
+        let expected_string = "projects/group%2Fproject/registry/repositories";
+        let query = gl.projects()
+            .id(::projects::ListingId::NamespaceProject(TEST_PROJECT_NAME.to_string()))
+            .registry_repositories()
+            .build_query();
+        assert_eq!(query, expected_string);
+    }
+
+
+    #[test]
+    fn build_tag_query() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+        let lister = gl.projects()
+            .id(::projects::ListingId::NamespaceProject(TEST_PROJECT_NAME.to_string()))
+            .registry_repositories();
+
+        let expected_string = "projects/group%2Fproject/registry/repositories/2/tags/v1.0";
+        assert_eq!(lister.build_tag_query(2, "v1.0"), expected_string);
+
+        let expected_string = "projects/group%2Fproject/registry/repositories/2/tags/\
+                               feature%2Fnew%20thing";
+        assert_eq!(lister.build_tag_query(2, "feature/new thing"), expected_string);
+    }
+
+
+    #[test]
+    fn build_tags_bulk_query() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+        let lister = gl.projects()
+            .id(::projects::ListingId::Id(TEST_PROJECT_ID))
+            .registry_repositories();
+
+        let deletion = TagsBulkDeletion::new(String::from(".*"));
+        let expected_string = format!("projects/{}/registry/repositories/2/tags?name_regex=.*",
+                                      TEST_PROJECT_ID);
+        assert_eq!(lister.build_tags_bulk_query(2, &deletion), expected_string);
+
+        let deletion = TagsBulkDeletion {
+            name_regex: String::from("^dev-[0-9a-f]+$"),
+            keep_n: Some(5),
+            older_than: Some(String::from("1month")),
+        };
+        let expected_string = format!("projects/{}/registry/repositories/2/tags\
+                                       ?name_regex=%5Edev-%5B0-9a-f%5D%2B%24&keep_n=5\
+                                       &older_than=1month",
+                                      TEST_PROJECT_ID);
+        assert_eq!(lister.build_tags_bulk_query(2, &deletion), expected_string);
+    }
+
+
+    #[test]
+    fn delete_registry_tags_in_bulk() {
+        let (gl, mock) = mock_gitlab();
+        mock.push_response(Response::new(hyper::status::StatusCode::Accepted));
+
+        let mut deletion = TagsBulkDeletion::new(String::from(".*"));
+        deletion.keep_n = Some(5);
+        gl.projects()
+            .id(::projects::ListingId::Id(TEST_PROJECT_ID))
+            .registry_repositories()
+            .delete_registry_tags_in_bulk(2, &deletion)
+            .unwrap();
+
+        let requests = mock.requests();
+        assert_eq!(requests[0].method, hyper::method::Method::Delete);
+        assert!(requests[0]
+            .url
+            .starts_with("https://gitlab.example.com/api/v3/projects/123/registry/repositories/2\
+                          /tags?name_regex=.*&keep_n=5&private_token="));
+    }
+
+
+    #[test]
+    fn deserialize_registry_repositories() {
+        let json_reply = r##"[
+            {
+                "id": 1,
+                "name": "",
+                "path": "group/project",
+                "location": "gitlab.example.com:5000/group/project"
+            },
+            {
+                "id": 2,
+                "name": "releases",
+                "path": "group/project/releases",
+                "location": "gitlab.example.com:5000/group/project/releases"
+            }
+        ]"##;
+
+        let repositories: RegistryRepositories = serde_json::from_str(json_reply)
+            .expect("JSON deserialization failed");
+        assert_eq!(repositories.len(), 2);
+        assert_eq!(repositories[0].name, "");
+        assert_eq!(repositories[1].path, "group/project/releases");
+        assert_eq!(repositories[1].location,
+                   "gitlab.example.com:5000/group/project/releases");
+    }
+}
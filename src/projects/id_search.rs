@@ -0,0 +1,199 @@
+//! Search within a project
+//!
+//! https://docs.gitlab.com/ce/api/search.html#project-search-api
+//!
+//! # Project search
+//!
+//! Search within the specified project.
+//!
+//! ```text
+//! GET /projects/ID/search
+//! ```
+//!
+//! Parameters:
+//!
+//! | Attribute | Type | Required | Description |
+//! | --------- | ---- | -------- | ----------- |
+//! | `id` | integer/string | yes | The ID or `NAMESPACE/PROJECT_NAME` of the project |
+//! | `scope` | string | yes | The scope to search in: `blobs` or `commits` |
+//! | `search` | string | yes | The search query |
+
+
+use serde_urlencoded;
+
+use BuildQuery;
+
+use projects::RepoCommits;
+
+use ::errors::*;
+
+
+/// What to search for.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum SearchScope {
+    /// Files content in the default branch
+    #[serde(rename = "blobs")]
+    Blobs,
+    #[serde(rename = "commits")]
+    Commits,
+}
+
+
+/// A file content match.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Blob {
+    pub basename: String,
+    /// The matching lines
+    pub data: String,
+    pub path: Option<String>,
+    pub filename: String,
+    pub id: Option<String>,
+    #[serde(rename = "ref")]
+    pub git_ref: String,
+    /// Line number of the first line of `data`
+    pub startline: i64,
+    pub project_id: i64,
+}
+
+pub type Blobs = Vec<Blob>;
+
+
+/// The results of a search, typed according to the `SearchScope`.
+#[derive(Debug)]
+pub enum SearchResults {
+    Blobs(Blobs),
+    Commits(RepoCommits),
+}
+
+
+#[derive(Debug, Clone, Serialize)]
+struct SearchListerInternal {
+    scope: SearchScope,
+    search: String,
+}
+
+
+#[derive(Debug, Clone)]
+pub struct SearchLister<'a> {
+    gl: &'a ::GitLab,
+    id: ::projects::ListingId,
+    internal: SearchListerInternal,
+}
+
+
+impl<'a> SearchLister<'a> {
+    pub fn new(gl: &'a ::GitLab,
+               id: ::projects::ListingId,
+               scope: SearchScope,
+               search: String)
+               -> SearchLister {
+        SearchLister {
+            gl: gl,
+            id: id,
+            internal: SearchListerInternal {
+                scope: scope,
+                search: search,
+            },
+        }
+    }
+
+
+    /// Commit the lister: Query GitLab and return the search results.
+    pub fn list(&self) -> Result<SearchResults> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        let results = match self.internal.scope {
+            SearchScope::Blobs => self.gl.get_listing(&query).map(SearchResults::Blobs),
+            SearchScope::Commits => self.gl.get_listing(&query).map(SearchResults::Commits),
+        };
+
+        results.chain_err(|| format!("cannot get query {}", query))
+    }
+}
+
+
+impl<'a> BuildQuery for SearchLister<'a> {
+    fn build_query(&self) -> String {
+        let mut query = String::from("projects/");
+
+        query.push_str(&self.id.encode());
+        query.push_str("/search?");
+        query.push_str(&serde_urlencoded::to_string(&self.internal).unwrap());
+
+        query
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use serde_json;
+
+    use BuildQuery;
+    use projects::id_search::{Blob, SearchScope};
+
+    const TEST_PROJECT_ID: i64 = 123;
+    const TEST_PROJECT_NAME: &'static str = "group/project";
+
+
+    #[test]
+    fn build_query_blobs() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+        // let gl: ::GitLab = Default::default();
+
+        let expected_string = format!("projects/{}/search?scope=blobs&search=fn+main",
+                                      TEST_PROJECT_ID);
+        let query = gl.projects()
+            .id(::projects::ListingId::Id(TEST_PROJECT_ID))
+            .search(SearchScope::Blobs, String::from("fn main"))
+            .build_query();
+        assert_eq!(query, expected_string);
+
+        let expected_string = "projects/group%2Fproject/search?scope=blobs&search=a%26b%3Dc";
+        let query = gl.projects()
+            .id(::projects::ListingId::NamespaceProject(TEST_PROJECT_NAME.to_string()))
+            .search(SearchScope::Blobs, String::from("a&b=c"))
+            .build_query();
+        assert_eq!(query, expected_string);
+    }
+
+
+    #[test]
+    fn build_query_commits() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+        // let gl: ::GitLab = Default::default();
+
+        let expected_string = format!("projects/{}/search?scope=commits&search=fix",
+                                      TEST_PROJECT_ID);
+        let query = gl.projects()
+            .id(::projects::ListingId::Id(TEST_PROJECT_ID))
+            .search(SearchScope::Commits, String::from("fix"))
+            .build_query();
+        assert_eq!(query, expected_string);
+    }
+
+
+    #[test]
+    fn deserialize_blobs() {
+        let json_reply = r##"[
+            {
+                "basename": "README",
+                "data": "```\n\n## Installation\n\nQuick start using the [pre-built",
+                "path": "README.md",
+                "filename": "README.md",
+                "id": null,
+                "ref": "master",
+                "startline": 46,
+                "project_id": 6
+            }
+        ]"##;
+
+        let blobs: Vec<Blob> = serde_json::from_str(json_reply)
+            .expect("JSON deserialization failed");
+        assert_eq!(blobs[0].filename, "README.md");
+        assert_eq!(blobs[0].git_ref, "master");
+        assert_eq!(blobs[0].startline, 46);
+        assert_eq!(blobs[0].id, None);
+    }
+}
@@ -20,6 +20,20 @@
 //! | `sort` | string | no | Return projects sorted in `asc` or `desc` order. Default is `desc` |
 //! | `search` | string | no | Return list of authorized projects matching the search criteria |
 //! | `simple` | boolean | no | Return only the ID, URL, name, and path of each project |
+//! | `topic` | string | no | Limit to projects tagged with the given topic (tag) |
+//! | `id_after` | integer | no | Limit to projects with IDs greater than the specified ID (API v4) |
+//! | `id_before` | integer | no | Limit to projects with IDs less than the specified ID (API v4) |
+//! | `last_activity_after` | datetime | no | Limit to projects with last activity after the specified time, in ISO 8601 format (`2019-03-15T08:00:00Z`) |
+//! | `last_activity_before` | datetime | no | Limit to projects with last activity before the specified time, in ISO 8601 format (`2019-03-15T08:00:00Z`) |
+//!
+//! To export all the projects without missing or repeating any when projects are created or
+//! deleted during the export, order them by ID and iterate with `id_after`:
+//!
+//! ```text
+//! GET /projects?order_by=id&sort=asc&id_after=1000
+//! ```
+//!
+//! passing the ID of the last project received as the next `id_after`.
 //!
 
 
@@ -27,19 +41,32 @@ use serde_urlencoded;
 
 use BuildQuery;
 use Lister;
+use PageInfo;
 
 
 pub mod all;
+pub mod create;
+pub mod id_approval_rules;
 pub mod id_branches;
+pub mod id_commit;
+pub mod id_commits;
 pub mod id_branch;
 pub mod id_events;
+pub mod id_forks;
 pub mod id_hooks_id;
 pub mod id_hooks;
+pub mod id_labels;
+pub mod id_members;
+pub mod id_notification_settings;
+pub mod id_pipelines;
+pub mod id_registry;
+pub mod id_search;
 pub mod id;
 pub mod owned;
 pub mod search;
 pub mod starred;
 pub mod visible;
+pub mod wikis;
 
 use ::errors::*;
 
@@ -92,6 +119,16 @@ struct ProjectListerInternal {
     search: Option<String>,
     /// Return only the ID, URL, name, and path of each project
     simple: Option<bool>,
+    /// Limit to projects tagged with the given topic (an entry of `Project::tag_list`).
+    topic: Option<String>,
+    /// Limit to projects with IDs greater than the specified ID.
+    id_after: Option<i64>,
+    /// Limit to projects with IDs less than the specified ID.
+    id_before: Option<i64>,
+    /// Limit to projects with last activity after the specified time (ISO 8601).
+    last_activity_after: Option<String>,
+    /// Limit to projects with last activity before the specified time (ISO 8601).
+    last_activity_before: Option<String>,
 }
 
 
@@ -119,14 +156,11 @@ struct SearchProjectListerInternal {
 
 
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub enum ListingId {
-    Id(i64),
-    NamespaceProject(String),
-}
+pub use id::ResourceId as ListingId;
 
 
 type AllProjectListerInternal = OwnedProjectListerInternal;
+type ForksProjectListerInternal = OwnedProjectListerInternal;
 
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -152,8 +186,10 @@ pub struct ProjectNamespace {
     pub name: String,
     pub path: String,
     pub owner_id: Option<i64>,  // FIXME: Why would a project not have this?
-    pub created_at: Option<String>,  // FIXME: Date instead?
-    pub updated_at: Option<String>,  // FIXME: Date instead?
+    #[serde(default, with = "::datetime::option")]
+    pub created_at: Option<::datetime::DateTime>,
+    #[serde(default, with = "::datetime::option")]
+    pub updated_at: Option<::datetime::DateTime>,
     pub description: Option<String>,
     pub avatar: Option<ProjectNamespaceAvatar>,
     pub membership_lock: Option<bool>,
@@ -195,6 +231,27 @@ pub struct ProjectPermissions {
     pub group_access: Option<ProjectAccess>,
 }
 
+/// Storage statistics of a project, only returned when asked for with `statistics=true`.
+///
+/// The sizes can exceed `i64::MAX` on huge instances, see `tolerant::deserialize_size()`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProjectStatistics {
+    pub commit_count: u64,
+    /// Total size used by the project, in bytes
+    #[serde(deserialize_with = "::tolerant::deserialize_size")]
+    pub storage_size: u64,
+    /// Size of the Git repository, in bytes
+    #[serde(deserialize_with = "::tolerant::deserialize_size")]
+    pub repository_size: u64,
+    #[serde(deserialize_with = "::tolerant::deserialize_size")]
+    pub lfs_objects_size: u64,
+}
+
+
+/// The languages of a project's repository, with their percentage of the code.
+pub type Languages = ::std::collections::BTreeMap<String, f64>;
+
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ProjectSharedWithGroup {
     pub group_id: i64,
@@ -217,6 +274,8 @@ pub struct Project {
     pub ssh_url_to_repo: String,
     pub http_url_to_repo: String,
     pub web_url: String,
+    /// The URL of the project's README file, `None` without README (API v4)
+    pub readme_url: Option<String>,
     // owner: Option<ProjectOwner>,  // FIXME: Why would a project not have an owner?
     pub name: String,
     pub name_with_namespace: String,
@@ -228,16 +287,21 @@ pub struct Project {
     pub wiki_enabled: Option<bool>,
     pub builds_enabled: Option<bool>,
     pub snippets_enabled: Option<bool>,
-    pub created_at: String,  // FIXME: Date instead?
-    pub last_activity_at: String,  // FIXME: Date instead?
-    pub shared_runners_enabled: bool,
+    #[serde(with = "::datetime")]
+    pub created_at: ::datetime::DateTime,
+    #[serde(with = "::datetime")]
+    pub last_activity_at: ::datetime::DateTime,
+    pub shared_runners_enabled: Option<bool>,
     pub lfs_enabled: Option<bool>,
+    /// Whether the project's email notifications are disabled (API v4)
+    pub emails_disabled: Option<bool>,
     pub creator_id: i64,
     pub namespace: ProjectNamespace,
     pub forked_from_project: Option<ProjectForkedFrom>,
     pub avatar_url: Option<String>,
     pub star_count: i64,
     pub forks_count: i64,
+    /// Number of open issues; not returned when the project's issues are disabled.
     pub open_issues_count: Option<i64>,
     pub runners_token: Option<String>,
     pub public_builds: Option<bool>,
@@ -247,10 +311,116 @@ pub struct Project {
     pub only_allow_merge_if_all_discussions_are_resolved: Option<bool>,  // FIXME: Is bool the proper type?
     pub approvals_before_merge: Option<i64>,
     pub permissions: Option<ProjectPermissions>,
+    pub statistics: Option<ProjectStatistics>,
 }
 
 pub type Projects = Vec<Project>;
 
+impl ::pagination::Identifiable for Project {
+    fn id(&self) -> i64 {
+        self.id
+    }
+}
+
+
+/// Body of a project update.
+///
+/// Only the fields set are sent to GitLab, the others are left unchanged.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ProjectUpdate {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_branch: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lfs_enabled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_access_enabled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shared_runners_enabled: Option<bool>,
+}
+
+
+/// The few fields of a project returned with `simple=true`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SimpleProject {
+    pub id: i64,
+    pub name: String,
+    pub path: String,
+    pub web_url: String,
+    pub name_with_namespace: Option<String>,
+    pub path_with_namespace: Option<String>,
+    pub star_count: Option<i64>,
+}
+
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RepoCommit {
+    pub id: String,
+    pub short_id: String,
+    pub title: String,
+    pub author_name: String,
+    pub author_email: String,
+    #[serde(with = "::datetime")]
+    pub created_at: ::datetime::DateTime,
+    pub message: String,
+    #[serde(default, with = "::datetime::option")]
+    pub committed_date: Option<::datetime::DateTime>,
+    #[serde(default, with = "::datetime::option")]
+    pub authored_date: Option<::datetime::DateTime>,
+    pub parent_ids: Option<Vec<String>>,
+    pub status: Option<String>,
+    /// Only sent for a single commit, by recent GitLab versions
+    pub stats: Option<id_commit::CommitStats>,
+}
+
+pub type RepoCommits = Vec<RepoCommit>;
+
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub enum CommitActionType {
+    #[serde(rename = "create")]
+    Create,
+    #[serde(rename = "update")]
+    Update,
+    #[serde(rename = "delete")]
+    Delete,
+    #[serde(rename = "move")]
+    Move,
+}
+
+
+/// A single file action, part of a `CommitCreate`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommitAction {
+    /// The action to perform.
+    pub action: CommitActionType,
+    /// Full path to the file. Ex. `lib/class.rb`
+    pub file_path: String,
+    /// Original full path to the file being moved. Only used by `CommitActionType::Move`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub previous_path: Option<String>,
+    /// File content, required for all actions except `CommitActionType::Delete`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+}
+
+
+/// Body of a commit creation request.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommitCreate {
+    /// The name of the branch to commit into.
+    // NOTE: The v3 API calls this parameter `branch_name`.
+    #[serde(rename = "branch_name")]
+    pub branch: String,
+    /// Commit message
+    pub commit_message: String,
+    /// Actions to commit as a batch.
+    pub actions: Vec<CommitAction>,
+}
+
 
 #[derive(Debug, Clone)]
 pub struct ProjectsLister<'a> {
@@ -265,7 +435,7 @@ impl<'a> Lister<::projects::Projects> for ProjectsLister<'a> {
         let query = self.build_query();
         debug!("query: {:?}", query);
 
-        self.gl.get(&query, None, None).chain_err(|| format!("cannot get query {}", query))
+        self.gl.get_listing(&query).chain_err(|| format!("cannot get query {}", query))
     }
 
     /// Commit the lister: Query GitLab and return a list of issues.
@@ -275,6 +445,16 @@ impl<'a> Lister<::projects::Projects> for ProjectsLister<'a> {
 
         self.gl.get(&query, page, per_page).chain_err(|| format!("cannot get query {}", query))
     }
+
+    /// Commit the lister: Query GitLab and return a page of projects with its pagination information.
+    fn list_page(&self, page: u16, per_page: u16) -> Result<(::projects::Projects, PageInfo)> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl
+            .get_with_headers(&query, page, per_page)
+            .chain_err(|| format!("cannot get query {}", query))
+    }
 }
 
 
@@ -289,6 +469,11 @@ impl<'a> ProjectsLister<'a> {
                 sort: None,
                 search: None,
                 simple: None,
+                topic: None,
+                id_after: None,
+                id_before: None,
+                last_activity_after: None,
+                last_activity_before: None,
             },
         }
     }
@@ -313,6 +498,11 @@ impl<'a> ProjectsLister<'a> {
         id::ProjectsLister::new(self.gl, id)
     }
 
+    /// Return a builder for a new project named `name`; call its `create()` to create it.
+    pub fn create(self, name: &str) -> create::ProjectCreator<'a> {
+        create::ProjectCreator::new(self.gl, name.to_string())
+    }
+
 
 
     pub fn archived(&'a mut self, archived: bool) -> &'a mut ProjectsLister {
@@ -335,6 +525,15 @@ impl<'a> ProjectsLister<'a> {
         self
     }
 
+    /// Return the projects with the most recent activity first.
+    ///
+    /// Shortcut for `order_by(ListingOrderBy::LastActivityAt)` and `sort(ListingSort::Desc)`.
+    pub fn most_recently_active(&'a mut self) -> &'a mut ProjectsLister {
+        self.internal.order_by = Some(ListingOrderBy::LastActivityAt);
+        self.internal.sort = Some(::ListingSort::Desc);
+        self
+    }
+
     pub fn search_pattern(&'a mut self, search: String) -> &'a mut ProjectsLister {
         self.internal.search = Some(search);
         self
@@ -344,6 +543,55 @@ impl<'a> ProjectsLister<'a> {
         self.internal.simple = Some(simple);
         self
     }
+
+    /// Only list the projects tagged with `tag` (called "topic" by GitLab).
+    pub fn with_tag(&'a mut self, tag: String) -> &'a mut ProjectsLister {
+        self.internal.topic = Some(tag);
+        self
+    }
+
+    /// Only list the projects whose ID is greater than `id_after` (API v4).
+    ///
+    /// Combined with `order_by(ListingOrderBy::Id)` and `sort(ListingSort::Asc)`, this allows
+    /// iterating over all the projects without the drift of offset pagination: pass the ID of the
+    /// last project received as the next `id_after`.
+    pub fn id_after(&'a mut self, id_after: i64) -> &'a mut ProjectsLister {
+        self.internal.id_after = Some(id_after);
+        self
+    }
+
+    /// Only list the projects whose ID is less than `id_before` (API v4).
+    pub fn id_before(&'a mut self, id_before: i64) -> &'a mut ProjectsLister {
+        self.internal.id_before = Some(id_before);
+        self
+    }
+
+    /// Only list the projects active after `date`.
+    pub fn last_activity_after(&'a mut self,
+                               date: ::datetime::DateTime)
+                               -> &'a mut ProjectsLister {
+        self.internal.last_activity_after = Some(::datetime::to_string(&date));
+        self
+    }
+
+    /// Only list the projects without activity since `date`: the dormant projects.
+    pub fn last_activity_before(&'a mut self,
+                                date: ::datetime::DateTime)
+                                -> &'a mut ProjectsLister {
+        self.internal.last_activity_before = Some(::datetime::to_string(&date));
+        self
+    }
+
+    /// Same as `list()` but `options` (timeout, retries, headers) override the `GitLab`'s
+    /// request options for this call.
+    pub fn list_with(&self, options: &::transport::RequestOptions) -> Result<::projects::Projects> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl
+            .get_with_options(&query, None, None, options)
+            .chain_err(|| format!("cannot get query {}", query))
+    }
 }
 
 impl<'a> BuildQuery for ProjectsLister<'a> {
@@ -377,7 +625,13 @@ impl<'a> Project {
 
 #[cfg(test)]
 mod tests {
+    use std::collections::HashMap;
+
     use BuildQuery;
+    use Lister;
+    use errors::Result;
+    use tests::mock_gitlab;
+    use transport::Response;
 
 
     #[test]
@@ -519,6 +773,28 @@ mod tests {
     }
 
 
+    #[test]
+    fn build_query_most_recently_active() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+        // let gl: ::GitLab = Default::default();
+
+        let expected_string = "projects?order_by=last_activity_at&sort=desc";
+        let mut projects_lister = gl.projects();
+        let query = projects_lister.most_recently_active().build_query();
+        assert_eq!(query, expected_string);
+        let query = gl.projects().most_recently_active().build_query();
+        assert_eq!(query, expected_string);
+
+        // `updated_at` and `last_activity_at` are two different GitLab fields.
+        let expected_string = "projects?order_by=updated_at&sort=desc";
+        let query = gl.projects()
+            .order_by(::projects::ListingOrderBy::UpdatedAt)
+            .sort(::ListingSort::Desc)
+            .build_query();
+        assert_eq!(query, expected_string);
+    }
+
+
     #[test]
     fn build_query_search() {
         let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
@@ -554,6 +830,119 @@ mod tests {
     }
 
 
+    #[test]
+    fn build_query_id_after_id_before() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+        // let gl: ::GitLab = Default::default();
+
+        let expected_string = "projects?id_after=1000";
+        let query = gl.projects().id_after(1000).build_query();
+        assert_eq!(query, expected_string);
+
+        let expected_string = "projects?id_before=2000";
+        let query = gl.projects().id_before(2000).build_query();
+        assert_eq!(query, expected_string);
+
+        let expected_string = "projects?order_by=id&sort=asc&id_after=1000&id_before=2000";
+        let query = gl.projects()
+            .id_before(2000)
+            .sort(::ListingSort::Asc)
+            .id_after(1000)
+            .order_by(::projects::ListingOrderBy::Id)
+            .build_query();
+        assert_eq!(query, expected_string);
+    }
+
+
+    #[test]
+    fn list() {
+        let (gl, mock) = mock_gitlab();
+        mock.push_response(Response::json(::tests::TEST_PROJECTS_JSON));
+
+        // `list()` queries GitLab, its failures are reported instead of an empty list.
+        let projects: Result<::projects::Projects> = gl.projects().list();
+        assert_eq!(projects.unwrap()[0].id, 517564);
+        assert!(gl.projects().list().is_err());
+
+        let requests = mock.requests();
+        assert_eq!(requests.len(), 2);
+        assert!(requests[0].url.starts_with("https://gitlab.example.com/api/v3/projects?"));
+    }
+
+
+    #[test]
+    fn collect_into() {
+        let project_json =
+            ::tests::test_project_json();
+        let with_id =
+            |id: i64| project_json.replace("\"id\": 517564,", &format!("\"id\": {},", id));
+        let (gl, mock) = mock_gitlab();
+        let mut response = Response::json(&format!("[{}, {}]", with_id(1), with_id(2)));
+        response.headers.set_raw("X-Next-Page", vec![b"2".to_vec()]);
+        mock.push_response(response);
+        mock.push_response(Response::json(&format!("[{}]", with_id(3))));
+        mock.push_response(Response::json(&format!("[{}]", with_id(4))));
+
+        let mut by_id: HashMap<i64, ::projects::Project> = HashMap::new();
+        gl.projects()
+            .collect_into_by(&mut by_id, |project: ::projects::Project| (project.id, project))
+            .unwrap();
+        assert_eq!(by_id.len(), 3);
+        assert_eq!(by_id[&2].id, 2);
+
+        let mut projects: ::projects::Projects = Vec::new();
+        gl.projects().collect_into(&mut projects).unwrap();
+        assert_eq!(projects.len(), 1);
+        assert_eq!(projects[0].id, 4);
+
+        assert_eq!(mock.requests().len(), 3);
+    }
+
+
+    #[test]
+    fn build_query_last_activity() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+        // let gl: ::GitLab = Default::default();
+
+        let expected_string = "projects?last_activity_after=2019-03-15T08%3A00%3A00.000Z";
+        let query = gl.projects()
+            .last_activity_after(::datetime::from_timestamp(1552636800).unwrap())
+            .build_query();
+        assert_eq!(query, expected_string);
+
+        let expected_string = "projects?archived=false\
+                               &last_activity_after=2018-01-01T00%3A00%3A00.000Z\
+                               &last_activity_before=2019-01-01T00%3A00%3A00.000Z";
+        let query = gl.projects()
+            .last_activity_before(::datetime::from_timestamp(1546300800).unwrap())
+            .archived(false)
+            .last_activity_after(::datetime::from_timestamp(1514764800).unwrap())
+            .build_query();
+        assert_eq!(query, expected_string);
+    }
+
+
+    #[test]
+    fn build_query_with_tag() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+
+        let expected_string = "projects?topic=rust";
+        let mut projects_lister = gl.projects();
+        let query = projects_lister.with_tag(String::from("rust")).build_query();
+        assert_eq!(query, expected_string);
+        let query = gl.projects().with_tag(String::from("rust")).build_query();
+        assert_eq!(query, expected_string);
+
+        let expected_string = "projects?topic=c%2B%2B+%26+rust";
+        let query = gl.projects().with_tag(String::from("c++ & rust")).build_query();
+        assert_eq!(query, expected_string);
+
+        let expected_string = "projects?archived=false&topic=rust";
+        let query = gl.projects().archived(false).with_tag(String::from("rust")).build_query();
+        assert_eq!(query, expected_string);
+    }
+
+
     #[test]
     fn groups_build_query_multiple() {
         let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
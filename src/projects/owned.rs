@@ -10,6 +10,9 @@
 //! GET /projects/owned
 //! ```
 //!
+//! The API v4 removed this endpoint: `GET /projects?owned=true` is queried instead (see
+//! `GitLab::api_version()`).
+//!
 //! Parameters:
 //!
 //! | Attribute | Type | Required | Description |
@@ -68,6 +71,15 @@ impl<'a> ProjectsLister<'a> {
         self
     }
 
+    /// Return the projects with the most recent activity first.
+    ///
+    /// Shortcut for `order_by(ListingOrderBy::LastActivityAt)` and `sort(ListingSort::Desc)`.
+    pub fn most_recently_active(&'a mut self) -> &'a mut ProjectsLister {
+        self.internal.order_by = Some(::projects::ListingOrderBy::LastActivityAt);
+        self.internal.sort = Some(::ListingSort::Desc);
+        self
+    }
+
     pub fn search(&'a mut self, search: String) -> &'a mut ProjectsLister {
         self.internal.search = Some(search);
         self
@@ -79,7 +91,7 @@ impl<'a> ProjectsLister<'a> {
         let query = self.build_query();
         debug!("query: {:?}", query);
 
-        self.gl.get(&query, None, None).chain_err(|| format!("cannot get query {}", query))
+        self.gl.get_listing(&query).chain_err(|| format!("cannot get query {}", query))
     }
 }
 
@@ -87,9 +99,13 @@ impl<'a> BuildQuery for ProjectsLister<'a> {
     fn build_query(&self) -> String {
 
         let encoded = serde_urlencoded::to_string(&self.internal).unwrap();
-        let mut query = String::from("projects/owned");
+        let mut query = if self.gl.api_version() >= 4 {
+            String::from("projects?owned=true")
+        } else {
+            String::from("projects/owned")
+        };
         if !encoded.is_empty() {
-            query.push_str("?");
+            query.push_str(if query.contains('?') { "&" } else { "?" });
             query.push_str(&encoded);
         }
         debug!("query: {}", query);
@@ -247,6 +263,17 @@ mod tests {
     }
 
 
+    #[test]
+    fn build_query_most_recently_active() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+        // let gl: ::GitLab = Default::default();
+
+        let expected_string = "projects/owned?order_by=last_activity_at&sort=desc";
+        let query = gl.projects().owned().most_recently_active().build_query();
+        assert_eq!(query, expected_string);
+    }
+
+
     #[test]
     fn build_query_search() {
         let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
@@ -10,6 +10,9 @@
 //! GET /projects/search/QUERY
 //! ```
 //!
+//! The API v4 removed this endpoint: `GET /projects?search=QUERY` is queried instead (see
+//! `GitLab::api_version()`).
+//!
 //! Parameters:
 //!
 //! | Attribute | Type | Required | Description |
@@ -23,6 +26,7 @@ use serde_urlencoded;
 
 use BuildQuery;
 use Lister;
+use PageInfo;
 
 use ::errors::*;
 
@@ -42,7 +46,7 @@ impl<'a> Lister<::projects::Projects> for ProjectsLister<'a> {
         let query = self.build_query();
         debug!("query: {:?}", query);
 
-        self.gl.get(&query, None, None).chain_err(|| format!("cannot get query {}", query))
+        self.gl.get_listing(&query).chain_err(|| format!("cannot get query {}", query))
     }
 
     /// Commit the lister: Query GitLab and return a list of issues.
@@ -52,6 +56,16 @@ impl<'a> Lister<::projects::Projects> for ProjectsLister<'a> {
 
         self.gl.get(&query, page, per_page).chain_err(|| format!("cannot get query {}", query))
     }
+
+    /// Commit the lister: Query GitLab and return a page of projects with its pagination information.
+    fn list_page(&self, page: u16, per_page: u16) -> Result<(::projects::Projects, PageInfo)> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl
+            .get_with_headers(&query, page, per_page)
+            .chain_err(|| format!("cannot get query {}", query))
+    }
 }
 
 
@@ -82,9 +96,14 @@ impl<'a> BuildQuery for ProjectsLister<'a> {
     fn build_query(&self) -> String {
 
         let encoded = serde_urlencoded::to_string(&self.internal).unwrap();
-        let mut query = format!("projects/search/{}", self.query);
+        let mut query = if self.gl.api_version() >= 4 {
+            format!("projects?{}",
+                    serde_urlencoded::to_string(&[("search", &self.query)]).unwrap())
+        } else {
+            format!("projects/search/{}", self.query)
+        };
         if !encoded.is_empty() {
-            query.push_str("?");
+            query.push_str(if query.contains('?') { "&" } else { "?" });
             query.push_str(&encoded);
         }
         debug!("query: {}", query);
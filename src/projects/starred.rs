@@ -10,6 +10,8 @@
 //! GET /projects/starred
 //! ```
 //!
+//! The API v4 removed this endpoint in favor of `GET /projects?starred=true`.
+//!
 //! Parameters:
 //!
 //! | Attribute | Type | Required | Description |
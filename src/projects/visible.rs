@@ -8,6 +8,8 @@
 //! GET /projects/visible
 //! ```
 //!
+//! The API v4 removed this endpoint in favor of `GET /projects`.
+//!
 //! Parameters:
 //!
 //! | Attribute | Type | Required | Description |
@@ -0,0 +1,282 @@
+//! List, get, create and delete wiki pages
+//!
+//! https://docs.gitlab.com/ce/api/wikis.html
+//!
+//! # List wiki pages
+//!
+//! Get all wiki pages for a given project.
+//!
+//! ```text
+//! GET /projects/ID/wikis
+//! ```
+//!
+//! | Attribute | Type | Required | Description |
+//! | --------- | ---- | -------- | ----------- |
+//! | `id` | integer/string | yes | The ID or `NAMESPACE/PROJECT_NAME` of the project |
+//!
+//! # Get a wiki page
+//!
+//! Get a wiki page for a given project.
+//!
+//! ```text
+//! GET /projects/ID/wikis/SLUG
+//! ```
+//!
+//! | Attribute | Type | Required | Description |
+//! | --------- | ---- | -------- | ----------- |
+//! | `id` | integer/string | yes | The ID or `NAMESPACE/PROJECT_NAME` of the project |
+//! | `slug` | string | yes | The slug (a unique string) of the wiki page |
+//!
+//! # Create a new wiki page
+//!
+//! Creates a new wiki page for the given repository with the given title, slug, and content.
+//!
+//! ```text
+//! POST /projects/ID/wikis
+//! ```
+//!
+//! | Attribute | Type | Required | Description |
+//! | --------- | ---- | -------- | ----------- |
+//! | `id` | integer/string | yes | The ID or `NAMESPACE/PROJECT_NAME` of the project |
+//! | `content` | string | yes | The content of the wiki page |
+//! | `title` | string | yes | The title of the wiki page |
+//! | `format` | string | no | The format of the wiki page. Available formats are: `markdown` (default), `rdoc`, and `asciidoc` |
+//!
+//! # Delete a wiki page
+//!
+//! Deletes a wiki page with a given slug.
+//!
+//! ```text
+//! DELETE /projects/ID/wikis/SLUG
+//! ```
+//!
+//! | Attribute | Type | Required | Description |
+//! | --------- | ---- | -------- | ----------- |
+//! | `id` | integer/string | yes | The ID or `NAMESPACE/PROJECT_NAME` of the project |
+//! | `slug` | string | yes | The slug (a unique string) of the wiki page |
+
+
+use BuildQuery;
+
+use ::errors::*;
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum WikiFormat {
+    #[serde(rename = "markdown")]
+    Markdown,
+    #[serde(rename = "rdoc")]
+    Rdoc,
+    #[serde(rename = "asciidoc")]
+    Asciidoc,
+}
+
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WikiPage {
+    pub slug: String,
+    pub title: String,
+    pub format: WikiFormat,
+    /// Not returned when listing the wiki pages.
+    pub content: Option<String>,
+}
+
+pub type WikiPages = Vec<WikiPage>;
+
+
+#[derive(Debug, Serialize)]
+struct WikiPageCreate<'b> {
+    title: &'b str,
+    content: &'b str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    format: Option<WikiFormat>,
+}
+
+
+#[derive(Debug, Clone)]
+pub struct WikisLister<'a> {
+    gl: &'a ::GitLab,
+    id: ::projects::ListingId,
+}
+
+
+impl<'a> WikisLister<'a> {
+    pub fn new(gl: &'a ::GitLab, id: ::projects::ListingId) -> WikisLister {
+        WikisLister { gl: gl, id: id }
+    }
+
+
+    /// Commit the lister: Query GitLab and return a list of wiki pages.
+    pub fn list(&self) -> Result<WikiPages> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl.get_listing(&query).chain_err(|| format!("cannot get query {}", query))
+    }
+
+    /// Get the wiki page identified by `slug`, including its content.
+    pub fn get_wiki(&self, slug: &str) -> Result<WikiPage> {
+        let query = self.build_page_query(slug);
+        debug!("query: {:?}", query);
+
+        self.gl.get(&query, None, None).chain_err(|| format!("cannot get query {}", query))
+    }
+
+    /// Create a wiki page. GitLab uses Markdown if no `format` is given.
+    ///
+    /// Returns the created page, from which the generated `slug` can be read.
+    pub fn create_wiki(&self,
+                       title: &str,
+                       content: &str,
+                       format: Option<WikiFormat>)
+                       -> Result<WikiPage> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        let body = WikiPageCreate {
+            title: title,
+            content: content,
+            format: format,
+        };
+
+        self.gl.post(&query, &body).chain_err(|| format!("cannot post query {}", query))
+    }
+
+    /// Delete the wiki page identified by `slug`.
+    pub fn delete_wiki(&self, slug: &str) -> Result<()> {
+        let query = self.build_page_query(slug);
+        debug!("query: {:?}", query);
+
+        self.gl.delete(&query).chain_err(|| format!("cannot delete query {}", query))
+    }
+
+    fn build_page_query(&self, slug: &str) -> String {
+        format!("{}/{}", self.build_query(), ::id::encode_path_segment(slug))
+    }
+}
+
+
+impl<'a> BuildQuery for WikisLister<'a> {
+    fn build_query(&self) -> String {
+        let mut query = String::from("projects/");
+
+        query.push_str(&self.id.encode());
+        query.push_str("/wikis");
+
+        query
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use hyper;
+    use serde_json;
+
+    use BuildQuery;
+    use projects::wikis::{WikiFormat, WikiPage, WikiPageCreate};
+    use tests::mock_gitlab;
+    use transport::Response;
+
+    const TEST_PROJECT_ID: i64 = 123;
+    const TEST_PROJECT_NAME: &'static str = "group/project";
+
+
+    #[test]
+    fn build_query_default() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+        // let gl: ::GitLab = Default::default();
+
+        let expected_string = format!("projects/{}/wikis", TEST_PROJECT_ID);
+        let query = gl.projects()
+            .id(::projects::ListingId::Id(TEST_PROJECT_ID))
+            .wikis()
+            .build_query();
+        assert_eq!(query, expected_string);
+
+        let expected_string = "projects/group%2Fproject/wikis";
+        let query = gl.projects()
+            .id(::projects::ListingId::NamespaceProject(TEST_PROJECT_NAME.to_string()))
+            .wikis()
+            .build_query();
+        assert_eq!(query, expected_string);
+    }
+
+
+    #[test]
+    fn build_page_query() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+        let lister = gl.projects().id(::projects::ListingId::Id(TEST_PROJECT_ID)).wikis();
+
+        let expected_string = format!("projects/{}/wikis/home", TEST_PROJECT_ID);
+        assert_eq!(lister.build_page_query("home"), expected_string);
+
+        let expected_string = format!("projects/{}/wikis/dev%2Fsetup%20notes", TEST_PROJECT_ID);
+        assert_eq!(lister.build_page_query("dev/setup notes"), expected_string);
+    }
+
+
+    #[test]
+    fn serialize_wiki_page_create() {
+        let body = WikiPageCreate {
+            title: "Home",
+            content: "Hello",
+            format: None,
+        };
+        assert_eq!(serde_json::to_string(&body).unwrap(),
+                   r#"{"title":"Home","content":"Hello"}"#);
+
+        let body = WikiPageCreate {
+            title: "Home",
+            content: "Hello",
+            format: Some(WikiFormat::Asciidoc),
+        };
+        assert_eq!(serde_json::to_string(&body).unwrap(),
+                   r#"{"title":"Home","content":"Hello","format":"asciidoc"}"#);
+    }
+
+
+    #[test]
+    fn deserialize_wiki_pages() {
+        let json_reply = r##"[
+            {
+                "content" : "Here is an instruction how to deploy this project.",
+                "format" : "markdown",
+                "slug" : "deploy",
+                "title" : "deploy"
+            },
+            {
+                "format" : "rdoc",
+                "slug" : "dev/setup",
+                "title" : "setup"
+            }
+        ]"##;
+
+        let pages: Vec<WikiPage> = serde_json::from_str(json_reply)
+            .expect("JSON deserialization failed");
+        assert_eq!(pages.len(), 2);
+        assert_eq!(pages[0].format, WikiFormat::Markdown);
+        assert!(pages[0].content.is_some());
+        assert_eq!(pages[1].slug, "dev/setup");
+        assert!(pages[1].content.is_none());
+    }
+
+
+    #[test]
+    fn delete_wiki() {
+        let (gl, mock) = mock_gitlab();
+        mock.push_response(Response::new(hyper::status::StatusCode::NoContent));
+
+        gl.projects()
+            .id(::projects::ListingId::Id(TEST_PROJECT_ID))
+            .wikis()
+            .delete_wiki("dev/setup")
+            .unwrap();
+
+        let requests = mock.requests();
+        assert_eq!(requests[0].method, hyper::method::Method::Delete);
+        assert!(requests[0]
+            .url
+            .starts_with("https://gitlab.example.com/api/v3/projects/123/wikis/dev%2Fsetup?"));
+    }
+}
@@ -6,7 +6,6 @@
 
 // FIXME: Use https://github.com/nox/serde_urlencoded
 // FIXME: Use a type for sha1
-// FIXME: Use chrono crate for dates
 // FIXME: Use unsigned integers where it makes sense (id, iid, etc.)
 // FIXME: Verify all `match` in push_str() in build_query(): They should contain all members.
 // FIXME: Get rid of build_query(), use serde's Serialize instead.
@@ -0,0 +1,145 @@
+//! Tolerant deserialization
+//!
+//! Some GitLab endpoints don't always answer with the same JSON type:
+//!
+//! * endpoints returning a map (like `GET /projects/ID/languages`) might answer `[]` instead of
+//!   `{}` when there is nothing in the map, an empty Ruby hash being serialized as an array;
+//! * endpoints returning a single object might answer `{}`, `[]`, `null` or `false` instead of
+//!   the object when there is none.
+//!
+//! Deserializing those answers directly fails. The functions of this module consider all these
+//! "empty" values as the absence of a value instead. `GitLab::get_or_default()` and
+//! `GitLab::get_optional()` use them.
+//!
+//! Sizes (in bytes) are also deserialized tolerantly by `deserialize_size()`: they might not fit
+//! in an `i64`, or even be sent as floating point numbers.
+
+
+use std;
+
+use serde;
+use serde_json;
+
+use ::errors::*;
+
+
+/// Whether `value` is one of the values GitLab uses to say "nothing": `null`, `false`, `[]` or
+/// `{}`.
+pub fn is_empty(value: &serde_json::Value) -> bool {
+    match *value {
+        serde_json::Value::Null |
+        serde_json::Value::Bool(false) => true,
+        serde_json::Value::Array(ref array) => array.is_empty(),
+        serde_json::Value::Object(ref object) => object.is_empty(),
+        _ => false,
+    }
+}
+
+
+/// Deserialize `json`, returning `None` if it is an empty value (see `is_empty()`).
+pub fn from_str_optional<T>(json: &str) -> Result<Option<T>>
+    where T: serde::Deserialize
+{
+    let value: serde_json::Value = serde_json::from_str(json)
+        .chain_err(|| format!("cannot parse JSON data: {}", json))?;
+    if is_empty(&value) {
+        return Ok(None);
+    }
+
+    serde_json::from_value(value)
+        .map(Some)
+        .chain_err(|| format!("cannot build Rust struct from JSON data: {}", json))
+}
+
+
+/// Deserialize `json`, returning `T::default()` if it is an empty value (see `is_empty()`).
+pub fn from_str_or_default<T>(json: &str) -> Result<T>
+    where T: serde::Deserialize + Default
+{
+    from_str_optional(json).map(|value| value.unwrap_or_default())
+}
+
+
+/// Deserialize a size, in bytes.
+///
+/// Sizes up to `u64::MAX` are read exactly. Sizes sent as floating point numbers (which JSON
+/// parsers do for integers too large for 64 bits) are rounded down, saturating at `u64::MAX`,
+/// instead of failing. Negative sizes are errors.
+pub fn deserialize_size<D>(deserializer: D) -> std::result::Result<u64, D::Error>
+    where D: serde::Deserializer
+{
+    let value: serde_json::Value = serde::Deserialize::deserialize(deserializer)?;
+
+    if let Some(size) = value.as_u64() {
+        return Ok(size);
+    }
+    match value.as_f64() {
+        Some(size) if size >= std::u64::MAX as f64 => Ok(std::u64::MAX),
+        Some(size) if size >= 0.0 => Ok(size as u64),
+        _ => Err(<D::Error as serde::de::Error>::custom(format!("invalid size: {}", value))),
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use serde_json;
+
+    use tolerant::*;
+    use tests::mock_gitlab;
+    use transport::Response;
+
+
+    #[test]
+    fn empty_values() {
+        for json in &["null", "false", "[]", "{}", " [ ] "] {
+            let value: ::projects::Languages = from_str_or_default(json).unwrap();
+            assert!(value.is_empty(), "{} is not empty", json);
+            assert!(from_str_optional::<::Version>(json).unwrap().is_none());
+        }
+
+        let languages: ::projects::Languages = from_str_or_default(r#"{"Rust": 100.0}"#).unwrap();
+        assert_eq!(languages.get("Rust"), Some(&100.0));
+
+        let version: Option<::Version> =
+            from_str_optional(r#"{"version": "9.5.0", "revision": "a"}"#).unwrap();
+        assert_eq!(version.unwrap().version, "9.5.0");
+
+        // Non-empty values of the wrong type are still errors.
+        assert!(from_str_or_default::<::projects::Languages>("[1]").is_err());
+        assert!(from_str_optional::<::Version>("true").is_err());
+        assert!(from_str_optional::<::Version>("not JSON").is_err());
+    }
+
+
+    #[test]
+    fn huge_sizes() {
+        let json = r#"{"commit_count": 12, "storage_size": 18446744073709551615,
+                       "repository_size": 9223372036854775808, "lfs_objects_size": 1e30}"#;
+
+        let statistics: ::projects::ProjectStatistics = serde_json::from_str(json)
+            .expect("JSON deserialization failed");
+        assert_eq!(statistics.commit_count, 12);
+        assert_eq!(statistics.storage_size, ::std::u64::MAX);
+        assert_eq!(statistics.repository_size, ::std::i64::MAX as u64 + 1);
+        assert_eq!(statistics.lfs_objects_size, ::std::u64::MAX);
+
+        let json = r#"{"commit_count": 12, "storage_size": -1, "repository_size": 0,
+                       "lfs_objects_size": 0}"#;
+        assert!(serde_json::from_str::<::projects::ProjectStatistics>(json).is_err());
+    }
+
+
+    #[test]
+    fn languages_empty_array() {
+        let (gl, mock) = mock_gitlab();
+        mock.push_response(Response::json("[]"));
+        mock.push_response(Response::json("{}"));
+        mock.push_response(Response::json(r#"{"Rust": 87.5, "Python": 12.5}"#));
+        let project = || gl.projects().id(::projects::ListingId::Id(123));
+
+        assert!(project().languages().unwrap().is_empty());
+        assert!(project().languages().unwrap().is_empty());
+        assert_eq!(project().languages().unwrap().len(), 2);
+    }
+}
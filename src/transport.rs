@@ -0,0 +1,1010 @@
+//! HTTP layer
+//!
+//! `GitLab` does not talk to the network directly but through a `Transport`. By default this is
+//! `HyperTransport`, sending the requests using `hyper`.
+//!
+//! Another transport can be given with `GitLab::transport()`. For example `MockTransport` answers
+//! with pre-defined responses and records the requests it receives, allowing to test code using
+//! `GitLab` without a GitLab server:
+//!
+//! ```
+//! use gitlab_api::GitLab;
+//! use gitlab_api::transport::{MockTransport, Response};
+//!
+//! let mock = MockTransport::new();
+//! mock.push_response(Response::json(r#"{"version": "8.16.4", "revision": "abcdef"}"#));
+//!
+//! let gl = GitLab::new("gitlab.example.com", "XXXXXXXXXXXXXXXXXXXX")
+//!     .unwrap()
+//!     .transport(Box::new(mock.clone()));
+//!
+//! assert_eq!(gl.version().unwrap().version, "8.16.4");
+//! assert!(mock.requests()[0].url.starts_with("https://gitlab.example.com/api/v3/version?"));
+//! ```
+
+
+use std::collections::VecDeque;
+use std::io::{Cursor, Read};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use hyper;
+use hyper_native_tls;
+use native_tls;
+#[cfg(feature = "gzip")]
+use flate2;
+
+use pagination::parse_header;
+
+use ::errors::*;
+
+
+/// Longest delay, in seconds, waited for before a retry, even if `Retry-After` asks for more.
+pub const MAX_RETRY_AFTER_SECS: u64 = 600;
+
+
+/// An HTTP request to send to GitLab.
+#[derive(Debug, Clone)]
+pub struct Request {
+    pub method: hyper::method::Method,
+    /// The full URL, including the private token
+    pub url: String,
+    pub headers: hyper::header::Headers,
+    pub body: Option<String>,
+    /// Read and write timeout, `None` to wait forever.
+    pub timeout: Option<Duration>,
+}
+
+
+impl Request {
+    /// A request without headers nor body.
+    pub fn new(method: hyper::method::Method, url: &str) -> Request {
+        Request {
+            method: method,
+            url: url.to_string(),
+            headers: hyper::header::Headers::new(),
+            body: None,
+            timeout: None,
+        }
+    }
+
+    /// A request with a JSON body.
+    pub fn json(method: hyper::method::Method, url: &str, body: &str) -> Request {
+        let mut request = Request::new(method, url);
+        request.headers.set(hyper::header::ContentType::json());
+        request.body = Some(body.to_string());
+        request
+    }
+}
+
+
+/// Settings of the requests sent to GitLab.
+///
+/// The options given to `GitLab::request_options()` apply to all requests. Options can also be
+/// given to a single call (for example `ProjectsLister::list_with()`): those set take precedence
+/// over the `GitLab`'s ones, for this call only.
+#[derive(Debug, Clone, Default)]
+pub struct RequestOptions {
+    timeout: Option<Duration>,
+    retries: Option<u8>,
+    deadline: Option<Instant>,
+    headers: hyper::header::Headers,
+}
+
+
+impl RequestOptions {
+    pub fn new() -> RequestOptions {
+        Default::default()
+    }
+
+    /// Give up reading or writing after `timeout`. By default, wait forever.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Send the request again, up to `retries` times, when it could not be sent or its response
+    /// could not be received. By default, requests are not retried.
+    ///
+    /// Only `GET`, `HEAD` and `DELETE` requests are retried: a `POST` or a `PUT` might have been
+    /// processed by GitLab even though its response was lost, sending it again could for example
+    /// create an item twice.
+    pub fn retries(mut self, retries: u8) -> Self {
+        self.retries = Some(retries);
+        self
+    }
+
+    /// Give up the whole operation at `deadline`, retries included.
+    ///
+    /// Contrary to `timeout()` which applies to each read and write, no request is sent (or sent
+    /// again) once the deadline has passed: `ErrorKind::DeadlineExceeded` is returned instead.
+    /// `pagination::list_all_partial()` then returns the items collected so far.
+    pub fn deadline(mut self, deadline: Instant) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Add the header `name: value` to the requests.
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        self.headers.set_raw(name.to_string(), vec![value.as_bytes().to_vec()]);
+        self
+    }
+
+    /// Ask for the `media_type` representation of the response, for example `text/plain` for
+    /// raw files or diffs. By default, `application/json` is accepted.
+    pub fn accept(self, media_type: &str) -> Self {
+        self.header("Accept", media_type)
+    }
+
+    /// Whether the deadline, if any, has passed.
+    pub fn deadline_passed(&self) -> bool {
+        self.deadline.map_or(false, |deadline| Instant::now() >= deadline)
+    }
+
+    /// Whether the deadline, if any, passes within `delay` from now.
+    pub fn deadline_within(&self, delay: Duration) -> bool {
+        self.deadline.map_or(false, |deadline| {
+            Instant::now().checked_add(delay).map_or(true, |end| end >= deadline)
+        })
+    }
+
+    /// The number of times a request is sent again after a failure.
+    pub fn retry_count(&self) -> u8 {
+        self.retries.unwrap_or(0)
+    }
+
+    /// These options, overridden by the ones set in `overrides`.
+    pub fn merge(&self, overrides: &RequestOptions) -> RequestOptions {
+        let mut headers = self.headers.clone();
+        for header in overrides.headers.iter() {
+            headers.set_raw(header.name().to_string(),
+                            vec![header.value_string().into_bytes()]);
+        }
+
+        RequestOptions {
+            timeout: overrides.timeout.or(self.timeout),
+            retries: overrides.retries.or(self.retries),
+            deadline: overrides.deadline.or(self.deadline),
+            headers: headers,
+        }
+    }
+
+    /// Apply the timeout and headers to `request`.
+    ///
+    /// The timeout is shortened to the time left before the deadline. The request's own headers
+    /// are kept.
+    pub fn apply(&self, request: &mut Request) {
+        request.timeout = self.timeout;
+        if let Some(deadline) = self.deadline {
+            let now = Instant::now();
+            let left = if deadline > now { deadline - now } else { Duration::from_millis(1) };
+            request.timeout = Some(match self.timeout {
+                Some(timeout) if timeout < left => timeout,
+                _ => left,
+            });
+        }
+        for header in self.headers.iter() {
+            if request.headers.get_raw(header.name()).is_none() {
+                request.headers.set_raw(header.name().to_string(),
+                                        vec![header.value_string().into_bytes()]);
+            }
+        }
+    }
+}
+
+
+/// Retry policy for the responses telling that GitLab is (temporarily) unavailable, see
+/// `GitLab::with_retry()`.
+///
+/// `429 Too Many Requests` and `5xx` responses are retried, up to `max_retries` times. Before
+/// retrying, the delay given by the `Retry-After` header is waited for (at most
+/// `MAX_RETRY_AFTER_SECS`); without it, the delay is `base_delay`, doubled at each retry. A
+/// backoff too large to be represented is not waited for: the request is not retried. Other
+/// statuses (for example `401 Unauthorized` or `404 Not Found`) are never retried.
+///
+/// With `jitter`, the backoff delay is instead drawn at random between zero and that value
+/// ("full jitter"), so that clients failing together don't retry together. The delays of
+/// `Retry-After` are never randomized.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RetryConfig {
+    pub max_retries: u32,
+    pub base_delay: Duration,
+    /// Randomize the backoff delays
+    pub jitter: bool,
+    /// Give up retrying when the next retry would be sent more than `max_elapsed` after the
+    /// first request, `None` to only limit the number of retries
+    pub max_elapsed: Option<Duration>,
+    /// Seed of the jitter's random numbers, `None` to seed from the clock
+    pub seed: Option<u64>,
+}
+
+
+impl RetryConfig {
+    /// Retry up to `max_retries` times, starting from `base_delay`, with jitter and without
+    /// `max_elapsed`.
+    pub fn new(max_retries: u32, base_delay: Duration) -> RetryConfig {
+        RetryConfig {
+            max_retries: max_retries,
+            base_delay: base_delay,
+            jitter: true,
+            max_elapsed: None,
+            seed: None,
+        }
+    }
+
+    /// Whether a response with `status` can be retried.
+    pub fn is_retryable(status: hyper::status::StatusCode) -> bool {
+        status == hyper::status::StatusCode::TooManyRequests || status.is_server_error()
+    }
+
+    /// How long to wait before the retry following `nb_retries` retries, `None` if the request
+    /// must not be retried anymore.
+    ///
+    /// `retry_after` is the delay asked by GitLab in the `Retry-After` header, if any. `elapsed`
+    /// is the time since the first request, checked against `max_elapsed`. `rng` draws the
+    /// jitter.
+    pub fn delay(&self,
+                 nb_retries: u32,
+                 retry_after: Option<Duration>,
+                 elapsed: Duration,
+                 rng: &mut JitterRng)
+                 -> Option<Duration> {
+        if nb_retries >= self.max_retries {
+            return None;
+        }
+
+        let delay = match retry_after {
+            Some(retry_after) => retry_after.min(Duration::from_secs(MAX_RETRY_AFTER_SECS)),
+            None => {
+                let backoff = match self.base_delay.checked_mul(2u32.pow(nb_retries.min(16))) {
+                    Some(backoff) => backoff,
+                    None => return None,
+                };
+                if self.jitter {
+                    rng.below(backoff)
+                } else {
+                    backoff
+                }
+            }
+        };
+
+        match self.max_elapsed {
+            Some(max_elapsed) => {
+                // A `Retry-After` too large to be added is past any budget.
+                match elapsed.checked_add(delay) {
+                    Some(end) if end <= max_elapsed => Some(delay),
+                    _ => None,
+                }
+            }
+            None => Some(delay),
+        }
+    }
+}
+
+
+/// The random number generator of the retries' jitter (a xorshift generator).
+///
+/// It only needs to spread the retries of different clients, not to be unpredictable. Seeding
+/// it explicitly makes the delays reproducible.
+#[derive(Debug, Clone)]
+pub struct JitterRng {
+    state: u64,
+}
+
+
+impl JitterRng {
+    pub fn new(seed: u64) -> JitterRng {
+        // A xorshift generator stays at zero forever.
+        JitterRng { state: if seed == 0 { 0x9e37_79b9_7f4a_7c15 } else { seed } }
+    }
+
+    /// A generator seeded from the clock.
+    pub fn from_clock() -> JitterRng {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or(Duration::from_secs(0));
+        JitterRng::new(now.as_secs() ^ ((now.subsec_nanos() as u64) << 32))
+    }
+
+    /// A random number in `[0, 1)`.
+    pub fn next_f64(&mut self) -> f64 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+
+        (self.state >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// A random duration in `[0, max)`.
+    pub fn below(&mut self, max: Duration) -> Duration {
+        let nanos = max.as_secs() as f64 * 1e9 + max.subsec_nanos() as f64;
+        let nanos = (nanos * self.next_f64()) as u64;
+
+        Duration::new(nanos / 1_000_000_000, (nanos % 1_000_000_000) as u32)
+    }
+}
+
+
+/// An HTTP response received from GitLab.
+#[derive(Debug, Clone)]
+pub struct Response {
+    pub status: hyper::status::StatusCode,
+    pub headers: hyper::header::Headers,
+    pub body: String,
+}
+
+
+impl Response {
+    /// A response with the given status code, without headers nor body.
+    pub fn new(status: hyper::status::StatusCode) -> Response {
+        Response {
+            status: status,
+            headers: hyper::header::Headers::new(),
+            body: String::new(),
+        }
+    }
+
+    /// A `200 OK` response with a JSON body.
+    pub fn json(body: &str) -> Response {
+        let mut response = Response::new(hyper::status::StatusCode::Ok);
+        response.headers.set(hyper::header::ContentType::json());
+        response.body = body.to_string();
+        response
+    }
+}
+
+
+/// An HTTP response received from GitLab, whose body is read on demand.
+///
+/// Reading the body while it is received avoids keeping it whole in memory, for example when
+/// deserializing a large JSON array.
+pub struct StreamingResponse {
+    pub status: hyper::status::StatusCode,
+    pub headers: hyper::header::Headers,
+    pub body: Box<Read>,
+}
+
+
+impl StreamingResponse {
+    /// Read the whole body.
+    pub fn into_response(mut self) -> Result<Response> {
+        let mut body = String::new();
+        self.body.read_to_string(&mut body).chain_err(|| "cannot read response body")?;
+
+        Ok(Response {
+            status: self.status,
+            headers: self.headers,
+            body: body,
+        })
+    }
+}
+
+
+impl From<Response> for StreamingResponse {
+    fn from(response: Response) -> StreamingResponse {
+        StreamingResponse {
+            status: response.status,
+            headers: response.headers,
+            body: Box::new(Cursor::new(response.body.into_bytes())),
+        }
+    }
+}
+
+
+/// Diagnostic information extracted from the headers of a GitLab response.
+///
+/// When a request fails, the `request_id` allows GitLab's administrators to find it in the
+/// server's logs.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ResponseMeta {
+    /// The `X-Request-Id` header
+    pub request_id: Option<String>,
+    /// The `X-Gitlab-*` headers, as (name, value) pairs
+    pub gitlab_headers: Vec<(String, String)>,
+}
+
+
+impl ResponseMeta {
+    pub fn from_headers(headers: &hyper::header::Headers) -> ResponseMeta {
+        let mut meta = ResponseMeta::default();
+
+        for header in headers.iter() {
+            let name = header.name().to_lowercase();
+            if name == "x-request-id" {
+                meta.request_id = Some(header.value_string());
+            } else if name.starts_with("x-gitlab-") {
+                meta.gitlab_headers.push((header.name().to_string(), header.value_string()));
+            }
+        }
+
+        meta
+    }
+}
+
+
+/// GitLab's rate limit, as announced by the `RateLimit-*` headers of a response.
+///
+/// Only sent by GitLab instances with rate limiting enabled.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RateLimit {
+    /// The number of requests allowed in the current period (`RateLimit-Limit`)
+    pub limit: u32,
+    /// The number of requests left in the current period (`RateLimit-Remaining`)
+    pub remaining: u32,
+    /// When the period ends and `remaining` is reset to `limit` (`RateLimit-Reset`)
+    pub reset: ::datetime::DateTime,
+}
+
+
+impl RateLimit {
+    /// Returns `None` if a header is missing or malformed.
+    pub fn from_headers(headers: &hyper::header::Headers) -> Option<RateLimit> {
+        // `RateLimit-Reset` is a Unix timestamp, in seconds.
+        match (parse_header(headers, "RateLimit-Limit"),
+               parse_header(headers, "RateLimit-Remaining"),
+               parse_header(headers, "RateLimit-Reset")) {
+            (Some(limit), Some(remaining), Some(reset)) => {
+                ::datetime::from_timestamp(reset).map(|reset| {
+                    RateLimit {
+                        limit: limit,
+                        remaining: remaining,
+                        reset: reset,
+                    }
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
+
+/// The HTTP layer used by `GitLab`.
+///
+/// Only `send()` needs to be implemented. The status code is not checked by the transport:
+/// a `404 Not Found` is a successfully received `Response`.
+///
+/// Transports must be `Send + Sync` so that `GitLab` can be shared between threads.
+pub trait Transport: Send + Sync {
+    fn send(&self, request: Request) -> Result<Response>;
+
+    /// Same as `send()` but the body is left to be read by the caller.
+    ///
+    /// By default the body is read whole by `send()`: transports able to stream it override
+    /// this method.
+    fn send_streaming(&self, request: Request) -> Result<StreamingResponse> {
+        self.send(request).map(StreamingResponse::from)
+    }
+
+    fn get(&self, url: &str) -> Result<Response> {
+        self.send(Request::new(hyper::method::Method::Get, url))
+    }
+
+    fn post(&self, url: &str, body: &str) -> Result<Response> {
+        self.send(Request::json(hyper::method::Method::Post, url, body))
+    }
+
+    fn put(&self, url: &str, body: &str) -> Result<Response> {
+        self.send(Request::json(hyper::method::Method::Put, url, body))
+    }
+
+    fn delete(&self, url: &str) -> Result<Response> {
+        self.send(Request::new(hyper::method::Method::Delete, url))
+    }
+}
+
+
+/// The default transport, using `hyper`.
+///
+/// Redirects are not followed: `GitLab` follows them itself, only to its own host.
+///
+/// Each request is sent with its own client, since the timeouts are set on the client: requests
+/// sent from several threads don't wait for each other. Clients are kept once their request was
+/// sent and reused by the next ones, new ones are only built when all of them are in use.
+pub struct HyperTransport {
+    /// Builds a client when none is idle
+    new_client: Box<Fn() -> hyper::Client + Send + Sync>,
+    /// Clients not sending a request
+    idle_clients: Mutex<Vec<hyper::Client>>,
+}
+
+
+impl HyperTransport {
+    /// A transport sending the requests with clients built by `new_client`.
+    pub fn new<F>(new_client: F) -> HyperTransport
+        where F: Fn() -> hyper::Client + Send + Sync + 'static
+    {
+        HyperTransport {
+            new_client: Box::new(new_client),
+            idle_clients: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// A transport accepting any TLS certificate, including self-signed and expired ones.
+    ///
+    /// This is dangerous: anyone able to intercept the connection can impersonate GitLab and
+    /// steal the private token. Only use it for development, see
+    /// `GitLab::danger_accept_invalid_certs()`.
+    pub fn danger_accept_invalid_certs() -> Result<HyperTransport> {
+        let connector = native_tls::TlsConnector::builder()
+            .danger_accept_invalid_certs(true)
+            .build()
+            .chain_err(|| "cannot build TLS connector")?;
+        let tls = hyper_native_tls::NativeTlsClient::from(connector);
+
+        Ok(HyperTransport::new(move || {
+            hyper::Client::with_connector(hyper::net::HttpsConnector::new(tls.clone()))
+        }))
+    }
+
+    /// An idle client, or a new one if they are all in use.
+    fn client(&self) -> hyper::Client {
+        let idle_client = self.idle_clients.lock().unwrap().pop();
+
+        idle_client.unwrap_or_else(|| {
+            let mut client = (self.new_client)();
+            client.set_redirect_policy(hyper::client::RedirectPolicy::FollowNone);
+            client
+        })
+    }
+}
+
+
+impl Transport for HyperTransport {
+    fn send(&self, request: Request) -> Result<Response> {
+        self.send_streaming(request)?.into_response()
+    }
+
+    fn send_streaming(&self, request: Request) -> Result<StreamingResponse> {
+        let Request { method, url, mut headers, body, timeout } = request;
+
+        if cfg!(feature = "gzip") && headers.get_raw("Accept-Encoding").is_none() {
+            headers.set_raw("Accept-Encoding", vec![b"gzip, deflate".to_vec()]);
+        }
+
+        let mut client = self.client();
+        client.set_read_timeout(timeout);
+        client.set_write_timeout(timeout);
+
+        let res = {
+            // Close connections after each request.
+            let mut builder = client.request(method, &url)
+                .headers(headers)
+                .header(hyper::header::Connection::close());
+            if let Some(ref body) = body {
+                builder = builder.body(body.as_str());
+            }
+
+            builder.send()
+        };
+        self.idle_clients.lock().unwrap().push(client);
+        let res: hyper::client::Response = res?;
+
+        let status = res.status;
+        let headers = res.headers.clone();
+        let body = decode_body(&headers, res)?;
+
+        Ok(StreamingResponse {
+            status: status,
+            headers: headers,
+            body: body,
+        })
+    }
+}
+
+
+/// Decode a response's body according to its `Content-Encoding` header, as it is read.
+///
+/// Compressed bodies are only supported with the `gzip` feature.
+fn decode_body<R>(headers: &hyper::header::Headers, body: R) -> Result<Box<Read>>
+    where R: Read + 'static
+{
+    let encoding = headers.get_raw("Content-Encoding")
+        .and_then(|values| values.first())
+        .map(|value| String::from_utf8_lossy(value).trim().to_lowercase());
+
+    let body: Box<Read> = match encoding.as_ref().map(|encoding| encoding.as_str()) {
+        None | Some("") | Some("identity") => Box::new(body),
+        #[cfg(feature = "gzip")]
+        Some("gzip") => {
+            Box::new(flate2::read::GzDecoder::new(body)
+                .chain_err(|| "cannot decode gzip response body")?)
+        }
+        #[cfg(feature = "gzip")]
+        Some("deflate") => Box::new(flate2::read::ZlibDecoder::new(body)),
+        Some(encoding) => bail!(format!("unsupported content encoding '{}'", encoding)),
+    };
+
+    Ok(body)
+}
+
+
+#[derive(Debug, Default)]
+struct MockTransportState {
+    responses: VecDeque<Response>,
+    /// Responses to the requests whose URL contains the pattern, see `push_response_for()`
+    routed_responses: Vec<(String, Response)>,
+    requests: Vec<Request>,
+    delay: Option<Duration>,
+}
+
+
+/// An in-memory transport, answering with pre-defined responses.
+///
+/// Responses are returned in the order they were pushed, one per request. Clones share the
+/// same responses and requests, so a clone can be kept to inspect the requests once the
+/// transport has been given to `GitLab`.
+#[derive(Debug, Clone, Default)]
+pub struct MockTransport {
+    state: Arc<Mutex<MockTransportState>>,
+}
+
+
+impl MockTransport {
+    pub fn new() -> MockTransport {
+        Default::default()
+    }
+
+    /// Queue the response to the next request.
+    pub fn push_response(&self, response: Response) {
+        self.state.lock().unwrap().responses.push_back(response);
+    }
+
+    /// Queue the response to the next request whose URL contains `pattern`.
+    ///
+    /// These responses take precedence over the ones queued by `push_response()`, allowing to
+    /// answer requests sent concurrently, in no particular order.
+    pub fn push_response_for(&self, pattern: &str, response: Response) {
+        self.state.lock().unwrap().routed_responses.push((pattern.to_string(), response));
+    }
+
+    /// Wait `delay` before answering each request, simulating a slow server.
+    pub fn set_delay(&self, delay: Duration) {
+        self.state.lock().unwrap().delay = Some(delay);
+    }
+
+    /// The requests received so far.
+    pub fn requests(&self) -> Vec<Request> {
+        self.state.lock().unwrap().requests.clone()
+    }
+}
+
+
+impl Transport for MockTransport {
+    fn send(&self, request: Request) -> Result<Response> {
+        let delay = self.state.lock().unwrap().delay;
+        if let Some(delay) = delay {
+            thread::sleep(delay);
+        }
+
+        let mut state = self.state.lock().unwrap();
+        let routed = state.routed_responses
+            .iter()
+            .position(|&(ref pattern, _)| request.url.contains(pattern.as_str()));
+        state.requests.push(request);
+
+        if let Some(index) = routed {
+            return Ok(state.routed_responses.remove(index).1);
+        }
+        match state.responses.pop_front() {
+            Some(response) => Ok(response),
+            None => bail!("no response left in mock transport"),
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Cursor, Read, Write};
+    use std::net::TcpListener;
+    use std::sync::Arc;
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    use hyper;
+
+    use errors::*;
+    use transport::*;
+
+
+    #[test]
+    fn retry_delay() {
+        let config = RetryConfig {
+            jitter: false,
+            ..RetryConfig::new(3, Duration::from_millis(100))
+        };
+        let mut rng = JitterRng::new(1);
+        let zero = Duration::from_secs(0);
+
+        assert_eq!(config.delay(0, None, zero, &mut rng), Some(Duration::from_millis(100)));
+        assert_eq!(config.delay(1, None, zero, &mut rng), Some(Duration::from_millis(200)));
+        assert_eq!(config.delay(2, None, zero, &mut rng), Some(Duration::from_millis(400)));
+        assert_eq!(config.delay(3, None, zero, &mut rng), None);
+        // `Retry-After` takes precedence over the backoff, but not over the number of retries.
+        assert_eq!(config.delay(1, Some(Duration::from_secs(5)), zero, &mut rng),
+                   Some(Duration::from_secs(5)));
+        assert_eq!(config.delay(3, Some(Duration::from_secs(5)), zero, &mut rng), None);
+
+        // `Retry-After` is capped.
+        assert_eq!(config.delay(1, Some(Duration::from_secs(86400)), zero, &mut rng),
+                   Some(Duration::from_secs(MAX_RETRY_AFTER_SECS)));
+
+        // The backoff overflows.
+        let huge = RetryConfig { base_delay: Duration::from_secs(u64::max_value() / 2), ..config };
+        assert_eq!(huge.delay(0, None, zero, &mut rng),
+                   Some(Duration::from_secs(u64::max_value() / 2)));
+        assert_eq!(huge.delay(2, None, zero, &mut rng), None);
+
+        let config = RetryConfig { max_retries: 0, ..config };
+        assert_eq!(config.delay(0, None, zero, &mut rng), None);
+
+        assert!(RetryConfig::is_retryable(hyper::status::StatusCode::TooManyRequests));
+        assert!(RetryConfig::is_retryable(hyper::status::StatusCode::BadGateway));
+        assert!(RetryConfig::is_retryable(hyper::status::StatusCode::ServiceUnavailable));
+        assert!(!RetryConfig::is_retryable(hyper::status::StatusCode::Unauthorized));
+        assert!(!RetryConfig::is_retryable(hyper::status::StatusCode::NotFound));
+        assert!(!RetryConfig::is_retryable(hyper::status::StatusCode::Ok));
+    }
+
+
+    #[test]
+    fn retry_delay_jitter() {
+        let config = RetryConfig::new(8, Duration::from_millis(100));
+        let zero = Duration::from_secs(0);
+
+        let mut rng = JitterRng::new(42);
+        let mut delays = Vec::new();
+        for _ in 0..100 {
+            for nb_retries in 0..8 {
+                let delay = config.delay(nb_retries, None, zero, &mut rng).unwrap();
+                assert!(delay < Duration::from_millis(100) * 2u32.pow(nb_retries),
+                        "delay {:?} out of bounds for retry {}",
+                        delay,
+                        nb_retries);
+                delays.push(delay);
+            }
+        }
+        // The delays are spread, not all the same.
+        assert!(delays.iter().any(|&delay| delay != delays[0]));
+        // `Retry-After` is not randomized.
+        assert_eq!(config.delay(1, Some(Duration::from_secs(5)), zero, &mut rng),
+                   Some(Duration::from_secs(5)));
+
+        // The same seed gives the same delays.
+        let mut rng = JitterRng::new(42);
+        let mut other = JitterRng::new(42);
+        for nb_retries in 0..8 {
+            assert_eq!(config.delay(nb_retries, None, zero, &mut rng),
+                       config.delay(nb_retries, None, zero, &mut other));
+        }
+
+        let mut rng = JitterRng::new(0);
+        for _ in 0..1000 {
+            let value = rng.next_f64();
+            assert!(value >= 0.0 && value < 1.0);
+        }
+    }
+
+
+    #[test]
+    fn retry_delay_max_elapsed() {
+        let config = RetryConfig {
+            jitter: false,
+            max_elapsed: Some(Duration::from_secs(1)),
+            ..RetryConfig::new(10, Duration::from_millis(100))
+        };
+        let mut rng = JitterRng::new(1);
+
+        assert_eq!(config.delay(2, None, Duration::from_millis(500), &mut rng),
+                   Some(Duration::from_millis(400)));
+        // The retry would be sent after the budget.
+        assert_eq!(config.delay(3, None, Duration::from_millis(500), &mut rng), None);
+        assert_eq!(config.delay(0, Some(Duration::from_secs(5)), Duration::from_secs(0), &mut rng),
+                   None);
+        let retry_after = Some(Duration::from_secs(u64::max_value()));
+        assert_eq!(config.delay(0, retry_after, Duration::from_secs(1), &mut rng), None);
+    }
+
+
+    #[test]
+    fn rate_limit_from_headers() {
+        let mut headers = hyper::header::Headers::new();
+        assert_eq!(RateLimit::from_headers(&headers), None);
+
+        headers.set_raw("RateLimit-Limit", vec![b"600".to_vec()]);
+        headers.set_raw("RateLimit-Remaining", vec![b"598".to_vec()]);
+        assert_eq!(RateLimit::from_headers(&headers), None);
+
+        headers.set_raw("RateLimit-Reset", vec![b"1493455560".to_vec()]);
+        assert_eq!(RateLimit::from_headers(&headers),
+                   Some(RateLimit {
+                       limit: 600,
+                       remaining: 598,
+                       reset: ::datetime::from_timestamp(1493455560).unwrap(),
+                   }));
+
+        // A reset too far in the future to be a date.
+        headers.set_raw("RateLimit-Reset", vec![b"18446744073709551615".to_vec()]);
+        assert_eq!(RateLimit::from_headers(&headers), None);
+
+        headers.set_raw("RateLimit-Reset", vec![b"1493455560".to_vec()]);
+        headers.set_raw("RateLimit-Remaining", vec![b"many".to_vec()]);
+        assert_eq!(RateLimit::from_headers(&headers), None);
+    }
+
+
+    #[test]
+    fn mock_transport() {
+        let mock = MockTransport::new();
+        mock.push_response(Response::json("[]"));
+        mock.push_response(Response::new(hyper::status::StatusCode::NoContent));
+
+        let transport: Box<Transport> = Box::new(mock.clone());
+
+        let response = transport.post("http://localhost/a", "{}").unwrap();
+        assert_eq!(response.status, hyper::status::StatusCode::Ok);
+        assert_eq!(response.body, "[]");
+
+        let response = transport.delete("http://localhost/b").unwrap();
+        assert_eq!(response.status, hyper::status::StatusCode::NoContent);
+
+        assert!(transport.get("http://localhost/c").is_err());
+
+        let requests = mock.requests();
+        assert_eq!(requests.len(), 3);
+        assert_eq!(requests[0].method, hyper::method::Method::Post);
+        assert_eq!(requests[0].url, "http://localhost/a");
+        assert_eq!(requests[0].body, Some(String::from("{}")));
+        assert_eq!(requests[0].headers.get::<hyper::header::ContentType>(),
+                   Some(&hyper::header::ContentType::json()));
+        assert_eq!(requests[1].method, hyper::method::Method::Delete);
+        assert_eq!(requests[1].body, None);
+        assert_eq!(requests[2].method, hyper::method::Method::Get);
+    }
+
+
+    #[test]
+    fn request_options_merge() {
+        let defaults = RequestOptions::new()
+            .timeout(Duration::from_secs(10))
+            .retries(2)
+            .header("X-A", "default")
+            .header("X-B", "default");
+        let overrides = RequestOptions::new()
+            .timeout(Duration::from_secs(60))
+            .header("X-B", "call");
+
+        let options = defaults.merge(&overrides);
+        assert_eq!(options.retry_count(), 2);
+
+        let mut request = Request::json(hyper::method::Method::Post, "http://localhost/a", "{}");
+        options.apply(&mut request);
+        assert_eq!(request.timeout, Some(Duration::from_secs(60)));
+        assert_eq!(request.headers.get_raw("X-A"), Some(&[b"default".to_vec()][..]));
+        assert_eq!(request.headers.get_raw("X-B"), Some(&[b"call".to_vec()][..]));
+        // The request's own headers are kept.
+        assert_eq!(request.headers.get::<hyper::header::ContentType>(),
+                   Some(&hyper::header::ContentType::json()));
+
+        assert_eq!(RequestOptions::new().retry_count(), 0);
+    }
+
+
+    #[test]
+    fn request_options_deadline() {
+        let options = RequestOptions::new().timeout(Duration::from_secs(60));
+        assert!(!options.deadline_passed());
+
+        // The timeout is shortened to the time left.
+        let options = options.deadline(Instant::now() + Duration::from_secs(5));
+        assert!(!options.deadline_passed());
+        let mut request = Request::new(hyper::method::Method::Get, "http://localhost/a");
+        options.apply(&mut request);
+        assert!(request.timeout.unwrap() <= Duration::from_secs(5));
+
+        let options = RequestOptions::new().deadline(Instant::now());
+        assert!(options.deadline_passed());
+
+        let options = RequestOptions::new().deadline(Instant::now() + Duration::from_secs(60));
+        assert!(!options.deadline_within(Duration::from_secs(1)));
+        assert!(options.deadline_within(Duration::from_secs(120)));
+        assert!(options.deadline_within(Duration::from_secs(u64::max_value())));
+        assert!(!RequestOptions::new().deadline_within(Duration::from_secs(120)));
+    }
+
+
+    #[test]
+    fn hyper_error_kind() {
+        let transport = HyperTransport::new(hyper::Client::new);
+
+        match transport.get("not a url") {
+            Err(Error(ErrorKind::Hyper(hyper::Error::Uri(_)), _)) => {}
+            other => panic!("expected a hyper URI error, got {:?}", other),
+        }
+    }
+
+
+    #[test]
+    fn hyper_concurrent_requests() {
+        // A server answering each connection after `delay`.
+        let delay = Duration::from_millis(300);
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        thread::spawn(move || for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+            thread::spawn(move || {
+                let mut request = [0; 4096];
+                let _ = stream.read(&mut request);
+                thread::sleep(delay);
+                let _ = stream.write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\nok");
+            });
+        });
+
+        let transport = Arc::new(HyperTransport::new(hyper::Client::new));
+        let start = Instant::now();
+        let threads: Vec<_> = (0..3)
+            .map(|_| {
+                let transport = transport.clone();
+                let url = url.clone();
+                thread::spawn(move || transport.get(&url).unwrap().body)
+            })
+            .collect();
+        for thread in threads {
+            assert_eq!(thread.join().unwrap(), "ok");
+        }
+
+        // The requests did not wait for each other.
+        assert!(start.elapsed() < delay * 3);
+    }
+
+
+    /// Decode `body` and read it whole.
+    fn decode(headers: &hyper::header::Headers, body: Vec<u8>) -> Result<String> {
+        let response = StreamingResponse {
+            status: hyper::status::StatusCode::Ok,
+            headers: headers.clone(),
+            body: decode_body(headers, Cursor::new(body))?,
+        };
+
+        response.into_response().map(|response| response.body)
+    }
+
+
+    fn encoded_headers(encoding: &str) -> hyper::header::Headers {
+        let mut headers = hyper::header::Headers::new();
+        headers.set_raw("Content-Encoding", vec![encoding.as_bytes().to_vec()]);
+        headers
+    }
+
+
+    #[test]
+    fn decode_plain_body() {
+        let body = decode(&hyper::header::Headers::new(), b"[]".to_vec()).unwrap();
+        assert_eq!(body, "[]");
+
+        let body = decode(&encoded_headers("identity"), b"[]".to_vec()).unwrap();
+        assert_eq!(body, "[]");
+
+        assert!(decode(&encoded_headers("br"), b"[]".to_vec()).is_err());
+        assert!(decode(&hyper::header::Headers::new(), vec![0xff, 0xfe]).is_err());
+    }
+
+
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn decode_gzip_body() {
+        use std::io::Write;
+        use flate2;
+
+        let json = r#"[{"id": 1, "name": "project"}]"#;
+
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::Default);
+        encoder.write_all(json.as_bytes()).unwrap();
+        let gzipped = encoder.finish().unwrap();
+        assert_eq!(decode(&encoded_headers("gzip"), gzipped).unwrap(), json);
+
+        let mut encoder = flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::Default);
+        encoder.write_all(json.as_bytes()).unwrap();
+        let deflated = encoder.finish().unwrap();
+        assert_eq!(decode(&encoded_headers("deflate"), deflated).unwrap(), json);
+    }
+}
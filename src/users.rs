@@ -0,0 +1,145 @@
+//! Users
+//!
+//! https://docs.gitlab.com/ce/api/users.html
+//!
+//! # Create an impersonation token
+//!
+//! Creates a new impersonation token for a user. Available only for admins.
+//!
+//! ```text
+//! POST /users/USER_ID/impersonation_tokens
+//! ```
+//!
+//! Parameters:
+//!
+//! | Attribute | Type | Required | Description |
+//! | --------- | ---- | -------- | ----------- |
+//! | `user_id` | integer | yes | The ID of the user |
+//! | `name` | string | yes | The name of the impersonation token |
+//! | `expires_at` | date | no | The expiration date of the impersonation token in ISO format (`YYYY-MM-DD`) |
+//! | `scopes` | array | yes | The array of scopes of the impersonation token (`api`, `read_user`) |
+//!
+//! The token is only returned once, on creation. Being a secret, it is never logged and is
+//! redacted from `ImpersonationToken`'s `Debug` output.
+
+
+use std::fmt;
+
+use BuildQuery;
+
+
+/// A created impersonation token.
+#[derive(Serialize, Deserialize)]
+pub struct ImpersonationToken {
+    pub id: i64,
+    pub name: String,
+    pub scopes: Vec<String>,
+    pub active: bool,
+    pub revoked: bool,
+    pub impersonation: bool,
+    /// `YYYY-MM-DD`
+    pub expires_at: Option<String>,
+    #[serde(with = "::datetime")]
+    pub created_at: ::datetime::DateTime,
+    /// The secret token, to be used as a private token
+    pub token: String,
+}
+
+
+impl fmt::Debug for ImpersonationToken {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ImpersonationToken")
+            .field("id", &self.id)
+            .field("name", &self.name)
+            .field("scopes", &self.scopes)
+            .field("active", &self.active)
+            .field("revoked", &self.revoked)
+            .field("impersonation", &self.impersonation)
+            .field("expires_at", &self.expires_at)
+            .field("created_at", &self.created_at)
+            .field("token", &"<redacted>")
+            .finish()
+    }
+}
+
+
+/// The settings of an impersonation token to create for the user `user_id`.
+#[derive(Debug, Clone, Serialize)]
+pub struct ImpersonationTokenCreator {
+    #[serde(skip_serializing)]
+    user_id: i64,
+    name: String,
+    scopes: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    expires_at: Option<String>,
+}
+
+
+impl ImpersonationTokenCreator {
+    pub fn new(user_id: i64,
+               name: String,
+               scopes: Vec<String>,
+               expires_at: Option<String>)
+               -> ImpersonationTokenCreator {
+        ImpersonationTokenCreator {
+            user_id: user_id,
+            name: name,
+            scopes: scopes,
+            expires_at: expires_at,
+        }
+    }
+}
+
+
+impl BuildQuery for ImpersonationTokenCreator {
+    fn build_query(&self) -> String {
+        format!("users/{}/impersonation_tokens", self.user_id)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use serde_json;
+
+    use BuildQuery;
+    use users::{ImpersonationToken, ImpersonationTokenCreator};
+
+
+    #[test]
+    fn build_query() {
+        let creator = ImpersonationTokenCreator::new(42,
+                                                     String::from("deploy"),
+                                                     vec![String::from("api")],
+                                                     None);
+        assert_eq!(creator.build_query(), "users/42/impersonation_tokens");
+        assert_eq!(serde_json::to_string(&creator).unwrap(),
+                   r#"{"name":"deploy","scopes":["api"]}"#);
+
+        let creator = ImpersonationTokenCreator::new(42,
+                                                     String::from("deploy"),
+                                                     vec![String::from("api"),
+                                                          String::from("read_user")],
+                                                     Some(String::from("2017-12-31")));
+        assert_eq!(serde_json::to_string(&creator).unwrap(),
+                   r#"{"name":"deploy","scopes":["api","read_user"],"expires_at":"2017-12-31"}"#);
+    }
+
+
+    #[test]
+    fn debug_redacts_token() {
+        let json_reply = r#"{"id": 2, "name": "deploy", "scopes": ["api"], "active": true,
+                             "revoked": false, "impersonation": true, "expires_at": null,
+                             "created_at": "2017-04-29T08:46:00.000Z",
+                             "token": "EsMo-vhKfXGwX9RKrwiy"}"#;
+
+        let token: ImpersonationToken = serde_json::from_str(json_reply)
+            .expect("JSON deserialization failed");
+        assert_eq!(token.token, "EsMo-vhKfXGwX9RKrwiy");
+
+        let debug = format!("{:?}", token);
+        assert!(!debug.contains("EsMo-vhKfXGwX9RKrwiy"));
+        assert!(debug.contains("<redacted>"));
+        assert!(debug.contains("deploy"));
+    }
+}
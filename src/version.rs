@@ -0,0 +1,109 @@
+//! Version
+//!
+//! https://docs.gitlab.com/ce/api/version.html
+//!
+//! # Version
+//!
+//! Retrieve version information for this GitLab instance. Responds `200 OK` for authenticated
+//! users.
+//!
+//! ```text
+//! GET /version
+//! ```
+//!
+//! # Feature detection
+//!
+//! Some features of the API depend on the GitLab version. The functions of this module allow
+//! checking if a feature is supported by a specific (semantic) version.
+//!
+
+
+use semver;
+
+use ::errors::*;
+
+
+/// Minimum GitLab version (major, minor) providing the API v4.
+const API_V4_MIN_VERSION: (u64, u64) = (9, 0);
+
+/// Minimum GitLab version (major, minor) providing keyset pagination.
+const KEYSET_PAGINATION_MIN_VERSION: (u64, u64) = (13, 0);
+
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Version {
+    pub version: String,
+    pub revision: String,
+}
+
+
+impl Version {
+    /// Parse the `version` string (for example `8.13.0-pre`) as a semantic version.
+    pub fn semver(&self) -> Result<semver::Version> {
+        semver::Version::parse(&self.version)
+            .chain_err(|| format!("cannot parse GitLab version '{}'", self.version))
+    }
+}
+
+
+fn at_least(version: &semver::Version, min_version: (u64, u64)) -> bool {
+    (version.major, version.minor) >= min_version
+}
+
+
+/// Does the GitLab `version` provide the API v4?
+pub fn supports_api_v4(version: &semver::Version) -> bool {
+    at_least(version, API_V4_MIN_VERSION)
+}
+
+
+/// Does the GitLab `version` provide keyset pagination?
+pub fn supports_keyset_pagination(version: &semver::Version) -> bool {
+    at_least(version, KEYSET_PAGINATION_MIN_VERSION)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use serde_json;
+
+    use version::*;
+
+
+    fn parse(json_reply: &str) -> ::semver::Version {
+        let version: Version = serde_json::from_str(json_reply)
+            .expect("JSON deserialization failed");
+        version.semver().unwrap()
+    }
+
+
+    #[test]
+    fn semver() {
+        let version = parse(r#"{"version":"8.13.0-pre","revision":"4e963fe"}"#);
+        assert_eq!(version.major, 8);
+        assert_eq!(version.minor, 13);
+        assert_eq!(version.patch, 0);
+
+        let version = Version {
+            version: String::from("not a version"),
+            revision: String::from("4e963fe"),
+        };
+        assert!(version.semver().is_err());
+    }
+
+
+    #[test]
+    fn features() {
+        let version = parse(r#"{"version":"8.13.0-pre","revision":"4e963fe"}"#);
+        assert!(!supports_api_v4(&version));
+        assert!(!supports_keyset_pagination(&version));
+
+        let version = parse(r#"{"version":"9.5.4-ee","revision":"ab15c1b"}"#);
+        assert!(supports_api_v4(&version));
+        assert!(!supports_keyset_pagination(&version));
+
+        let version = parse(r#"{"version":"13.2.0","revision":"7c48e8f"}"#);
+        assert!(supports_api_v4(&version));
+        assert!(supports_keyset_pagination(&version));
+    }
+}